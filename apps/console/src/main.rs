@@ -1,24 +1,92 @@
-use maze_core::{Checkpoint, Maze, MazeConfig, Position};
-use maze_generator::{create_generator, MazeGeneratorType};
-use maze_runner::MazeRunner;
+use std::{env, process};
+
+use maze_core::{Checkpoint, Maze, MazeConfig, Position, ScoringRuleType};
+use maze_generator::{create_generator_by_id, list_generators};
+use maze_runner::{DistanceField, MazeRunner};
 
 fn main() {
-    generator();
+    // the first argument picks the generator by its registry id
+    let generator_id = env::args().nth(1).unwrap_or_else(|| "vanilla".to_string());
+
+    generator(&generator_id);
     runner();
 }
 
-fn generator() {
-    let generator = create_generator(MazeGeneratorType::Vanilla);
+fn generator(id: &str) {
+    let generator = match create_generator_by_id(id, None) {
+        Ok(generator) => generator,
+        Err(error) => {
+            eprintln!("{}, available generators:", error);
+            for info in list_generators() {
+                eprintln!("{:>12}  {}", info.id, info.description);
+            }
+            process::exit(1);
+        }
+    };
 
-    let config = generator.generate().unwrap();
+    let generated = generator.generate().unwrap();
+    println!("seed: {}", generated.seed);
 
-    let maze = Maze::new(&config).unwrap();
+    let maze = Maze::new(&generated.config).unwrap();
 
     let runner = MazeRunner::new(&maze);
 
     let result = runner.run(&vec![]).unwrap().unwrap();
 
-    println!("{:?}", result.solved_path());
+    println!("{:?}", result.solved_path().unwrap());
+
+    let floors = result.start_distance_field();
+    for (floor, field) in floors.iter().enumerate() {
+        if floors.len() > 1 {
+            println!("floor {}:", floor);
+        }
+        print_distance_field(field);
+    }
+}
+
+fn print_distance_field(field: &DistanceField) {
+    let row_count = field.first().map_or(0, |column| column.len());
+
+    for y in 0..row_count {
+        let line: Vec<String> = field
+            .iter()
+            .map(|column| match column[y] {
+                Some(distance) => format!("{:>4}", distance),
+                None => format!("{:>4}", "."),
+            })
+            .collect();
+        println!("{}", line.join(""));
+    }
+}
+
+#[allow(dead_code)]
+fn tracer() {
+    let maze = Maze::new(&MazeConfig {
+        col_count: 4,
+        row_count: 3,
+        floor_count: 1,
+        max_soft_wall_count: 2,
+        walls: vec![Position::new(1, 1)],
+        entrypoints: vec![Position::new(0, 0)],
+        checkpoints: vec![
+            Checkpoint {
+                position: Position::new(3, 2),
+                level: 1,
+            },
+            Checkpoint {
+                position: Position::new(0, 2),
+                level: 2,
+            },
+        ],
+        stairs: vec![],
+        scoring_rule: ScoringRuleType::Min,
+    })
+    .unwrap();
+
+    let runner = MazeRunner::new(&maze);
+    for event in runner.trace(&vec![Position::new(2, 1)]).unwrap() {
+        println!("{:?}", event);
+    }
 }
 
 #[allow(dead_code)]
@@ -26,31 +94,34 @@ fn runner() {
     let maze = Maze::new(&MazeConfig {
         col_count: 7,
         row_count: 2,
+        floor_count: 1,
         max_soft_wall_count: 200,
         walls: vec![],
-        entrypoints: vec![Position { x: 0, y: 0 }],
+        entrypoints: vec![Position::new(0, 0)],
         checkpoints: vec![
             Checkpoint {
-                position: Position { x: 0, y: 1 },
+                position: Position::new(0, 1),
                 level: 1,
             },
             Checkpoint {
-                position: Position { x: 2, y: 0 },
+                position: Position::new(2, 0),
                 level: 2,
             },
             Checkpoint {
-                position: Position { x: 5, y: 0 },
+                position: Position::new(5, 0),
                 level: 3,
             },
             Checkpoint {
-                position: Position { x: 4, y: 0 },
+                position: Position::new(4, 0),
                 level: 3,
             },
             Checkpoint {
-                position: Position { x: 6, y: 0 },
+                position: Position::new(6, 0),
                 level: 4,
             },
         ],
+        stairs: vec![],
+        scoring_rule: ScoringRuleType::Min,
     })
     .unwrap();
 
@@ -62,27 +133,30 @@ fn runner() {
     let maze = Maze::new(&MazeConfig {
         col_count: 23,
         row_count: 26,
+        floor_count: 1,
         max_soft_wall_count: 200,
         walls: vec![],
-        entrypoints: vec![Position { x: 0, y: 0 }],
+        entrypoints: vec![Position::new(0, 0)],
         checkpoints: vec![
             Checkpoint {
-                position: Position { x: 1, y: 1 },
+                position: Position::new(1, 1),
                 level: 19,
             },
             Checkpoint {
-                position: Position { x: 2, y: 1 },
+                position: Position::new(2, 1),
                 level: 20,
             },
             Checkpoint {
-                position: Position { x: 10, y: 20 },
+                position: Position::new(10, 20),
                 level: 23,
             },
             Checkpoint {
-                position: Position { x: 20, y: 1 },
+                position: Position::new(20, 1),
                 level: 21,
             },
         ],
+        stairs: vec![],
+        scoring_rule: ScoringRuleType::Min,
     })
     .unwrap();
 
@@ -93,10 +167,10 @@ fn runner() {
     let runner = MazeRunner::new(&maze);
     let result = runner
         .run(&vec![
-            Position { x: 19, y: 1 },
-            Position { x: 21, y: 1 },
-            Position { x: 19, y: 0 },
-            Position { x: 20, y: 2 },
+            Position::new(19, 1),
+            Position::new(21, 1),
+            Position::new(19, 0),
+            Position::new(20, 2),
         ])
         .unwrap()
         .unwrap();
@@ -105,27 +179,30 @@ fn runner() {
     let maze = Maze::new(&MazeConfig {
         col_count: 23,
         row_count: 26,
+        floor_count: 1,
         max_soft_wall_count: 200,
         walls: vec![],
-        entrypoints: vec![Position { x: 0, y: 0 }],
+        entrypoints: vec![Position::new(0, 0)],
         checkpoints: vec![
             Checkpoint {
-                position: Position { x: 1, y: 1 },
+                position: Position::new(1, 1),
                 level: 19,
             },
             Checkpoint {
-                position: Position { x: 2, y: 1 },
+                position: Position::new(2, 1),
                 level: 20,
             },
             Checkpoint {
-                position: Position { x: 10, y: 20 },
+                position: Position::new(10, 20),
                 level: 20,
             },
             Checkpoint {
-                position: Position { x: 20, y: 1 },
+                position: Position::new(20, 1),
                 level: 21,
             },
         ],
+        stairs: vec![],
+        scoring_rule: ScoringRuleType::Min,
     })
     .unwrap();
 
@@ -136,10 +213,10 @@ fn runner() {
     let runner = MazeRunner::new(&maze);
     let result = runner
         .run(&vec![
-            Position { x: 19, y: 1 },
-            Position { x: 21, y: 1 },
-            Position { x: 19, y: 0 },
-            Position { x: 20, y: 2 },
+            Position::new(19, 1),
+            Position::new(21, 1),
+            Position::new(19, 0),
+            Position::new(20, 2),
         ])
         .unwrap()
         .unwrap();
@@ -148,107 +225,110 @@ fn runner() {
     let maze = Maze::new(&MazeConfig {
         col_count: 210,
         row_count: 26,
+        floor_count: 1,
         max_soft_wall_count: 200,
         walls: vec![],
-        entrypoints: vec![Position { x: 0, y: 0 }],
+        entrypoints: vec![Position::new(0, 0)],
         checkpoints: vec![
             Checkpoint {
-                position: Position { x: 4, y: 5 },
+                position: Position::new(4, 5),
                 level: 1,
             },
             Checkpoint {
-                position: Position { x: 150, y: 20 },
+                position: Position::new(150, 20),
                 level: 2,
             },
             Checkpoint {
-                position: Position { x: 1, y: 1 },
+                position: Position::new(1, 1),
                 level: 3,
             },
             Checkpoint {
-                position: Position { x: 160, y: 20 },
+                position: Position::new(160, 20),
                 level: 4,
             },
             Checkpoint {
-                position: Position { x: 1, y: 2 },
+                position: Position::new(1, 2),
                 level: 5,
             },
             Checkpoint {
-                position: Position { x: 10, y: 25 },
+                position: Position::new(10, 25),
                 level: 6,
             },
             Checkpoint {
-                position: Position { x: 10, y: 21 },
+                position: Position::new(10, 21),
                 level: 6,
             },
             Checkpoint {
-                position: Position { x: 3, y: 3 },
+                position: Position::new(3, 3),
                 level: 7,
             },
             Checkpoint {
-                position: Position { x: 120, y: 25 },
+                position: Position::new(120, 25),
                 level: 8,
             },
             Checkpoint {
-                position: Position { x: 4, y: 4 },
+                position: Position::new(4, 4),
                 level: 9,
             },
             Checkpoint {
-                position: Position { x: 130, y: 25 },
+                position: Position::new(130, 25),
                 level: 10,
             },
             Checkpoint {
-                position: Position { x: 0, y: 1 },
+                position: Position::new(0, 1),
                 level: 10,
             },
             Checkpoint {
-                position: Position { x: 200, y: 5 },
+                position: Position::new(200, 5),
                 level: 11,
             },
             Checkpoint {
-                position: Position { x: 1, y: 21 },
+                position: Position::new(1, 21),
                 level: 12,
             },
             Checkpoint {
-                position: Position { x: 6, y: 6 },
+                position: Position::new(6, 6),
                 level: 13,
             },
             Checkpoint {
-                position: Position { x: 120, y: 24 },
+                position: Position::new(120, 24),
                 level: 14,
             },
             Checkpoint {
-                position: Position { x: 7, y: 7 },
+                position: Position::new(7, 7),
                 level: 15,
             },
             Checkpoint {
-                position: Position { x: 8, y: 19 },
+                position: Position::new(8, 19),
                 level: 16,
             },
             Checkpoint {
-                position: Position { x: 8, y: 8 },
+                position: Position::new(8, 8),
                 level: 17,
             },
             Checkpoint {
-                position: Position { x: 150, y: 19 },
+                position: Position::new(150, 19),
                 level: 18,
             },
             Checkpoint {
-                position: Position { x: 200, y: 1 },
+                position: Position::new(200, 1),
                 level: 19,
             },
             Checkpoint {
-                position: Position { x: 202, y: 1 },
+                position: Position::new(202, 1),
                 level: 20,
             },
             Checkpoint {
-                position: Position { x: 1, y: 20 },
+                position: Position::new(1, 20),
                 level: 20,
             },
             Checkpoint {
-                position: Position { x: 206, y: 1 },
+                position: Position::new(206, 1),
                 level: 21,
             },
         ],
+        stairs: vec![],
+        scoring_rule: ScoringRuleType::Min,
     })
     .unwrap();
 
@@ -259,123 +339,126 @@ fn runner() {
     let runner = MazeRunner::new(&maze);
     let result = runner
         .run(&vec![
-            Position { x: 205, y: 1 },
-            Position { x: 207, y: 1 },
-            Position { x: 206, y: 0 },
-            Position { x: 205, y: 2 },
+            Position::new(205, 1),
+            Position::new(207, 1),
+            Position::new(206, 0),
+            Position::new(205, 2),
         ])
         .unwrap()
         .unwrap();
     println!("{:?}", result.score());
 
     let runner = MazeRunner::new(&maze);
-    let result = runner.run(&vec![Position { x: 7, y: 6 }]).unwrap().unwrap();
+    let result = runner.run(&vec![Position::new(7, 6)]).unwrap().unwrap();
     println!("{:?}", result.score());
 
     let maze = Maze::new(&MazeConfig {
         col_count: 210,
         row_count: 26,
+        floor_count: 1,
         max_soft_wall_count: 200,
         walls: vec![],
-        entrypoints: vec![Position { x: 0, y: 0 }, Position { x: 3, y: 4 }],
+        entrypoints: vec![Position::new(0, 0), Position::new(3, 4)],
         checkpoints: vec![
             Checkpoint {
-                position: Position { x: 4, y: 5 },
+                position: Position::new(4, 5),
                 level: 1,
             },
             Checkpoint {
-                position: Position { x: 150, y: 20 },
+                position: Position::new(150, 20),
                 level: 2,
             },
             Checkpoint {
-                position: Position { x: 1, y: 1 },
+                position: Position::new(1, 1),
                 level: 3,
             },
             Checkpoint {
-                position: Position { x: 160, y: 20 },
+                position: Position::new(160, 20),
                 level: 4,
             },
             Checkpoint {
-                position: Position { x: 1, y: 2 },
+                position: Position::new(1, 2),
                 level: 5,
             },
             Checkpoint {
-                position: Position { x: 10, y: 25 },
+                position: Position::new(10, 25),
                 level: 6,
             },
             Checkpoint {
-                position: Position { x: 10, y: 21 },
+                position: Position::new(10, 21),
                 level: 6,
             },
             Checkpoint {
-                position: Position { x: 3, y: 3 },
+                position: Position::new(3, 3),
                 level: 7,
             },
             Checkpoint {
-                position: Position { x: 120, y: 25 },
+                position: Position::new(120, 25),
                 level: 8,
             },
             Checkpoint {
-                position: Position { x: 4, y: 4 },
+                position: Position::new(4, 4),
                 level: 9,
             },
             Checkpoint {
-                position: Position { x: 130, y: 25 },
+                position: Position::new(130, 25),
                 level: 10,
             },
             Checkpoint {
-                position: Position { x: 0, y: 1 },
+                position: Position::new(0, 1),
                 level: 10,
             },
             Checkpoint {
-                position: Position { x: 200, y: 5 },
+                position: Position::new(200, 5),
                 level: 11,
             },
             Checkpoint {
-                position: Position { x: 1, y: 21 },
+                position: Position::new(1, 21),
                 level: 12,
             },
             Checkpoint {
-                position: Position { x: 6, y: 6 },
+                position: Position::new(6, 6),
                 level: 13,
             },
             Checkpoint {
-                position: Position { x: 120, y: 24 },
+                position: Position::new(120, 24),
                 level: 14,
             },
             Checkpoint {
-                position: Position { x: 7, y: 7 },
+                position: Position::new(7, 7),
                 level: 15,
             },
             Checkpoint {
-                position: Position { x: 8, y: 19 },
+                position: Position::new(8, 19),
                 level: 16,
             },
             Checkpoint {
-                position: Position { x: 8, y: 8 },
+                position: Position::new(8, 8),
                 level: 17,
             },
             Checkpoint {
-                position: Position { x: 150, y: 19 },
+                position: Position::new(150, 19),
                 level: 18,
             },
             Checkpoint {
-                position: Position { x: 200, y: 1 },
+                position: Position::new(200, 1),
                 level: 19,
             },
             Checkpoint {
-                position: Position { x: 202, y: 1 },
+                position: Position::new(202, 1),
                 level: 20,
             },
             Checkpoint {
-                position: Position { x: 1, y: 20 },
+                position: Position::new(1, 20),
                 level: 20,
             },
             Checkpoint {
-                position: Position { x: 206, y: 1 },
+                position: Position::new(206, 1),
                 level: 21,
             },
         ],
+        stairs: vec![],
+        scoring_rule: ScoringRuleType::Min,
     })
     .unwrap();
 
@@ -386,32 +469,32 @@ fn runner() {
     let runner = MazeRunner::new(&maze);
     let result = runner
         .run(&vec![
-            Position { x: 205, y: 1 },
-            Position { x: 207, y: 1 },
-            Position { x: 206, y: 0 },
-            Position { x: 205, y: 2 },
+            Position::new(205, 1),
+            Position::new(207, 1),
+            Position::new(206, 0),
+            Position::new(205, 2),
         ])
         .unwrap()
         .unwrap();
     println!("{:?}", result.score());
 
     let runner = MazeRunner::new(&maze);
-    let result = runner.run(&vec![Position { x: 7, y: 6 }]).unwrap().unwrap();
+    let result = runner.run(&vec![Position::new(7, 6)]).unwrap().unwrap();
     println!("{:?}", result.score());
 
     let runner = MazeRunner::new(&maze);
     let result = runner
         .run(&vec![
-            Position { x: 2, y: 0 },
-            Position { x: 2, y: 1 },
-            Position { x: 2, y: 3 },
-            Position { x: 2, y: 4 },
-            Position { x: 2, y: 5 },
-            Position { x: 2, y: 6 },
-            Position { x: 4, y: 7 },
-            Position { x: 4, y: 6 },
-            Position { x: 4, y: 3 },
-            Position { x: 4, y: 2 },
+            Position::new(2, 0),
+            Position::new(2, 1),
+            Position::new(2, 3),
+            Position::new(2, 4),
+            Position::new(2, 5),
+            Position::new(2, 6),
+            Position::new(4, 7),
+            Position::new(4, 6),
+            Position::new(4, 3),
+            Position::new(4, 2),
         ])
         .unwrap()
         .unwrap();
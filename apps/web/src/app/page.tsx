@@ -2,8 +2,6 @@
 
 import { useCallback, useState } from 'react';
 
-import { MazerGeneratorType } from 'mazer';
-
 import { MazeBoard } from '~/components/maze';
 import { useMaze, useMazer } from '~/hooks/maze';
 import { useMazerGenerator } from '~/hooks/maze/use-mazer-generator';
@@ -59,7 +57,7 @@ export default function Home() {
         <pre>{mazeLimits.softWallsRemaining}</pre>
         <button
           onClick={() => {
-            const config = generateConfig(MazerGeneratorType.Waterfall);
+            const config = generateConfig('waterfall');
             setMazeConfig(config);
           }}
         >
@@ -67,7 +65,7 @@ export default function Home() {
         </button>
         <button
           onClick={() => {
-            const config = generateConfig(MazerGeneratorType.Vanilla);
+            const config = generateConfig('vanilla');
             setMazeConfig(config);
           }}
         >
@@ -1,6 +1,6 @@
 import { useCallback } from 'react';
 
-import { Mazer, MazerGeneratorType } from 'mazer';
+import { Mazer } from 'mazer';
 
 import { MazeConfig } from '~/types/maze';
 
@@ -10,10 +10,10 @@ export const useMazerGenerator = () => {
   const { isMazerReady } = useMazerInitialization();
 
   const generateConfig = useCallback(
-    (type: MazerGeneratorType): MazeConfig => {
+    (generatorId: string): MazeConfig => {
       if (!isMazerReady) throw new Error('Mazer is not initialized!');
 
-      const mazerConfig = Mazer.generateConfig(type);
+      const mazerConfig = Mazer.generateConfig(generatorId);
 
       return {
         colCount: mazerConfig.colCount,
@@ -2,9 +2,11 @@ mod constants;
 mod maze;
 mod maze_config;
 mod maze_error;
+mod scoring_rule;
 mod tile;
 
 pub use maze::Maze;
 pub use maze_config::MazeConfig;
 pub use maze_error::*;
+pub use scoring_rule::ScoringRuleType;
 pub use tile::*;
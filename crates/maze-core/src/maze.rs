@@ -1,29 +1,44 @@
 use super::{
     maze_config::MazeConfig,
     maze_error::MazeError,
-    tile::{Position, TileBoard},
+    scoring_rule::ScoringRuleType,
+    tile::{Position, TileBoard, TileFloors},
 };
 
 pub struct Maze {
-    board: TileBoard,
+    floors: TileFloors,
     entrypoints: Vec<Position>,
     max_soft_wall_count: u32,
+    scoring_rule: ScoringRuleType,
 }
 
 impl Maze {
     pub fn new(config: &MazeConfig) -> Result<Self, MazeError> {
-        let board = config.validate_and_convert_to_board()?;
+        let floors = config.validate_and_convert_to_floors()?;
         let max_soft_wall_count = config.max_soft_wall_count;
 
         Ok(Self {
-            board,
+            floors,
             entrypoints: config.entrypoints.clone(),
             max_soft_wall_count,
+            scoring_rule: config.scoring_rule,
         })
     }
 
-    pub fn board(&self) -> &TileBoard {
-        &self.board
+    /// Tiles of single floor mazes, `None` for mazes of several floors whose tiles are in `floors`
+    pub fn board(&self) -> Option<&TileBoard> {
+        match self.floors.as_slice() {
+            [board] => Some(board),
+            _ => None,
+        }
+    }
+
+    pub fn floors(&self) -> &TileFloors {
+        &self.floors
+    }
+
+    pub fn floor_count(&self) -> usize {
+        self.floors.len()
     }
 
     pub fn entrypoints(&self) -> &Vec<Position> {
@@ -33,11 +48,15 @@ impl Maze {
     pub fn max_soft_wall_count(&self) -> u32 {
         self.max_soft_wall_count
     }
+
+    pub fn scoring_rule(&self) -> ScoringRuleType {
+        self.scoring_rule
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::tile::{Checkpoint, TileKind};
+    use crate::tile::{Checkpoint, Stairs, TileKind};
 
     // Note this useful idiom: importing names from outer (for mod tests) scope.
     use super::*;
@@ -47,40 +66,73 @@ mod tests {
         let maze = Maze::new(&MazeConfig {
             col_count: 2,
             row_count: 2,
+            floor_count: 1,
             max_soft_wall_count: 7,
-            walls: vec![Position { x: 0, y: 10 }],
-            entrypoints: vec![Position { x: 1, y: 0 }],
+            walls: vec![Position::new(0, 10)],
+            entrypoints: vec![Position::new(1, 0)],
             checkpoints: vec![Checkpoint {
-                position: Position { x: 1, y: 1 },
+                position: Position::new(1, 1),
                 level: 1,
             }],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
         });
 
         assert!(maze.is_err());
     }
 
+    #[test]
+    fn test_create_too_large() {
+        let maze = Maze::new(&MazeConfig {
+            col_count: usize::MAX,
+            row_count: 2,
+            floor_count: 1,
+            max_soft_wall_count: 7,
+            walls: vec![],
+            entrypoints: vec![Position::new(1, 0)],
+            checkpoints: vec![Checkpoint {
+                position: Position::new(1, 1),
+                level: 1,
+            }],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
+        });
+
+        assert_eq!(
+            maze.err(),
+            Some(MazeError::MazeTooLarge {
+                col_count: usize::MAX,
+                row_count: 2,
+                floor_count: 1
+            })
+        );
+    }
+
     #[test]
     fn test_create_basic() {
         let maze = Maze::new(&MazeConfig {
             col_count: 2,
             row_count: 2,
+            floor_count: 1,
             max_soft_wall_count: 7,
-            walls: vec![Position { x: 0, y: 1 }],
-            entrypoints: vec![Position { x: 1, y: 0 }],
+            walls: vec![Position::new(0, 1)],
+            entrypoints: vec![Position::new(1, 0)],
             checkpoints: vec![Checkpoint {
-                position: Position { x: 1, y: 1 },
+                position: Position::new(1, 1),
                 level: 1,
             }],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
         });
 
         assert_eq!(maze.as_ref().map(|maze| maze.max_soft_wall_count()), Ok(7));
 
         assert_eq!(
             maze.as_ref().map(|maze| maze.board()),
-            Ok(&vec![
+            Ok(Some(&vec![
                 vec![TileKind::Empty, TileKind::Wall],
                 vec![TileKind::Entrypoint, TileKind::Checkpoint { level: 1 }]
-            ])
+            ]))
         )
     }
 
@@ -89,26 +141,29 @@ mod tests {
         let maze = Maze::new(&MazeConfig {
             col_count: 3,
             row_count: 3,
+            floor_count: 1,
             max_soft_wall_count: 5,
-            walls: vec![Position { x: 0, y: 1 }],
-            entrypoints: vec![Position { x: 1, y: 0 }],
+            walls: vec![Position::new(0, 1)],
+            entrypoints: vec![Position::new(1, 0)],
             checkpoints: vec![
                 Checkpoint {
-                    position: Position { x: 1, y: 1 },
+                    position: Position::new(1, 1),
                     level: 1,
                 },
                 Checkpoint {
-                    position: Position { x: 2, y: 2 },
+                    position: Position::new(2, 2),
                     level: 2,
                 },
             ],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
         });
 
         assert_eq!(maze.as_ref().map(|maze| maze.max_soft_wall_count()), Ok(5));
 
         assert_eq!(
             maze.as_ref().map(|maze| maze.board()),
-            Ok(&vec![
+            Ok(Some(&vec![
                 vec![TileKind::Empty, TileKind::Wall, TileKind::Empty],
                 vec![
                     TileKind::Entrypoint,
@@ -120,7 +175,41 @@ mod tests {
                     TileKind::Empty,
                     TileKind::Checkpoint { level: 2 }
                 ]
-            ])
+            ]))
         )
     }
+
+    #[test]
+    fn test_create_with_multiple_floors() -> Result<(), MazeError> {
+        let maze = Maze::new(&MazeConfig {
+            col_count: 2,
+            row_count: 2,
+            floor_count: 2,
+            max_soft_wall_count: 5,
+            walls: vec![Position::on_floor(0, 1, 1)],
+            entrypoints: vec![Position::new(0, 0)],
+            checkpoints: vec![Checkpoint {
+                position: Position::on_floor(1, 0, 1),
+                level: 1,
+            }],
+            stairs: vec![Stairs {
+                position: Position::new(1, 1),
+                to_floor: 1,
+            }],
+            scoring_rule: ScoringRuleType::Min,
+        })?;
+
+        // tiles of other floors are never left out silently
+        assert_eq!(maze.board(), None);
+        assert_eq!(maze.floor_count(), 2);
+        assert_eq!(
+            maze.floors()[1],
+            vec![
+                vec![TileKind::Empty, TileKind::Wall],
+                vec![TileKind::Checkpoint { level: 1 }, TileKind::Empty]
+            ]
+        );
+
+        Ok(())
+    }
 }
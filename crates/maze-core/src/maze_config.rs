@@ -2,93 +2,107 @@ use crate::constants::MIN_MAZE_SIZE;
 
 use super::{
     maze_error::MazeError,
-    tile::{Checkpoint, Position, TileBoard, TileKind},
+    scoring_rule::ScoringRuleType,
+    tile::{Checkpoint, Position, Stairs, TileFloors, TileKind},
 };
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct MazeConfig {
     pub col_count: usize,
     pub row_count: usize,
+    /// Number of stacked floors, all of them have the same number of columns and rows
+    pub floor_count: usize,
     pub max_soft_wall_count: u32,
     pub entrypoints: Vec<Position>,
     pub checkpoints: Vec<Checkpoint>,
     pub walls: Vec<Position>,
+    pub stairs: Vec<Stairs>,
+    /// Rule the runner combines scores of separate entrypoints with
+    pub scoring_rule: ScoringRuleType,
 }
 
 impl MazeConfig {
-    pub(crate) fn validate_and_convert_to_board(&self) -> Result<TileBoard, MazeError> {
-        let maze_size = self.col_count * self.row_count;
-        if maze_size < 4 {
+    pub(crate) fn validate_and_convert_to_floors(&self) -> Result<TileFloors, MazeError> {
+        let floor_size = self.col_count.checked_mul(self.row_count);
+        let maze_size = floor_size.and_then(|size| size.checked_mul(self.floor_count));
+        let (floor_size, maze_size) = match floor_size.zip(maze_size) {
+            Some(sizes) => sizes,
+            None => {
+                return Err(MazeError::MazeTooLarge {
+                    col_count: self.col_count,
+                    row_count: self.row_count,
+                    floor_count: self.floor_count,
+                })
+            }
+        };
+        if floor_size < 4 || self.floor_count == 0 {
             return Err(MazeError::InvalidMazeSize {
                 size: maze_size,
                 min: MIN_MAZE_SIZE,
             });
         }
 
-        if self.entrypoints.len() == 0 {
+        if self.entrypoints.is_empty() {
             return Err(MazeError::NoEntrypoint);
         }
 
-        if self.checkpoints.len() == 0 {
+        if self.checkpoints.is_empty() {
             return Err(MazeError::NoCheckpoint);
         }
 
-        let mut board: TileBoard = vec![vec![TileKind::Empty; self.row_count]; self.col_count];
-
-        for &Position { x, y } in self.entrypoints.iter() {
-            if x >= self.col_count || y >= self.row_count {
-                return Err(MazeError::TileOutOfBounds(
-                    Position { x, y },
-                    TileKind::Entrypoint,
-                ));
-            }
-
-            if board[x][y] != TileKind::Empty {
-                return Err(MazeError::OverlappingTiles {
-                    position: Position { x, y },
-                    kinds: (board[x][y], TileKind::Entrypoint),
-                });
-            }
+        let mut floors: TileFloors =
+            vec![vec![vec![TileKind::Empty; self.row_count]; self.col_count]; self.floor_count];
 
-            board[x][y] = TileKind::Entrypoint;
+        for &position in self.entrypoints.iter() {
+            self.place_tile(&mut floors, position, TileKind::Entrypoint)?;
         }
 
         for &Checkpoint { position, level } in self.checkpoints.iter() {
-            let Position { x, y } = position;
-            if x >= self.col_count || y >= self.row_count {
-                return Err(MazeError::TileOutOfBounds(
-                    Position { x, y },
-                    TileKind::Checkpoint { level },
-                ));
-            }
+            self.place_tile(&mut floors, position, TileKind::Checkpoint { level })?;
+        }
 
-            if board[x][y] != TileKind::Empty {
-                return Err(MazeError::OverlappingTiles {
-                    position: Position { x, y },
-                    kinds: (board[x][y], TileKind::Checkpoint { level }),
-                });
+        for &Stairs { position, to_floor } in self.stairs.iter() {
+            if to_floor >= self.floor_count || to_floor == position.floor_index() {
+                return Err(MazeError::InvalidStairs { position, to_floor });
             }
-            board[x][y] = TileKind::Checkpoint { level };
+            self.place_tile(&mut floors, position, TileKind::Stairs { to_floor })?;
         }
 
-        for &Position { x, y } in self.walls.iter() {
-            if x >= self.col_count || y >= self.row_count {
-                return Err(MazeError::TileOutOfBounds(
-                    Position { x, y },
-                    TileKind::Wall,
-                ));
-            }
+        for &position in self.walls.iter() {
+            self.place_tile(&mut floors, position, TileKind::Wall)?;
+        }
 
-            if board[x][y] != TileKind::Empty {
-                return Err(MazeError::OverlappingTiles {
-                    position: Position { x, y },
-                    kinds: (board[x][y], TileKind::Wall),
-                });
+        // stairs are placed before walls so the landing tile can be checked only now
+        for &Stairs { position, to_floor } in self.stairs.iter() {
+            if floors[to_floor][position.x][position.y] == TileKind::Wall {
+                return Err(MazeError::BlockedStairs { position, to_floor });
             }
-            board[x][y] = TileKind::Wall;
         }
 
-        Ok(board)
+        Ok(floors)
+    }
+
+    fn place_tile(
+        &self,
+        floors: &mut TileFloors,
+        position: Position,
+        kind: TileKind,
+    ) -> Result<(), MazeError> {
+        let Position { x, y, .. } = position;
+        let floor = position.floor_index();
+        if x >= self.col_count || y >= self.row_count || floor >= self.floor_count {
+            return Err(MazeError::TileOutOfBounds(position, kind));
+        }
+
+        if floors[floor][x][y] != TileKind::Empty {
+            return Err(MazeError::OverlappingTiles {
+                position,
+                kinds: (floors[floor][x][y], kind),
+            });
+        }
+        floors[floor][x][y] = kind;
+
+        Ok(())
     }
 }
 
@@ -100,17 +114,20 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_validate_and_convert_to_board_with_invalid_size() {
+    fn test_validate_and_convert_to_floors_with_invalid_size() {
         let config = MazeConfig {
             col_count: 1,
             row_count: 0,
+            floor_count: 1,
             max_soft_wall_count: 5,
             walls: vec![],
             entrypoints: vec![],
             checkpoints: vec![],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
         };
 
-        let board = config.validate_and_convert_to_board();
+        let board = config.validate_and_convert_to_floors();
 
         assert_eq!(
             board,
@@ -122,57 +139,66 @@ mod tests {
     }
 
     #[test]
-    fn test_validate_and_convert_to_board_without_any_entrypoint() {
+    fn test_validate_and_convert_to_floors_without_any_entrypoint() {
         let config = MazeConfig {
             col_count: 2,
             row_count: 2,
+            floor_count: 1,
             max_soft_wall_count: 5,
             walls: vec![],
             entrypoints: vec![],
             checkpoints: vec![],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
         };
 
-        let board = config.validate_and_convert_to_board();
+        let board = config.validate_and_convert_to_floors();
 
         assert_eq!(board, Err(MazeError::NoEntrypoint))
     }
 
     #[test]
-    fn test_validate_and_convert_to_board_without_any_checkpoint() {
+    fn test_validate_and_convert_to_floors_without_any_checkpoint() {
         let config = MazeConfig {
             col_count: 2,
             row_count: 2,
+            floor_count: 1,
             max_soft_wall_count: 5,
             walls: vec![],
-            entrypoints: vec![Position { x: 0, y: 0 }],
+            entrypoints: vec![Position::new(0, 0)],
             checkpoints: vec![],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
         };
 
-        let board = config.validate_and_convert_to_board();
+        let board = config.validate_and_convert_to_floors();
 
         assert_eq!(board, Err(MazeError::NoCheckpoint))
     }
 
     #[test]
-    fn test_validate_and_convert_to_board_with_wall_out_of_bounds() {
+    fn test_validate_and_convert_to_floors_with_wall_out_of_bounds() {
         let config = MazeConfig {
             col_count: 2,
             row_count: 2,
+            floor_count: 1,
             max_soft_wall_count: 5,
-            walls: vec![Position { x: 5, y: 5 }],
-            entrypoints: vec![Position { x: 0, y: 0 }],
+            walls: vec![Position::new(5, 5)],
+            entrypoints: vec![Position::new(0, 0)],
             checkpoints: vec![Checkpoint {
-                position: Position { x: 1, y: 1 },
+                position: Position::new(1, 1),
                 level: 1,
             }],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
         };
 
-        let board = config.validate_and_convert_to_board();
+        let board = config.validate_and_convert_to_floors();
 
         assert_eq!(
             board,
             Err(MazeError::TileOutOfBounds(
-                Position { x: 5, y: 5 },
+                Position::new(5, 5),
                 TileKind::Wall
             ))
         )
@@ -183,126 +209,141 @@ mod tests {
         let config = MazeConfig {
             col_count: 2,
             row_count: 2,
+            floor_count: 1,
             max_soft_wall_count: 5,
-            walls: vec![Position { x: 1, y: 0 }],
-            entrypoints: vec![Position { x: 3, y: 3 }],
+            walls: vec![Position::new(1, 0)],
+            entrypoints: vec![Position::new(3, 3)],
             checkpoints: vec![Checkpoint {
-                position: Position { x: 1, y: 1 },
+                position: Position::new(1, 1),
                 level: 1,
             }],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
         };
 
-        let board = config.validate_and_convert_to_board();
+        let board = config.validate_and_convert_to_floors();
 
         assert_eq!(
             board,
             Err(MazeError::TileOutOfBounds(
-                Position { x: 3, y: 3 },
+                Position::new(3, 3),
                 TileKind::Entrypoint
             ))
         )
     }
 
     #[test]
-    fn test_validate_and_convert_to_board_with_checkpoint_out_of_bounds() {
+    fn test_validate_and_convert_to_floors_with_checkpoint_out_of_bounds() {
         let config = MazeConfig {
             col_count: 2,
             row_count: 2,
+            floor_count: 1,
             max_soft_wall_count: 5,
-            walls: vec![Position { x: 1, y: 0 }],
-            entrypoints: vec![Position { x: 0, y: 0 }],
+            walls: vec![Position::new(1, 0)],
+            entrypoints: vec![Position::new(0, 0)],
             checkpoints: vec![Checkpoint {
-                position: Position { x: 77, y: 77 },
+                position: Position::new(77, 77),
                 level: 1,
             }],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
         };
 
-        let board = config.validate_and_convert_to_board();
+        let board = config.validate_and_convert_to_floors();
 
         assert_eq!(
             board,
             Err(MazeError::TileOutOfBounds(
-                Position { x: 77, y: 77 },
+                Position::new(77, 77),
                 TileKind::Checkpoint { level: 1 }
             ))
         )
     }
 
     #[test]
-    fn test_validate_and_convert_to_board_with_overlapping_wall_and_entrypoint() {
+    fn test_validate_and_convert_to_floors_with_overlapping_wall_and_entrypoint() {
         let config = MazeConfig {
             col_count: 2,
             row_count: 2,
+            floor_count: 1,
             max_soft_wall_count: 5,
-            walls: vec![Position { x: 0, y: 0 }],
-            entrypoints: vec![Position { x: 0, y: 0 }],
+            walls: vec![Position::new(0, 0)],
+            entrypoints: vec![Position::new(0, 0)],
             checkpoints: vec![Checkpoint {
-                position: Position { x: 1, y: 1 },
+                position: Position::new(1, 1),
                 level: 1,
             }],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
         };
 
-        let board = config.validate_and_convert_to_board();
+        let board = config.validate_and_convert_to_floors();
 
         assert_eq!(
             board,
             Err(MazeError::OverlappingTiles {
-                position: Position { x: 0, y: 0 },
+                position: Position::new(0, 0),
                 kinds: (TileKind::Entrypoint, TileKind::Wall)
             })
         )
     }
 
     #[test]
-    fn test_validate_and_convert_to_board_with_overlapping_wall_and_checkpoint() {
+    fn test_validate_and_convert_to_floors_with_overlapping_wall_and_checkpoint() {
         let config = MazeConfig {
             col_count: 2,
             row_count: 2,
+            floor_count: 1,
             max_soft_wall_count: 5,
-            walls: vec![Position { x: 1, y: 1 }],
-            entrypoints: vec![Position { x: 1, y: 0 }],
+            walls: vec![Position::new(1, 1)],
+            entrypoints: vec![Position::new(1, 0)],
             checkpoints: vec![Checkpoint {
-                position: Position { x: 1, y: 1 },
+                position: Position::new(1, 1),
                 level: 1,
             }],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
         };
 
-        let board = config.validate_and_convert_to_board();
+        let board = config.validate_and_convert_to_floors();
 
         assert_eq!(
             board,
             Err(MazeError::OverlappingTiles {
-                position: Position { x: 1, y: 1 },
+                position: Position::new(1, 1),
                 kinds: (TileKind::Checkpoint { level: 1 }, TileKind::Wall)
             })
         )
     }
 
     #[test]
-    fn test_validate_and_convert_to_board() {
+    fn test_validate_and_convert_to_floors() {
         let config = MazeConfig {
             col_count: 3,
             row_count: 3,
+            floor_count: 1,
             max_soft_wall_count: 5,
-            walls: vec![Position { x: 0, y: 1 }],
-            entrypoints: vec![Position { x: 1, y: 0 }],
+            walls: vec![Position::new(0, 1)],
+            entrypoints: vec![Position::new(1, 0)],
             checkpoints: vec![
                 Checkpoint {
-                    position: Position { x: 1, y: 1 },
+                    position: Position::new(1, 1),
                     level: 1,
                 },
                 Checkpoint {
-                    position: Position { x: 2, y: 2 },
+                    position: Position::new(2, 2),
                     level: 2,
                 },
             ],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
         };
 
-        let board = config.validate_and_convert_to_board();
+        let board = config.validate_and_convert_to_floors();
 
         assert_eq!(
             board,
-            Ok(vec![
+            Ok(vec![vec![
                 vec![TileKind::Empty, TileKind::Wall, TileKind::Empty],
                 vec![
                     TileKind::Entrypoint,
@@ -314,7 +355,120 @@ mod tests {
                     TileKind::Empty,
                     TileKind::Checkpoint { level: 2 }
                 ]
+            ]])
+        )
+    }
+
+    fn get_two_floor_config(stairs: Vec<Stairs>, walls: Vec<Position>) -> MazeConfig {
+        MazeConfig {
+            col_count: 2,
+            row_count: 2,
+            floor_count: 2,
+            max_soft_wall_count: 5,
+            walls,
+            entrypoints: vec![Position::new(0, 0)],
+            checkpoints: vec![Checkpoint {
+                position: Position::on_floor(1, 1, 1),
+                level: 1,
+            }],
+            stairs,
+            scoring_rule: ScoringRuleType::Min,
+        }
+    }
+
+    #[test]
+    fn test_validate_and_convert_to_floors_with_stairs() {
+        let config = get_two_floor_config(
+            vec![
+                Stairs {
+                    position: Position::new(1, 0),
+                    to_floor: 1,
+                },
+                Stairs {
+                    position: Position::on_floor(0, 1, 1),
+                    to_floor: 0,
+                },
+            ],
+            vec![Position::on_floor(0, 0, 1)],
+        );
+
+        let floors = config.validate_and_convert_to_floors();
+
+        assert_eq!(
+            floors,
+            Ok(vec![
+                vec![
+                    vec![TileKind::Entrypoint, TileKind::Empty],
+                    vec![TileKind::Stairs { to_floor: 1 }, TileKind::Empty]
+                ],
+                vec![
+                    vec![TileKind::Wall, TileKind::Stairs { to_floor: 0 }],
+                    vec![TileKind::Empty, TileKind::Checkpoint { level: 1 }]
+                ]
             ])
         )
     }
+
+    #[test]
+    fn test_validate_and_convert_to_floors_with_floor_out_of_bounds() {
+        let config = get_two_floor_config(vec![], vec![Position::on_floor(0, 1, 2)]);
+
+        let floors = config.validate_and_convert_to_floors();
+
+        assert_eq!(
+            floors,
+            Err(MazeError::TileOutOfBounds(
+                Position::on_floor(0, 1, 2),
+                TileKind::Wall
+            ))
+        )
+    }
+
+    #[test]
+    fn test_validate_and_convert_to_floors_with_row_out_of_bounds() {
+        // rows past the floor never spill over to the next floor
+        let config = get_two_floor_config(vec![], vec![Position::new(0, 2)]);
+
+        let floors = config.validate_and_convert_to_floors();
+
+        assert_eq!(
+            floors,
+            Err(MazeError::TileOutOfBounds(
+                Position::new(0, 2),
+                TileKind::Wall
+            ))
+        )
+    }
+
+    #[test]
+    fn test_validate_and_convert_to_floors_with_invalid_stairs() {
+        for (position, to_floor) in [(Position::new(1, 0), 2), (Position::on_floor(1, 0, 1), 1)] {
+            let config = get_two_floor_config(vec![Stairs { position, to_floor }], vec![]);
+
+            let floors = config.validate_and_convert_to_floors();
+
+            assert_eq!(floors, Err(MazeError::InvalidStairs { position, to_floor }))
+        }
+    }
+
+    #[test]
+    fn test_validate_and_convert_to_floors_with_blocked_stairs() {
+        let config = get_two_floor_config(
+            vec![Stairs {
+                position: Position::new(1, 0),
+                to_floor: 1,
+            }],
+            vec![Position::on_floor(1, 0, 1)],
+        );
+
+        let floors = config.validate_and_convert_to_floors();
+
+        assert_eq!(
+            floors,
+            Err(MazeError::BlockedStairs {
+                position: Position::new(1, 0),
+                to_floor: 1
+            })
+        )
+    }
 }
@@ -6,6 +6,14 @@ use super::tile::{Position, TileKind};
 pub enum MazeError {
     #[error("Maze must have at least {min} tiles, got {size}")]
     InvalidMazeSize { size: usize, min: usize },
+    #[error(
+        "Maze with {col_count} columns, {row_count} rows and {floor_count} floors is too large"
+    )]
+    MazeTooLarge {
+        col_count: usize,
+        row_count: usize,
+        floor_count: usize,
+    },
     #[error("Maze required at least one entrypoint")]
     NoEntrypoint,
     #[error("Maze required at least one checkpoint")]
@@ -17,4 +25,8 @@ pub enum MazeError {
         position: Position,
         kinds: (TileKind, TileKind),
     },
+    #[error("Stairs at position {position} lead to invalid floor {to_floor}")]
+    InvalidStairs { position: Position, to_floor: usize },
+    #[error("Stairs at position {position} lead to a wall on floor {to_floor}")]
+    BlockedStairs { position: Position, to_floor: usize },
 }
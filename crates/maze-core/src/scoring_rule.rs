@@ -0,0 +1,15 @@
+/// Rule combining scores of separate entrypoints into the score of the maze
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ScoringRuleType {
+    /// Score of the best entrypoint for the player, the shortest of all solved runs
+    #[default]
+    Min,
+    /// Score of the longest of all solved runs
+    Max,
+    /// Scores of all solved runs added together
+    Sum,
+    /// Average score of solved runs rounded down
+    Average,
+    /// Score of the shortest run, but only if every entrypoint reaches the exit, otherwise the maze is unsolved
+    AllEntrypoints,
+}
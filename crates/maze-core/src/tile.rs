@@ -1,24 +1,79 @@
-use std::fmt::Display;
+use std::{
+    fmt::Display,
+    hash::{Hash, Hasher},
+};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum TileKind {
     Entrypoint,
     Empty,
     Wall,
-    Checkpoint { level: i32 },
+    Checkpoint {
+        level: i32,
+    },
+    /// Connects the tile with the tile at the same column and row of the target floor, in both directions
+    Stairs {
+        to_floor: usize,
+    },
 }
 
 pub type TileBoard = Vec<Vec<TileKind>>;
 
-#[derive(Clone, Copy, PartialEq, Debug, Eq, Hash)]
+/// Boards of all floors of the maze indexed by floor, the ground floor goes first
+pub type TileFloors = Vec<TileBoard>;
+
+/// Position on the board, positions without floor lay on the ground floor
+#[derive(Clone, Copy, Debug)]
 pub struct Position {
     pub x: usize,
     pub y: usize,
+    pub floor: Option<usize>,
 }
 
+impl Position {
+    /// Creates position on the ground floor
+    pub fn new(x: usize, y: usize) -> Self {
+        Self { x, y, floor: None }
+    }
+
+    /// Creates position on the floor, ground floor positions are created without floor
+    pub fn on_floor(x: usize, y: usize, floor: usize) -> Self {
+        Self {
+            x,
+            y,
+            floor: Some(floor).filter(|floor| *floor > 0),
+        }
+    }
+
+    pub fn floor_index(&self) -> usize {
+        self.floor.unwrap_or(0)
+    }
+}
+
+// position without floor and position on the ground floor are the same tile
+impl PartialEq for Position {
+    fn eq(&self, other: &Self) -> bool {
+        self.x == other.x && self.y == other.y && self.floor_index() == other.floor_index()
+    }
+}
+
+impl Eq for Position {}
+
+impl Hash for Position {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.x.hash(state);
+        self.y.hash(state);
+        self.floor_index().hash(state);
+    }
+}
+
+// positions on the ground floor print the same whether the floor is set or not
 impl Display for Position {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({},{})", self.x, self.y)
+        match self.floor_index() {
+            0 => write!(f, "({},{})", self.x, self.y),
+            floor => write!(f, "({},{},{})", self.x, self.y, floor),
+        }
     }
 }
 
@@ -27,3 +82,44 @@ pub struct Checkpoint {
     pub position: Position,
     pub level: i32,
 }
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Stairs {
+    pub position: Position,
+    pub to_floor: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_position_without_floor_is_on_ground_floor() {
+        let positions = HashSet::from([
+            Position::new(1, 2),
+            Position {
+                x: 1,
+                y: 2,
+                floor: Some(0),
+            },
+            Position::on_floor(1, 2, 0),
+        ]);
+
+        assert_eq!(positions.len(), 1);
+        assert_eq!(Position::on_floor(1, 2, 0).floor, None);
+        assert_ne!(Position::new(1, 2), Position::on_floor(1, 2, 1));
+        assert_eq!(Position::on_floor(1, 2, 3).to_string(), "(1,2,3)");
+        assert_eq!(Position::new(1, 2).to_string(), "(1,2)");
+        assert_eq!(
+            Position {
+                x: 1,
+                y: 2,
+                floor: Some(0)
+            }
+            .to_string(),
+            "(1,2)"
+        );
+    }
+}
@@ -0,0 +1,107 @@
+//! Times generation of every built-in generator, run with `cargo bench -p maze-generator`.
+//! Printed checksum of the generated mazes has to stay the same when generation gets faster.
+//! Wall placement is also timed against the placement rerunning the whole maze after every
+//! wall, on the same seeds.
+
+use std::time::Instant;
+
+use maze_core::{Maze, Position, TileKind};
+use maze_generator::{
+    create_generator, create_generator_by_id, get_random_solvable_wall_groups,
+    get_reference_solvable_wall_groups, list_generators, GeneratorError, GeneratorOptions,
+    MazeGeneratorType,
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+const SEED_COUNT: u64 = 20;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+/// FNV-1a hash of the bytes, unlike `DefaultHasher` it is the same across Rust versions
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+    })
+}
+
+fn main() -> Result<(), GeneratorError> {
+    let mut total = 0.0;
+
+    for info in list_generators() {
+        let generator = create_generator_by_id(&info.id, None)?;
+        let mut checksum = FNV_OFFSET_BASIS;
+
+        let start = Instant::now();
+        for seed in 0..SEED_COUNT {
+            let generated = generator.generate_with_seed(seed)?;
+            checksum = fnv1a(checksum, format!("{:?}", generated.config).as_bytes());
+        }
+        let elapsed = start.elapsed().as_secs_f64() * 1000.0;
+        total += elapsed;
+
+        println!(
+            "{:<16} {:>9.2} ms/maze  checksum {:016x}",
+            info.id,
+            elapsed / SEED_COUNT as f64,
+            checksum
+        );
+    }
+
+    println!("{:<16} {:>9.2} ms", "total", total);
+
+    compare_wall_placements()
+}
+
+/// Walls wall-free vanilla boards up to their soft wall limit with both placements
+fn compare_wall_placements() -> Result<(), GeneratorError> {
+    let generator = create_generator(
+        MazeGeneratorType::Vanilla,
+        GeneratorOptions {
+            wall_range: 0..1,
+            ..GeneratorOptions::default_for(MazeGeneratorType::Vanilla)
+        },
+    )?;
+
+    let (mut incremental, mut reference) = (0.0, 0.0);
+    for seed in 0..SEED_COUNT {
+        let config = generator.generate_with_seed(seed)?.config;
+        let maze = Maze::new(&config)?;
+        // placed walls are held to the soft wall limit of the maze
+        let wall_count = config.max_soft_wall_count as usize;
+        let groups: Vec<Vec<Position>> = (0..config.col_count)
+            .flat_map(|x| (0..config.row_count).map(move |y| Position::new(x, y)))
+            .filter(|position| maze.floors()[0][position.x][position.y] == TileKind::Empty)
+            .map(|position| vec![position])
+            .collect();
+
+        let start = Instant::now();
+        let walls = get_random_solvable_wall_groups(
+            &mut ChaCha8Rng::seed_from_u64(seed),
+            &config,
+            &groups,
+            wall_count,
+        )?;
+        incremental += start.elapsed().as_secs_f64() * 1000.0;
+
+        let start = Instant::now();
+        let expected = get_reference_solvable_wall_groups(
+            &mut ChaCha8Rng::seed_from_u64(seed),
+            &config,
+            &groups,
+            wall_count,
+        )?;
+        reference += start.elapsed().as_secs_f64() * 1000.0;
+
+        assert_eq!(walls, expected, "placements differ for seed {}", seed);
+    }
+
+    println!();
+    for (name, elapsed) in [("walls", incremental), ("reference", reference)] {
+        println!("{:<16} {:>9.2} ms/maze", name, elapsed / SEED_COUNT as f64);
+    }
+    println!("{:<16} {:>9.2}x", "speedup", reference / incremental);
+
+    Ok(())
+}
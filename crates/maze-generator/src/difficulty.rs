@@ -0,0 +1,131 @@
+use std::cmp::Ordering;
+
+use maze_core::{Maze, MazeConfig};
+use maze_runner::{hints::best_next_wall, MazeRunner};
+
+use crate::GeneratorError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DifficultyMetric {
+    /// Score reached by greedily placing the best soft walls divided by the score without soft walls
+    ScoreRatio,
+    /// Number of empty tiles that raise the score when walled on their own
+    UsefulWallCells,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DifficultyTarget {
+    pub metric: DifficultyMetric,
+    /// Lowest accepted difficulty, inclusive
+    pub min: f64,
+    /// Highest accepted difficulty, inclusive
+    pub max: f64,
+    /// Number of candidate mazes generated before giving up
+    pub max_attempts: usize,
+}
+
+impl DifficultyTarget {
+    pub(crate) fn validate(&self) -> Result<(), GeneratorError> {
+        // NaN bounds are not comparable and get rejected as well
+        let ordered = matches!(
+            self.min.partial_cmp(&self.max),
+            Some(Ordering::Less | Ordering::Equal)
+        );
+        if !ordered || self.max_attempts == 0 {
+            return Err(GeneratorError::InvalidDifficultyTarget {
+                min: self.min,
+                max: self.max,
+                max_attempts: self.max_attempts,
+            });
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn contains(&self, difficulty: f64) -> bool {
+        self.min <= difficulty && difficulty <= self.max
+    }
+}
+
+/// Measures difficulty of the maze, unsolvable mazes have no difficulty at all
+pub fn measure_difficulty(
+    config: &MazeConfig,
+    metric: DifficultyMetric,
+) -> Result<f64, GeneratorError> {
+    let maze = Maze::new(config)?;
+    let runner = MazeRunner::new(&maze);
+
+    let baseline = match runner.run_score(&vec![])? {
+        Some(score) => score,
+        None => return Ok(0.0),
+    };
+
+    match metric {
+        DifficultyMetric::ScoreRatio => {
+            let mut walls = vec![];
+            // hints run out once the soft wall limit is reached
+            while let Some(hint) = best_next_wall(&maze, &walls)?.first() {
+                if hint.score_gain == 0 {
+                    break;
+                }
+                walls.push(hint.position);
+            }
+
+            let score = runner.run_score(&walls)?.unwrap_or(baseline);
+            Ok(score as f64 / baseline as f64)
+        }
+        DifficultyMetric::UsefulWallCells => {
+            let hints = best_next_wall(&maze, &[])?;
+            Ok(hints.iter().filter(|hint| hint.score_gain > 0).count() as f64)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use maze_core::{Checkpoint, Position, ScoringRuleType};
+
+    use super::*;
+
+    fn get_config(max_soft_wall_count: u32) -> MazeConfig {
+        MazeConfig {
+            col_count: 3,
+            row_count: 3,
+            floor_count: 1,
+            max_soft_wall_count,
+            entrypoints: vec![Position::new(0, 0)],
+            checkpoints: vec![Checkpoint {
+                position: Position::new(2, 0),
+                level: 1,
+            }],
+            walls: vec![],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
+        }
+    }
+
+    #[test]
+    fn test_measure_difficulty() -> Result<(), Box<dyn Error>> {
+        // walling (1,0) leads the path around, walling (1,1) afterwards as well
+        assert_eq!(
+            measure_difficulty(&get_config(2), DifficultyMetric::ScoreRatio)?,
+            3.0
+        );
+        assert_eq!(
+            measure_difficulty(&get_config(1), DifficultyMetric::ScoreRatio)?,
+            2.0
+        );
+        assert_eq!(
+            measure_difficulty(&get_config(0), DifficultyMetric::ScoreRatio)?,
+            1.0
+        );
+        assert_eq!(
+            measure_difficulty(&get_config(2), DifficultyMetric::UsefulWallCells)?,
+            1.0
+        );
+
+        Ok(())
+    }
+}
@@ -3,10 +3,59 @@ use std::error::Error;
 use maze_core::MazeError;
 use maze_runner::MazeRunnerError;
 
+use crate::UnsatisfiedConstraint;
+
 #[derive(Debug, thiserror::Error)]
 pub enum GeneratorError {
     #[error("Aggregated error occurred: {0}")]
     AggregatedError(Box<dyn Error>),
+    #[error("Generator option {name} has invalid range {start}..{end}")]
+    InvalidRange {
+        name: &'static str,
+        start: usize,
+        end: usize,
+    },
+    #[error("Generator option {name} allows up to {requested} tiles but only {available} fit on the board")]
+    TooManyTiles {
+        name: &'static str,
+        requested: usize,
+        available: usize,
+    },
+    #[error("Board with {col_count} columns and {row_count} rows is too small for the padding")]
+    BoardTooSmall { col_count: usize, row_count: usize },
+    #[error("No generator is registered under id {id}")]
+    UnknownGenerator { id: String },
+    #[error("Generator with id {id} is already registered")]
+    DuplicateGenerator { id: String },
+    #[error("Template is invalid: {reason}")]
+    InvalidTemplate { reason: String },
+    #[error("Template has constraints no maze can satisfy: {constraints:?}")]
+    UnsatisfiableTemplate {
+        constraints: Vec<UnsatisfiedConstraint>,
+    },
+    #[error("Maze is not solvable so it can't be mutated into a solvable one")]
+    UnsolvableMaze,
+    #[error("Campaign must have at least one level, got {level_count}")]
+    InvalidCampaignLength { level_count: usize },
+    #[error("Difficulty target between {min} and {max} with {max_attempts} attempts is invalid")]
+    InvalidDifficultyTarget {
+        min: f64,
+        max: f64,
+        max_attempts: usize,
+    },
+    #[error("No maze with difficulty between {min} and {max} was generated in {attempts} attempts, the closest one had {closest}")]
+    DifficultyNotReached {
+        min: f64,
+        max: f64,
+        attempts: usize,
+        closest: f64,
+    },
+    #[error("No maze of campaign level {level} was as hard as the previous level with {previous}, the hardest one had {hardest}")]
+    CampaignDifficultyNotReached {
+        level: usize,
+        previous: f64,
+        hardest: f64,
+    },
 }
 
 impl From<MazeError> for GeneratorError {
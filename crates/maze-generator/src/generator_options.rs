@@ -0,0 +1,108 @@
+use std::ops::Range;
+
+use crate::{get_generator_info, GeneratorError, MazeGeneratorType};
+
+/// Dimensions and count ranges of generated mazes, all ranges are half-open.
+/// Symmetric counts entrypoints, checkpoints, exits and walls of a single side,
+/// Spiral and Arena measure paddings in rings from the border
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratorOptions {
+    pub col_count: usize,
+    pub row_count: usize,
+    /// Unused by Waterfall, it enters over the whole top row
+    pub entrypoint_range: Range<usize>,
+    pub checkpoint_range: Range<usize>,
+    /// Unused by Waterfall, it exits over the whole bottom row
+    pub exit_range: Range<usize>,
+    pub wall_range: Range<usize>,
+    pub max_soft_wall_range: Range<usize>,
+    /// Minimal distance of checkpoints from the entrypoint and exit sides of the board
+    pub checkpoint_padding: usize,
+    /// Minimal distance of walls from the entrypoint and exit sides of the board
+    pub wall_padding: usize,
+}
+
+impl GeneratorOptions {
+    /// Default options the builtin generator of the type is registered with
+    pub fn default_for(generator_type: MazeGeneratorType) -> Self {
+        // builtin generators are registered with the registry and can't be replaced
+        get_generator_info(generator_type.id())
+            .map(|info| info.default_options)
+            .expect("builtin generator should be registered")
+    }
+
+    /// Checks options of a generator running from one side of the board to the opposite one,
+    /// `length` is the size of the board along that direction and `width` across it
+    pub(crate) fn validate(&self, length: usize, width: usize) -> Result<(), GeneratorError> {
+        let padding = self.checkpoint_padding.max(self.wall_padding).max(1);
+        if width == 0 || length <= padding * 2 {
+            return Err(GeneratorError::BoardTooSmall {
+                col_count: self.col_count,
+                row_count: self.row_count,
+            });
+        }
+
+        // entrypoint and exit sides are never available to checkpoints
+        self.validate_counts((length - self.checkpoint_padding.max(1) * 2) * width)
+    }
+
+    /// Checks counts of tiles placed inside the board, `checkpoint_space` is the number
+    /// of tiles available to checkpoints
+    pub(crate) fn validate_counts(&self, checkpoint_space: usize) -> Result<(), GeneratorError> {
+        validate_range(
+            "checkpoint_range",
+            &self.checkpoint_range,
+            0,
+            checkpoint_space,
+        )?;
+        validate_range("wall_range", &self.wall_range, 0, usize::MAX)?;
+        validate_range(
+            "max_soft_wall_range",
+            &self.max_soft_wall_range,
+            0,
+            usize::MAX,
+        )
+    }
+
+    /// Checks count of entrypoints and exits against the number of tiles available to them
+    pub(crate) fn validate_sides(
+        &self,
+        entrypoint_space: usize,
+        exit_space: usize,
+    ) -> Result<(), GeneratorError> {
+        validate_range(
+            "entrypoint_range",
+            &self.entrypoint_range,
+            1,
+            entrypoint_space,
+        )?;
+        validate_range("exit_range", &self.exit_range, 1, exit_space)
+    }
+}
+
+fn validate_range(
+    name: &'static str,
+    range: &Range<usize>,
+    min: usize,
+    available: usize,
+) -> Result<(), GeneratorError> {
+    // counts are sampled through `u32`
+    if range.start < min || range.start >= range.end || range.end > u32::MAX as usize {
+        return Err(GeneratorError::InvalidRange {
+            name,
+            start: range.start,
+            end: range.end,
+        });
+    }
+
+    let requested = range.end - 1;
+    if requested > available {
+        return Err(GeneratorError::TooManyTiles {
+            name,
+            requested,
+            available,
+        });
+    }
+
+    Ok(())
+}
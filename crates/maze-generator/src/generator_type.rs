@@ -1,4 +1,30 @@
+use crate::Symmetry;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MazeGeneratorType {
     Vanilla,
     Waterfall,
+    /// Concentric rings with a checkpoint on each, entered from the border and exited in the center
+    Spiral,
+    /// Open board entered from the whole border with the exit in the center
+    Arena,
+    /// Left to right board with walls grouped into islands
+    Islands,
+    /// Point or mirror symmetric board entered from both sides with exits in the middle
+    Symmetric(Symmetry),
+}
+
+impl MazeGeneratorType {
+    /// Id of the generator in the generator registry
+    pub fn id(&self) -> &'static str {
+        match self {
+            MazeGeneratorType::Vanilla => "vanilla",
+            MazeGeneratorType::Waterfall => "waterfall",
+            MazeGeneratorType::Spiral => "spiral",
+            MazeGeneratorType::Arena => "arena",
+            MazeGeneratorType::Islands => "islands",
+            MazeGeneratorType::Symmetric(Symmetry::Mirror) => "symmetric-mirror",
+            MazeGeneratorType::Symmetric(Symmetry::Point) => "symmetric-point",
+        }
+    }
 }
@@ -0,0 +1,188 @@
+use maze_core::{MazeConfig, ScoringRuleType};
+use rand::RngCore;
+
+use crate::{GeneratorError, GeneratorOptions};
+
+use super::{
+    helpers::{
+        get_checkpoints, get_random_number_in_range, get_random_positions,
+        get_random_solvable_walls, get_ring_count, get_ring_positions,
+    },
+    MazeGenerator,
+};
+
+pub(crate) struct ArenaGenerator {
+    options: GeneratorOptions,
+}
+
+impl ArenaGenerator {
+    pub(crate) fn new(options: GeneratorOptions) -> Result<Self, GeneratorError> {
+        let (col_count, row_count) = (options.col_count, options.row_count);
+        let inner_ring = get_ring_count(col_count, row_count).saturating_sub(1);
+        let padding = options.checkpoint_padding.max(options.wall_padding).max(1);
+        if inner_ring <= padding {
+            return Err(GeneratorError::BoardTooSmall {
+                col_count,
+                row_count,
+            });
+        }
+
+        options.validate_sides(
+            get_ring_positions(col_count, row_count, 0..1, &[]).len(),
+            get_ring_positions(col_count, row_count, inner_ring..inner_ring + 1, &[]).len(),
+        )?;
+        options.validate_counts(
+            get_ring_positions(
+                col_count,
+                row_count,
+                options.checkpoint_padding.max(1)..inner_ring,
+                &[],
+            )
+            .len(),
+        )?;
+
+        Ok(Self { options })
+    }
+}
+
+impl MazeGenerator for ArenaGenerator {
+    fn generate_with_rng(&self, rng: &mut dyn RngCore) -> Result<MazeConfig, GeneratorError> {
+        let options = &self.options;
+        let (col_count, row_count) = (options.col_count, options.row_count);
+        let inner_ring = get_ring_count(col_count, row_count) - 1;
+
+        let entrypoint_count = get_random_number_in_range(rng, options.entrypoint_range.clone());
+        let checkpoint_count = get_random_number_in_range(rng, options.checkpoint_range.clone());
+        let exit_count = get_random_number_in_range(rng, options.exit_range.clone());
+        let wall_count = get_random_number_in_range(rng, options.wall_range.clone());
+        let max_soft_wall_count =
+            get_random_number_in_range(rng, options.max_soft_wall_range.clone());
+
+        // the whole border is open for entering
+        let entrypoint_positions = get_random_positions(
+            rng,
+            &get_ring_positions(col_count, row_count, 0..1, &[]),
+            entrypoint_count,
+        );
+        let exit_positions = get_random_positions(
+            rng,
+            &get_ring_positions(col_count, row_count, inner_ring..inner_ring + 1, &[]),
+            exit_count,
+        );
+
+        let empty_positions = get_ring_positions(
+            col_count,
+            row_count,
+            options.checkpoint_padding.max(1)..inner_ring,
+            &[],
+        );
+
+        let checkpoint_positions = get_random_positions(rng, &empty_positions, checkpoint_count);
+
+        let empty_positions = get_ring_positions(
+            col_count,
+            row_count,
+            options.wall_padding..inner_ring,
+            &[&entrypoint_positions, &checkpoint_positions][..],
+        );
+
+        let checkpoints = get_checkpoints(&checkpoint_positions, &exit_positions);
+
+        let mut config = MazeConfig {
+            col_count,
+            row_count,
+            floor_count: 1,
+            max_soft_wall_count: wall_count as u32,
+            entrypoints: entrypoint_positions,
+            checkpoints,
+            walls: vec![],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
+        };
+
+        let walls = get_random_solvable_walls(rng, &config, &empty_positions, wall_count)?;
+
+        config.max_soft_wall_count = max_soft_wall_count as u32;
+        config.walls = walls;
+
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use maze_core::Position;
+
+    use crate::MazeGeneratorType;
+
+    use super::super::helpers::{check_generated_mazes, get_exit_level, get_ring};
+    use super::*;
+
+    fn get_options() -> GeneratorOptions {
+        GeneratorOptions::default_for(MazeGeneratorType::Arena)
+    }
+
+    #[test]
+    fn test_arena_generator() -> Result<(), Box<dyn Error>> {
+        let generator = ArenaGenerator::new(get_options())?;
+        for _ in 0..5 {
+            let config = generator.generate();
+            assert!(config.is_ok());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_arena_generator_placement() -> Result<(), Box<dyn Error>> {
+        let generator = ArenaGenerator::new(get_options())?;
+        let ring = |position: &Position| get_ring(15, 11, position);
+
+        check_generated_mazes(&generator, |config| {
+            let exit_level = get_exit_level(config)?;
+
+            assert!(config
+                .entrypoints
+                .iter()
+                .all(|position| ring(position) == 0));
+            for checkpoint in config.checkpoints.iter() {
+                let checkpoint_ring = ring(&checkpoint.position);
+                if checkpoint.level == exit_level {
+                    // the center of 15x11 board is the middle of its sixth ring
+                    assert_eq!(checkpoint_ring, 5);
+                } else {
+                    assert!((2..5).contains(&checkpoint_ring));
+                }
+            }
+            assert!(config.walls.len() < 9);
+            assert!(config.walls.iter().all(|position| ring(position) < 5));
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_arena_generator_with_invalid_options() {
+        assert!(matches!(
+            ArenaGenerator::new(GeneratorOptions {
+                col_count: 5,
+                row_count: 5,
+                ..get_options()
+            }),
+            Err(GeneratorError::BoardTooSmall { .. })
+        ));
+        assert!(matches!(
+            ArenaGenerator::new(GeneratorOptions {
+                entrypoint_range: 2..50,
+                ..get_options()
+            }),
+            Err(GeneratorError::TooManyTiles {
+                name: "entrypoint_range",
+                requested: 49,
+                available: 48
+            })
+        ));
+    }
+}
@@ -0,0 +1,381 @@
+use std::ops::Range;
+
+use rand::{thread_rng, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    measure_difficulty, DifficultyMetric, GeneratorError, GeneratorOptions, MazeGeneratorType,
+};
+
+use super::{
+    vanilla::VanillaGenerator, waterfall::WaterfallGenerator, GeneratedMaze, MazeGenerator,
+};
+
+/// Lessons in the order the campaign introduces them, campaigns shorter than the list
+/// introduce several lessons on the same level
+const LESSONS: [CampaignLesson; 5] = [
+    CampaignLesson::FirstMaze,
+    CampaignLesson::FirstCheckpoint,
+    CampaignLesson::MultipleCheckpoints,
+    CampaignLesson::MultipleEntrypoints,
+    CampaignLesson::FirstWaterfall,
+];
+
+/// Candidate mazes generated per level, the easiest one not easier than the previous level is picked
+const CANDIDATE_COUNT: usize = 8;
+
+/// Candidates a level may go through when none of the first ones is hard enough
+const MAX_ATTEMPTS: usize = 64;
+
+/// Walls and soft walls added to a level whose candidates were all easier than the previous level
+const WALL_RAISE: usize = 2;
+
+/// Times the walls of a level are raised before the campaign gives up on it
+const MAX_WALL_RAISES: usize = 4;
+
+/// What the campaign level is meant to teach
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CampaignLesson {
+    FirstMaze,
+    FirstCheckpoint,
+    MultipleCheckpoints,
+    MultipleEntrypoints,
+    FirstWaterfall,
+    /// Repeats the lessons introduced so far on a bigger board
+    Practice,
+}
+
+impl CampaignLesson {
+    pub fn description(&self) -> &'static str {
+        match self {
+            CampaignLesson::FirstMaze => "Place soft walls to make the path to the exit longer",
+            CampaignLesson::FirstCheckpoint => {
+                "First level with a checkpoint to visit before the exit"
+            }
+            CampaignLesson::MultipleCheckpoints => {
+                "First multi-checkpoint level, checkpoints are visited in the order of their levels"
+            }
+            CampaignLesson::MultipleEntrypoints => {
+                "First level with several entrypoints, the runner takes the shortest path of all of them"
+            }
+            CampaignLesson::FirstWaterfall => {
+                "First level entered over the whole top row and exited over the whole bottom row"
+            }
+            CampaignLesson::Practice => "Practice of the lessons so far on a bigger board",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CampaignLevel {
+    pub lesson: CampaignLesson,
+    pub generator_type: MazeGeneratorType,
+    /// Options the maze was generated with, together with the maze seed they reproduce the level
+    pub options: GeneratorOptions,
+    /// Difficulty measured as `DifficultyMetric::ScoreRatio`, never lower than of the previous level
+    pub difficulty: f64,
+    pub maze: GeneratedMaze,
+}
+
+/// Levels of the campaign in the order they are played
+#[derive(Debug, Clone, PartialEq)]
+pub struct Campaign {
+    pub seed: u64,
+    pub levels: Vec<CampaignLevel>,
+}
+
+/// Generator of campaigns growing board size, checkpoint count and difficulty level by level
+pub struct CampaignGenerator {
+    level_count: usize,
+}
+
+impl CampaignGenerator {
+    pub fn new(level_count: usize) -> Result<Self, GeneratorError> {
+        if level_count == 0 {
+            return Err(GeneratorError::InvalidCampaignLength { level_count });
+        }
+
+        Ok(Self { level_count })
+    }
+
+    pub fn generate(&self) -> Result<Campaign, GeneratorError> {
+        self.generate_with_seed(thread_rng().gen())
+    }
+
+    /// Same seed always yields the same campaign, seeds of the levels are drawn from it
+    pub fn generate_with_seed(&self, seed: u64) -> Result<Campaign, GeneratorError> {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let mut levels: Vec<CampaignLevel> = vec![];
+
+        for index in 0..self.level_count {
+            let (lesson, stage) = self.get_lesson(index);
+            let (generator_type, options) = self.get_options(index, stage);
+            let previous = levels.last().map_or(0.0, |level| level.difficulty);
+            let (options, difficulty, maze) =
+                self.pick_level(&mut rng, index, generator_type, options, previous)?;
+
+            levels.push(CampaignLevel {
+                lesson,
+                generator_type,
+                options,
+                difficulty,
+                maze,
+            });
+        }
+
+        Ok(Campaign { seed, levels })
+    }
+
+    /// Easiest candidate of the level not easier than the previous level, walls are raised
+    /// and the candidates generated again while none is hard enough
+    fn pick_level(
+        &self,
+        rng: &mut ChaCha8Rng,
+        index: usize,
+        generator_type: MazeGeneratorType,
+        mut options: GeneratorOptions,
+        previous: f64,
+    ) -> Result<(GeneratorOptions, f64, GeneratedMaze), GeneratorError> {
+        let mut hardest: Option<f64> = None;
+
+        for raise in 0..=MAX_WALL_RAISES {
+            if raise > 0 {
+                let shift = |range: &Range<usize>| range.start + WALL_RAISE..range.end + WALL_RAISE;
+                options.wall_range = shift(&options.wall_range);
+                options.max_soft_wall_range = shift(&options.max_soft_wall_range);
+            }
+
+            let generator: Box<dyn MazeGenerator> = match generator_type {
+                MazeGeneratorType::Waterfall => match WaterfallGenerator::new(options.clone()) {
+                    Ok(generator) => Box::new(generator),
+                    // the board has no room for more walls
+                    Err(_) if raise > 0 => break,
+                    Err(error) => return Err(error),
+                },
+                _ => match VanillaGenerator::new(options.clone()) {
+                    Ok(generator) => Box::new(generator),
+                    Err(_) if raise > 0 => break,
+                    Err(error) => return Err(error),
+                },
+            };
+
+            let mut picked: Option<(f64, GeneratedMaze)> = None;
+            for attempt in 0..MAX_ATTEMPTS {
+                if attempt >= CANDIDATE_COUNT && picked.is_some() {
+                    break;
+                }
+
+                let maze = generator.generate_with_seed(rng.gen())?;
+                let difficulty = measure_difficulty(&maze.config, DifficultyMetric::ScoreRatio)?;
+
+                if difficulty >= previous
+                    && picked
+                        .as_ref()
+                        .is_none_or(|(easiest, _)| difficulty < *easiest)
+                {
+                    picked = Some((difficulty, maze));
+                }
+                hardest = Some(hardest.map_or(difficulty, |hardest| hardest.max(difficulty)));
+            }
+
+            if let Some((difficulty, maze)) = picked {
+                return Ok((options, difficulty, maze));
+            }
+        }
+
+        Err(GeneratorError::CampaignDifficultyNotReached {
+            level: index,
+            previous,
+            hardest: hardest.unwrap_or(0.0),
+        })
+    }
+
+    /// Lesson of the level and the number of lessons introduced up to it, lessons are spread
+    /// evenly and the levels between them are practice. Level introducing several lessons
+    /// is named after the last of them
+    fn get_lesson(&self, index: usize) -> (CampaignLesson, usize) {
+        let stage = (0..LESSONS.len())
+            .take_while(|lesson| lesson * self.level_count / LESSONS.len() <= index)
+            .count();
+        let introduced_at = (stage - 1) * self.level_count / LESSONS.len();
+
+        match introduced_at == index {
+            true => (LESSONS[stage - 1], stage),
+            false => (CampaignLesson::Practice, stage),
+        }
+    }
+
+    /// Board and counts grow with the progress through the campaign, features come with the lessons
+    fn get_options(&self, index: usize, stage: usize) -> (MazeGeneratorType, GeneratorOptions) {
+        let progress = index as f64 / (self.level_count - 1).max(1) as f64;
+        let scale =
+            |from: usize, to: usize| from + ((to - from) as f64 * progress).round() as usize;
+
+        let generator_type = match stage >= 5 {
+            true => MazeGeneratorType::Waterfall,
+            false => MazeGeneratorType::Vanilla,
+        };
+        let checkpoint_count = [0, 1, 2, 2, 3][stage - 1];
+        let wall_count = scale(2, 14);
+        let max_soft_wall_count = scale(4, 16);
+
+        let mut options = GeneratorOptions {
+            col_count: scale(8, 18),
+            row_count: scale(6, 12),
+            checkpoint_range: checkpoint_count..checkpoint_count + 1,
+            wall_range: wall_count..wall_count + 1,
+            max_soft_wall_range: max_soft_wall_count..max_soft_wall_count + 1,
+            ..GeneratorOptions::default_for(generator_type)
+        };
+        // waterfall is entered over the whole top row and exited over the whole bottom row
+        if generator_type == MazeGeneratorType::Vanilla {
+            options.entrypoint_range = match stage >= 4 {
+                true => 2..4,
+                false => 1..2,
+            };
+            // a single exit keeps the checkpoint count growing
+            options.exit_range = 1..2;
+        }
+
+        (generator_type, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use super::*;
+
+    #[test]
+    fn test_campaign_generator() -> Result<(), Box<dyn Error>> {
+        let generator = CampaignGenerator::new(5)?;
+        let campaign = generator.generate_with_seed(42)?;
+        let levels = &campaign.levels;
+
+        assert_eq!(campaign.seed, 42);
+        assert_eq!(levels.len(), 5);
+
+        for pair in levels.windows(2) {
+            let (previous, next) = (&pair[0].maze.config, &pair[1].maze.config);
+
+            assert!(next.col_count >= previous.col_count);
+            assert!(next.row_count >= previous.row_count);
+            assert!(next.checkpoints.len() >= previous.checkpoints.len());
+            assert!(pair[1].difficulty >= pair[0].difficulty);
+        }
+
+        // campaign as long as the lesson list introduces a lesson every level
+        let lessons: Vec<CampaignLesson> = levels.iter().map(|level| level.lesson).collect();
+        assert_eq!(lessons, LESSONS.to_vec());
+        assert_eq!(levels[0].maze.config.checkpoints.len(), 1);
+        assert!(levels[3].maze.config.entrypoints.len() >= 2);
+        assert_eq!(levels[4].generator_type, MazeGeneratorType::Waterfall);
+
+        // a level is reproduced from its options and seed
+        let level = &levels[3];
+        let level_generator = VanillaGenerator::new(level.options.clone())?;
+        assert_eq!(
+            level_generator.generate_with_seed(level.maze.seed)?,
+            level.maze
+        );
+
+        assert_eq!(generator.generate_with_seed(42)?, campaign);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_campaign_difficulty_never_drops() -> Result<(), Box<dyn Error>> {
+        let generator = CampaignGenerator::new(3)?;
+
+        for seed in 0..4 {
+            let campaign = generator.generate_with_seed(seed)?;
+
+            for pair in campaign.levels.windows(2) {
+                assert!(
+                    pair[1].difficulty >= pair[0].difficulty,
+                    "seed {} has level of difficulty {} after {}",
+                    seed,
+                    pair[1].difficulty,
+                    pair[0].difficulty
+                );
+            }
+            // raised walls are kept in the options reproducing the level
+            for level in campaign.levels.iter() {
+                let config = &level.maze.config;
+                assert!(level.options.wall_range.contains(&config.walls.len()));
+                assert!(level
+                    .options
+                    .max_soft_wall_range
+                    .contains(&(config.max_soft_wall_count as usize)));
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_campaign_lessons() -> Result<(), Box<dyn Error>> {
+        for level_count in [5, 6, 11, 12, 30] {
+            let generator = CampaignGenerator::new(level_count)?;
+            let lessons: Vec<(CampaignLesson, usize)> = (0..level_count)
+                .map(|index| generator.get_lesson(index))
+                .collect();
+
+            assert_eq!(lessons[0], (CampaignLesson::FirstMaze, 1));
+            for lesson in LESSONS.iter() {
+                assert_eq!(
+                    lessons.iter().filter(|(l, _)| l == lesson).count(),
+                    1,
+                    "{:?} in campaign of {} levels",
+                    lesson,
+                    level_count
+                );
+            }
+            assert!(lessons.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+        }
+
+        // shorter campaigns introduce several lessons at once and still reach the last one
+        let lessons = |level_count: usize| -> Result<Vec<(CampaignLesson, usize)>, GeneratorError> {
+            let generator = CampaignGenerator::new(level_count)?;
+            Ok((0..level_count)
+                .map(|index| generator.get_lesson(index))
+                .collect())
+        };
+        assert_eq!(lessons(1)?, vec![(CampaignLesson::FirstWaterfall, 5)]);
+        assert_eq!(
+            lessons(2)?,
+            vec![
+                (CampaignLesson::MultipleCheckpoints, 3),
+                (CampaignLesson::FirstWaterfall, 5)
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_campaign_generator_of_any_length() -> Result<(), Box<dyn Error>> {
+        for level_count in [1, 3] {
+            let campaign = CampaignGenerator::new(level_count)?.generate_with_seed(7)?;
+            let levels = &campaign.levels;
+
+            assert_eq!(levels.len(), level_count);
+            let last = levels.last().ok_or("campaign should have levels")?;
+            assert_eq!(last.generator_type, MazeGeneratorType::Waterfall);
+            // waterfall takes its entrypoints from the whole top row
+            assert_eq!(
+                last.maze.config.entrypoints.len(),
+                last.maze.config.col_count
+            );
+        }
+
+        assert!(matches!(
+            CampaignGenerator::new(0),
+            Err(GeneratorError::InvalidCampaignLength { level_count: 0 })
+        ));
+
+        Ok(())
+    }
+}
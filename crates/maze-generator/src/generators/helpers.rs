@@ -1,41 +1,36 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, ops::Range};
 
 use maze_core::{Checkpoint, Maze, MazeConfig, Position};
-use maze_runner::MazeRunner;
-use rand::distributions::uniform::{SampleRange, SampleUniform};
-use rand::seq::SliceRandom;
-use rand::{thread_rng, Rng};
+use maze_runner::{
+    analysis::{disconnecting_cells, reachability},
+    upper_bound, MazeRunner, WallPlacement,
+};
+use rand::{seq::SliceRandom, Rng, RngCore};
 
 use crate::GeneratorError;
 
 pub(super) fn get_top_wall_positions(col_count: usize, row_count: usize) -> Vec<Position> {
     let x_line_range = 0..col_count;
     x_line_range
-        .map(|x| Position {
-            x,
-            y: row_count - 1,
-        })
+        .map(|x| Position::new(x, row_count - 1))
         .collect()
 }
 
 pub(super) fn get_bottom_wall_positions(col_count: usize) -> Vec<Position> {
     let x_line_range = 0..col_count;
-    x_line_range.map(|x| Position { x, y: 0 }).collect()
+    x_line_range.map(|x| Position::new(x, 0)).collect()
 }
 
 pub(super) fn get_right_wall_positions(col_count: usize, row_count: usize) -> Vec<Position> {
     let y_line_range = 0..row_count;
     y_line_range
-        .map(|y| Position {
-            x: col_count - 1,
-            y,
-        })
+        .map(|y| Position::new(col_count - 1, y))
         .collect()
 }
 
 pub(super) fn get_left_wall_positions(row_count: usize) -> Vec<Position> {
     let y_line_range = 0..row_count;
-    y_line_range.map(|y| Position { x: 0, y }).collect()
+    y_line_range.map(|y| Position::new(0, y)).collect()
 }
 
 pub(super) fn get_empty_positions_in_rectangle(
@@ -50,7 +45,7 @@ pub(super) fn get_empty_positions_in_rectangle(
         .flat_map(|x| {
             let y_line = bottom_right.y..top_left.y;
             y_line
-                .map(move |y| Position { x, y })
+                .map(move |y| Position::new(x, y))
                 .filter(|pos| !used.contains(pos))
         })
         .collect()
@@ -64,21 +59,45 @@ pub(super) fn get_empty_positions_with_padding(
     used_positions: &[&Vec<Position>],
 ) -> Vec<Position> {
     get_empty_positions_in_rectangle(
-        Position {
-            x: padding_x,
-            y: row_count - padding_y,
-        },
-        Position {
-            x: col_count - padding_x,
-            y: padding_y,
-        },
+        Position::new(padding_x, row_count - padding_y),
+        Position::new(col_count - padding_x, padding_y),
         used_positions,
     )
 }
 
+/// Number of concentric rings of the board, the last one is in its center
+pub(super) fn get_ring_count(col_count: usize, row_count: usize) -> usize {
+    col_count.min(row_count).div_ceil(2)
+}
+
+/// Ring of the position, it is its distance from the closest board side
+pub(super) fn get_ring(col_count: usize, row_count: usize, position: &Position) -> usize {
+    position
+        .x
+        .min(position.y)
+        .min(col_count - 1 - position.x)
+        .min(row_count - 1 - position.y)
+}
+
+pub(super) fn get_ring_positions(
+    col_count: usize,
+    row_count: usize,
+    rings: Range<usize>,
+    used_positions: &[&Vec<Position>],
+) -> Vec<Position> {
+    get_empty_positions_in_rectangle(
+        Position::new(0, row_count),
+        Position::new(col_count, 0),
+        used_positions,
+    )
+    .into_iter()
+    .filter(|position| rings.contains(&get_ring(col_count, row_count, position)))
+    .collect()
+}
+
 pub(super) fn get_checkpoints(
-    checkpoint_positions: &Vec<Position>,
-    exit_positions: &Vec<Position>,
+    checkpoint_positions: &[Position],
+    exit_positions: &[Position],
 ) -> Vec<Checkpoint> {
     checkpoint_positions
         .iter()
@@ -95,49 +114,268 @@ pub(super) fn get_checkpoints(
         .collect()
 }
 
-pub(super) fn is_solvable(
-    config: &MazeConfig,
-    walls: &Vec<Position>,
-) -> Result<bool, GeneratorError> {
+/// Maze is solvable if its exit can be reached without any soft walls
+pub(crate) fn is_solvable(config: &MazeConfig) -> Result<bool, GeneratorError> {
     let maze = Maze::new(config)?;
-    let run = MazeRunner::new(&maze).run(&walls)?;
-    Ok(run.is_some())
+    Ok(MazeRunner::new(&maze).run_score(&vec![])?.is_some())
 }
 
-pub(super) fn get_random_number_in_range<T, R>(range: R) -> T
-where
-    T: SampleUniform,
-    R: SampleRange<T>,
-{
-    thread_rng().gen_range(range)
+/// Samples through `u32`, sampling `usize` directly would differ between 64-bit targets and wasm
+pub(crate) fn get_random_number_in_range(rng: &mut dyn RngCore, range: Range<usize>) -> usize {
+    rng.gen_range(range.start as u32..range.end as u32) as usize
 }
 
-pub(super) fn get_random_shuffle(positions: &Vec<Position>) -> Vec<Position> {
-    let mut copy = positions.clone();
-    copy.shuffle(&mut thread_rng());
+pub(super) fn get_random_shuffle<T: Clone>(rng: &mut dyn RngCore, items: &[T]) -> Vec<T> {
+    let mut copy = items.to_vec();
+    copy.shuffle(rng);
     copy
 }
 
-pub(super) fn get_random_positions(positions: &Vec<Position>, n: usize) -> Vec<Position> {
-    get_random_shuffle(positions).into_iter().take(n).collect()
+pub(super) fn get_random_positions(
+    rng: &mut dyn RngCore,
+    positions: &[Position],
+    n: usize,
+) -> Vec<Position> {
+    get_random_shuffle(rng, positions)
+        .into_iter()
+        .take(n)
+        .collect()
 }
 
 pub(super) fn get_random_solvable_walls(
+    rng: &mut dyn RngCore,
+    config: &MazeConfig,
+    empty_positions: &[Position],
+    wall_count: usize,
+) -> Result<Vec<Position>, GeneratorError> {
+    let groups: Vec<Vec<Position>> = empty_positions
+        .iter()
+        .map(|position| vec![*position])
+        .collect();
+
+    get_random_solvable_wall_groups(rng, config, &groups, wall_count)
+}
+
+/// Same as `get_random_solvable_walls` with walls placed in groups, either the whole group is walled or none of it
+pub fn get_random_solvable_wall_groups(
+    rng: &mut dyn RngCore,
     config: &MazeConfig,
-    empty_positions: &Vec<Position>,
+    groups: &[Vec<Position>],
     wall_count: usize,
 ) -> Result<Vec<Position>, GeneratorError> {
+    let groups = get_random_shuffle(rng, groups);
+
+    let maze = Maze::new(config)?;
+    let runner = MazeRunner::new(&maze);
+    let mut placement = WallPlacement::new(&maze);
+    let (mut baseline, mut path) = match get_solved_path(&runner, placement.walls())? {
+        Some(solved) => solved,
+        None => return Ok(vec![]),
+    };
+
+    for group in groups.into_iter() {
+        let wall_len = placement.walls().len();
+        if wall_len >= wall_count {
+            break;
+        }
+        if wall_len + group.len() > wall_count
+            || group
+                .iter()
+                .any(|position| placement.walls().contains(position))
+        {
+            continue;
+        }
+
+        // nobody can make use of a dead cell, so walling it would be wasted
+        if group.iter().any(|position| placement.is_dead(position)) {
+            continue;
+        }
+
+        placement.push_walls(&group)?;
+
+        // walls off the solved path keep it walkable and can't make any path shorter,
+        // so the maze only needs to be run again when the group blocks the path
+        let solved = if group.iter().any(|position| path.contains(position)) {
+            get_solved_path(&runner, placement.walls())?
+        } else {
+            Some((baseline, path.clone()))
+        };
+
+        match solved {
+            // maze has potential if there is a chance that some soft wall placement improves the baseline score
+            Some((score, solved_path)) if placement.exceeds_upper_bound(score) => {
+                baseline = score;
+                path = solved_path;
+            }
+            _ => placement.pop_walls(group.len()),
+        }
+    }
+
+    Ok(placement.walls().clone())
+}
+
+/// Placement rerunning the whole maze after every wall, the incremental one has to match it,
+/// public only for the generation bench to time it against
+#[doc(hidden)]
+pub fn get_reference_solvable_wall_groups(
+    rng: &mut dyn RngCore,
+    config: &MazeConfig,
+    groups: &[Vec<Position>],
+    wall_count: usize,
+) -> Result<Vec<Position>, GeneratorError> {
+    let has_score_potential = |walls: &[Position]| -> Result<bool, GeneratorError> {
+        let mut config = config.clone();
+        config.walls.extend_from_slice(walls);
+
+        let maze = Maze::new(&config)?;
+        let baseline = MazeRunner::new(&maze).run_score(&vec![])?;
+        Ok(matches!(baseline, Some(score) if upper_bound(&maze) > score))
+    };
+
+    let maze = Maze::new(config)?;
     let mut walls = vec![];
-    for position in get_random_shuffle(empty_positions).into_iter() {
+    let mut disconnecting = disconnecting_cells(&maze, &walls)?;
+    let mut dead = reachability(&maze, &walls)?.dead;
+
+    for group in get_random_shuffle(rng, groups).into_iter() {
         if walls.len() >= wall_count {
             break;
         }
+        if walls.len() + group.len() > wall_count
+            || group.iter().any(|position| walls.contains(position))
+            || group
+                .iter()
+                .any(|position| disconnecting.contains(position))
+            || group
+                .iter()
+                .any(|position| dead[position.floor_index()][position.x][position.y])
+        {
+            continue;
+        }
 
-        walls.push(position);
-        if !is_solvable(&config, &walls)? {
-            walls.pop();
+        walls.extend_from_slice(&group);
+        if !has_score_potential(&walls)? {
+            walls.truncate(walls.len() - group.len());
+        } else if walls.len() < wall_count {
+            disconnecting = disconnecting_cells(&maze, &walls)?;
+            dead = reachability(&maze, &walls)?.dead;
         }
     }
 
     Ok(walls)
 }
+
+/// Score and tiles of the shortest path with the walls, `None` if the walls make the maze unsolvable
+fn get_solved_path(
+    runner: &MazeRunner,
+    walls: &[Position],
+) -> Result<Option<(u32, HashSet<Position>)>, GeneratorError> {
+    match runner.run(&walls.to_vec())? {
+        Some(run) => Ok(Some((
+            run.score(),
+            run.solved_path()?.into_iter().collect(),
+        ))),
+        None => Ok(None),
+    }
+}
+
+/// Generates mazes of the first seeds, passes every config to the preset specific assertions
+/// and checks the maze is solvable
+#[cfg(test)]
+pub(super) fn check_generated_mazes(
+    generator: &dyn super::MazeGenerator,
+    check: impl Fn(&MazeConfig) -> Result<(), Box<dyn std::error::Error>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    for seed in 0..4 {
+        let config = generator.generate_with_seed(seed)?.config;
+        check(&config)?;
+
+        let maze = Maze::new(&config)?;
+        assert!(MazeRunner::new(&maze).run(&vec![])?.is_some());
+    }
+
+    Ok(())
+}
+
+/// Level of the checkpoints the maze is exited over
+#[cfg(test)]
+pub(super) fn get_exit_level(config: &MazeConfig) -> Result<i32, Box<dyn std::error::Error>> {
+    let level = config
+        .checkpoints
+        .iter()
+        .map(|checkpoint| checkpoint.level)
+        .max()
+        .ok_or("maze should have checkpoints")?;
+
+    Ok(level)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use maze_core::TileKind;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use crate::{create_generator, GeneratorOptions, MazeGeneratorType};
+
+    use super::*;
+
+    #[test]
+    fn test_solvable_wall_groups_match_reference() -> Result<(), Box<dyn Error>> {
+        let generator = create_generator(
+            MazeGeneratorType::Vanilla,
+            GeneratorOptions {
+                col_count: 8,
+                row_count: 6,
+                wall_range: 0..1,
+                checkpoint_padding: 1,
+                ..GeneratorOptions::default_for(MazeGeneratorType::Vanilla)
+            },
+        )?;
+
+        for seed in 0..20 {
+            let mut config = generator.generate_with_seed(seed)?.config;
+            let wall_count = 6 + seed as usize % 12;
+            config.max_soft_wall_count = wall_count as u32;
+
+            let maze = Maze::new(&config)?;
+            let empty_positions: Vec<Position> = (0..config.col_count)
+                .flat_map(|x| (0..config.row_count).map(move |y| Position::new(x, y)))
+                .filter(|position| maze.floors()[0][position.x][position.y] == TileKind::Empty)
+                .collect();
+
+            let singles: Vec<Vec<Position>> = empty_positions
+                .iter()
+                .map(|position| vec![*position])
+                .collect();
+            let pairs: Vec<Vec<Position>> = empty_positions
+                .iter()
+                .filter(|position| {
+                    empty_positions.contains(&Position::new(position.x, position.y + 1))
+                })
+                .map(|position| vec![*position, Position::new(position.x, position.y + 1)])
+                .collect();
+
+            for groups in [singles, pairs] {
+                let walls = get_random_solvable_wall_groups(
+                    &mut ChaCha8Rng::seed_from_u64(seed),
+                    &config,
+                    &groups,
+                    wall_count,
+                )?;
+                let expected = get_reference_solvable_wall_groups(
+                    &mut ChaCha8Rng::seed_from_u64(seed),
+                    &config,
+                    &groups,
+                    wall_count,
+                )?;
+
+                assert_eq!(walls, expected, "walls differ for seed {}", seed);
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,239 @@
+use std::collections::HashSet;
+
+use maze_core::{MazeConfig, Position, ScoringRuleType};
+use rand::{Rng, RngCore};
+
+use crate::{GeneratorError, GeneratorOptions};
+
+use super::{
+    helpers::{
+        get_checkpoints, get_empty_positions_with_padding, get_left_wall_positions,
+        get_random_number_in_range, get_random_positions, get_random_solvable_wall_groups,
+        get_right_wall_positions,
+    },
+    MazeGenerator,
+};
+
+/// Shapes of islands as offsets from their top left tile
+const ISLAND_SHAPES: [&[(usize, usize)]; 4] = [
+    &[(0, 0), (1, 0), (0, 1), (1, 1)],
+    &[(0, 0), (1, 0), (2, 0)],
+    &[(0, 0), (0, 1), (0, 2)],
+    &[(0, 0), (1, 0), (0, 1)],
+];
+
+pub(crate) struct IslandsGenerator {
+    options: GeneratorOptions,
+}
+
+impl IslandsGenerator {
+    pub(crate) fn new(options: GeneratorOptions) -> Result<Self, GeneratorError> {
+        options.validate(options.col_count, options.row_count)?;
+        options.validate_sides(options.row_count, options.row_count)?;
+
+        Ok(Self { options })
+    }
+}
+
+impl MazeGenerator for IslandsGenerator {
+    fn generate_with_rng(&self, rng: &mut dyn RngCore) -> Result<MazeConfig, GeneratorError> {
+        let options = &self.options;
+        let entrypoint_count = get_random_number_in_range(rng, options.entrypoint_range.clone());
+        let checkpoint_count = get_random_number_in_range(rng, options.checkpoint_range.clone());
+        let exit_count = get_random_number_in_range(rng, options.exit_range.clone());
+        let wall_count = get_random_number_in_range(rng, options.wall_range.clone());
+        let max_soft_wall_count =
+            get_random_number_in_range(rng, options.max_soft_wall_range.clone());
+
+        let entrypoint_positions = get_random_positions(
+            rng,
+            &get_left_wall_positions(options.row_count),
+            entrypoint_count,
+        );
+        let exit_positions = get_random_positions(
+            rng,
+            &get_right_wall_positions(options.col_count, options.row_count),
+            exit_count,
+        );
+
+        let empty_positions = get_empty_positions_with_padding(
+            options.col_count,
+            options.row_count,
+            options.checkpoint_padding,
+            0,
+            &[&entrypoint_positions, &exit_positions][..],
+        );
+
+        let checkpoint_positions = get_random_positions(rng, &empty_positions, checkpoint_count);
+
+        let empty_positions = get_empty_positions_with_padding(
+            options.col_count,
+            options.row_count,
+            options.wall_padding,
+            0,
+            &[
+                &entrypoint_positions,
+                &exit_positions,
+                &checkpoint_positions,
+            ][..],
+        );
+
+        let checkpoints = get_checkpoints(&checkpoint_positions, &exit_positions);
+
+        let mut config = MazeConfig {
+            col_count: options.col_count,
+            row_count: options.row_count,
+            floor_count: 1,
+            max_soft_wall_count: wall_count as u32,
+            entrypoints: entrypoint_positions,
+            checkpoints,
+            walls: vec![],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
+        };
+
+        let islands = get_islands(rng, &empty_positions);
+        let walls = get_random_solvable_wall_groups(rng, &config, &islands, wall_count)?;
+
+        config.max_soft_wall_count = max_soft_wall_count as u32;
+        config.walls = walls;
+
+        Ok(config)
+    }
+}
+
+/// Island of a random shape at every empty position, islands reaching out of the empty positions are dropped
+fn get_islands(rng: &mut dyn RngCore, empty_positions: &[Position]) -> Vec<Vec<Position>> {
+    let empty: HashSet<&Position> = empty_positions.iter().collect();
+
+    empty_positions
+        .iter()
+        .filter_map(|position| {
+            let shape = ISLAND_SHAPES[rng.gen_range(0..ISLAND_SHAPES.len() as u32) as usize];
+            let island: Vec<Position> = shape
+                .iter()
+                .map(|(x, y)| Position::new(position.x + x, position.y + y))
+                .collect();
+
+            island
+                .iter()
+                .all(|position| empty.contains(position))
+                .then_some(island)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use crate::MazeGeneratorType;
+
+    use super::super::helpers::check_generated_mazes;
+    use super::*;
+
+    fn get_options() -> GeneratorOptions {
+        GeneratorOptions::default_for(MazeGeneratorType::Islands)
+    }
+
+    #[test]
+    fn test_islands_generator() -> Result<(), Box<dyn Error>> {
+        let generator = IslandsGenerator::new(get_options())?;
+        for _ in 0..5 {
+            let config = generator.generate();
+            assert!(config.is_ok());
+        }
+
+        Ok(())
+    }
+
+    /// Walls split into groups of tiles connected side by side
+    fn get_wall_components(walls: &[Position]) -> Vec<HashSet<Position>> {
+        let mut left: HashSet<Position> = walls.iter().copied().collect();
+        let mut components = vec![];
+
+        while let Some(&start) = left.iter().next() {
+            left.remove(&start);
+            let mut component = HashSet::from([start]);
+            let mut stack = vec![start];
+
+            while let Some(wall) = stack.pop() {
+                let neighbors = [
+                    Position::new(wall.x + 1, wall.y),
+                    Position::new(wall.x.wrapping_sub(1), wall.y),
+                    Position::new(wall.x, wall.y + 1),
+                    Position::new(wall.x, wall.y.wrapping_sub(1)),
+                ];
+                for neighbor in neighbors {
+                    if left.remove(&neighbor) {
+                        component.insert(neighbor);
+                        stack.push(neighbor);
+                    }
+                }
+            }
+            components.push(component);
+        }
+
+        components
+    }
+
+    /// Whether the tiles split into islands of the shapes without any tile left over
+    fn is_union_of_islands(tiles: &HashSet<Position>) -> bool {
+        // the top left tile left over is the top left tile of its island
+        let first = match tiles.iter().min_by_key(|position| (position.y, position.x)) {
+            Some(first) => *first,
+            None => return true,
+        };
+
+        ISLAND_SHAPES.iter().any(|shape| {
+            let island: Vec<Position> = shape
+                .iter()
+                .map(|(x, y)| Position::new(first.x + x, first.y + y))
+                .collect();
+
+            island.iter().all(|position| tiles.contains(position))
+                && is_union_of_islands(
+                    &tiles
+                        .iter()
+                        .filter(|position| !island.contains(position))
+                        .copied()
+                        .collect(),
+                )
+        })
+    }
+
+    #[test]
+    fn test_islands_generator_placement() -> Result<(), Box<dyn Error>> {
+        let options = get_options();
+        let generator = IslandsGenerator::new(options.clone())?;
+
+        check_generated_mazes(&generator, |config| {
+            assert!(!config.walls.is_empty());
+            // placement stops before going over the wall count drawn from the range
+            assert!(config.walls.len() < options.wall_range.end);
+            assert!(config.entrypoints.iter().all(|position| position.x == 0));
+
+            // no wall stands alone, walls touching each other are whole islands
+            for component in get_wall_components(&config.walls) {
+                assert!(component.len() >= 3);
+                assert!(is_union_of_islands(&component), "{:?}", component);
+            }
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_islands_generator_with_invalid_options() {
+        assert!(matches!(
+            IslandsGenerator::new(GeneratorOptions {
+                exit_range: 0..2,
+                ..get_options()
+            }),
+            Err(GeneratorError::InvalidRange {
+                name: "exit_range",
+                ..
+            })
+        ));
+    }
+}
@@ -1,12 +1,41 @@
 use maze_core::MazeConfig;
+use rand::{thread_rng, Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 
 use crate::GeneratorError;
 
-mod helpers;
+pub(crate) mod helpers;
 
+pub(crate) mod arena;
+pub(crate) mod campaign;
+pub(crate) mod islands;
+pub(crate) mod spiral;
+pub(crate) mod symmetric;
+pub(crate) mod targeted;
+pub(crate) mod template;
 pub(crate) mod vanilla;
 pub(crate) mod waterfall;
 
+/// Generated maze config together with the seed it was generated from
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratedMaze {
+    pub seed: u64,
+    pub config: MazeConfig,
+}
+
 pub trait MazeGenerator {
-    fn generate(&self) -> Result<MazeConfig, GeneratorError>;
+    /// Generates maze from a random seed, the seed is part of the output so the maze can be reproduced
+    fn generate(&self) -> Result<GeneratedMaze, GeneratorError> {
+        self.generate_with_seed(thread_rng().gen())
+    }
+
+    /// Same seed always yields the same maze on every platform, including wasm,
+    /// as long as the crate version is the same
+    fn generate_with_seed(&self, seed: u64) -> Result<GeneratedMaze, GeneratorError> {
+        let config = self.generate_with_rng(&mut ChaCha8Rng::seed_from_u64(seed))?;
+        Ok(GeneratedMaze { seed, config })
+    }
+
+    /// Generates maze drawing all randomness from the given generator
+    fn generate_with_rng(&self, rng: &mut dyn RngCore) -> Result<MazeConfig, GeneratorError>;
 }
@@ -0,0 +1,199 @@
+use maze_core::{MazeConfig, Position, ScoringRuleType};
+use rand::RngCore;
+
+use crate::{GeneratorError, GeneratorOptions};
+
+use super::{
+    helpers::{
+        get_checkpoints, get_random_number_in_range, get_random_positions, get_random_shuffle,
+        get_random_solvable_walls, get_ring_count, get_ring_positions,
+    },
+    MazeGenerator,
+};
+
+pub(crate) struct SpiralGenerator {
+    options: GeneratorOptions,
+}
+
+impl SpiralGenerator {
+    pub(crate) fn new(options: GeneratorOptions) -> Result<Self, GeneratorError> {
+        let (col_count, row_count) = (options.col_count, options.row_count);
+        let inner_ring = get_ring_count(col_count, row_count).saturating_sub(1);
+        let padding = options.checkpoint_padding.max(options.wall_padding).max(1);
+        if inner_ring < padding {
+            return Err(GeneratorError::BoardTooSmall {
+                col_count,
+                row_count,
+            });
+        }
+
+        options.validate_sides(
+            get_ring_positions(col_count, row_count, 0..1, &[]).len(),
+            get_ring_positions(col_count, row_count, inner_ring..inner_ring + 1, &[]).len(),
+        )?;
+        // every checkpoint has a ring of its own
+        options.validate_counts(inner_ring - options.checkpoint_padding.max(1))?;
+
+        Ok(Self { options })
+    }
+}
+
+impl MazeGenerator for SpiralGenerator {
+    fn generate_with_rng(&self, rng: &mut dyn RngCore) -> Result<MazeConfig, GeneratorError> {
+        let options = &self.options;
+        let (col_count, row_count) = (options.col_count, options.row_count);
+        let inner_ring = get_ring_count(col_count, row_count) - 1;
+
+        let entrypoint_count = get_random_number_in_range(rng, options.entrypoint_range.clone());
+        let checkpoint_count = get_random_number_in_range(rng, options.checkpoint_range.clone());
+        let exit_count = get_random_number_in_range(rng, options.exit_range.clone());
+        let wall_count = get_random_number_in_range(rng, options.wall_range.clone());
+        let max_soft_wall_count =
+            get_random_number_in_range(rng, options.max_soft_wall_range.clone());
+
+        let entrypoint_positions = get_random_positions(
+            rng,
+            &get_ring_positions(col_count, row_count, 0..1, &[]),
+            entrypoint_count,
+        );
+        let exit_positions = get_random_positions(
+            rng,
+            &get_ring_positions(col_count, row_count, inner_ring..inner_ring + 1, &[]),
+            exit_count,
+        );
+
+        // levels grow towards the center, so the outer rings are visited first
+        let rings: Vec<usize> = (options.checkpoint_padding.max(1)..inner_ring).collect();
+        let mut checkpoint_rings: Vec<usize> = get_random_shuffle(rng, &rings)
+            .into_iter()
+            .take(checkpoint_count)
+            .collect();
+        checkpoint_rings.sort_unstable();
+
+        let checkpoint_positions: Vec<Position> = checkpoint_rings
+            .into_iter()
+            .flat_map(|ring| {
+                let positions = get_ring_positions(col_count, row_count, ring..ring + 1, &[]);
+                get_random_positions(rng, &positions, 1)
+            })
+            .collect();
+
+        let empty_positions = get_ring_positions(
+            col_count,
+            row_count,
+            options.wall_padding..inner_ring,
+            &[&entrypoint_positions, &checkpoint_positions][..],
+        );
+
+        let checkpoints = get_checkpoints(&checkpoint_positions, &exit_positions);
+
+        let mut config = MazeConfig {
+            col_count,
+            row_count,
+            floor_count: 1,
+            max_soft_wall_count: wall_count as u32,
+            entrypoints: entrypoint_positions,
+            checkpoints,
+            walls: vec![],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
+        };
+
+        let walls = get_random_solvable_walls(rng, &config, &empty_positions, wall_count)?;
+
+        config.max_soft_wall_count = max_soft_wall_count as u32;
+        config.walls = walls;
+
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use crate::MazeGeneratorType;
+
+    use super::super::helpers::{check_generated_mazes, get_exit_level, get_ring};
+    use super::*;
+
+    fn get_options() -> GeneratorOptions {
+        GeneratorOptions::default_for(MazeGeneratorType::Spiral)
+    }
+
+    #[test]
+    fn test_spiral_generator() -> Result<(), Box<dyn Error>> {
+        let generator = SpiralGenerator::new(get_options())?;
+        for _ in 0..5 {
+            let config = generator.generate();
+            assert!(config.is_ok());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_spiral_generator_placement() -> Result<(), Box<dyn Error>> {
+        let generator = SpiralGenerator::new(get_options())?;
+        let ring = |position: &Position| get_ring(13, 13, position);
+
+        check_generated_mazes(&generator, |config| {
+            let exit_level = get_exit_level(config)?;
+
+            assert!(config
+                .entrypoints
+                .iter()
+                .all(|position| ring(position) == 0));
+
+            let mut checkpoints = config.checkpoints.clone();
+            checkpoints.sort_by_key(|checkpoint| checkpoint.level);
+
+            let mut previous_ring = 0;
+            for checkpoint in checkpoints {
+                let checkpoint_ring = ring(&checkpoint.position);
+                if checkpoint.level == exit_level {
+                    assert_eq!(checkpoint_ring, 6);
+                } else {
+                    assert!(checkpoint_ring > previous_ring);
+                    previous_ring = checkpoint_ring;
+                }
+            }
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_spiral_generator_with_invalid_options() {
+        assert!(matches!(
+            SpiralGenerator::new(GeneratorOptions {
+                col_count: 20,
+                row_count: 3,
+                ..get_options()
+            }),
+            Err(GeneratorError::BoardTooSmall { .. })
+        ));
+        assert!(matches!(
+            SpiralGenerator::new(GeneratorOptions {
+                checkpoint_range: 3..6,
+                ..get_options()
+            }),
+            Err(GeneratorError::TooManyTiles {
+                name: "checkpoint_range",
+                requested: 5,
+                available: 4
+            })
+        ));
+        assert!(matches!(
+            SpiralGenerator::new(GeneratorOptions {
+                exit_range: 1..3,
+                ..get_options()
+            }),
+            Err(GeneratorError::TooManyTiles {
+                name: "exit_range",
+                requested: 2,
+                available: 1
+            })
+        ));
+    }
+}
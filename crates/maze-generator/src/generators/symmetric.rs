@@ -0,0 +1,299 @@
+use maze_core::{Checkpoint, MazeConfig, Position, ScoringRuleType};
+use rand::RngCore;
+
+use crate::{GeneratorError, GeneratorOptions};
+
+use super::{
+    helpers::{
+        get_checkpoints, get_empty_positions_in_rectangle, get_left_wall_positions,
+        get_random_number_in_range, get_random_positions, get_random_solvable_wall_groups,
+        get_right_wall_positions,
+    },
+    MazeGenerator,
+};
+
+/// Generates the left half of the board and completes the right one as its image,
+/// so both sides have exactly the same chances
+pub(crate) struct SymmetricGenerator {
+    options: GeneratorOptions,
+    symmetry: Symmetry,
+}
+
+/// How the right half of a Symmetric board is made of the left one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Symmetry {
+    /// Mirrored over the vertical axis in the middle of the board
+    #[default]
+    Mirror,
+    /// Rotated by half a turn around the center of the board
+    Point,
+}
+
+impl Symmetry {
+    /// Position on the other side of the board matching the position
+    pub fn image(self, position: &Position, col_count: usize, row_count: usize) -> Position {
+        match self {
+            Symmetry::Mirror => Position::new(col_count - 1 - position.x, position.y),
+            Symmetry::Point => {
+                Position::new(col_count - 1 - position.x, row_count - 1 - position.y)
+            }
+        }
+    }
+}
+
+impl SymmetricGenerator {
+    pub(crate) fn new(
+        options: GeneratorOptions,
+        symmetry: Symmetry,
+    ) -> Result<Self, GeneratorError> {
+        let exit_x = get_exit_column(&options);
+        let padding = options.checkpoint_padding.max(options.wall_padding).max(1);
+        if options.row_count == 0 || exit_x <= padding {
+            return Err(GeneratorError::BoardTooSmall {
+                col_count: options.col_count,
+                row_count: options.row_count,
+            });
+        }
+
+        options.validate_sides(options.row_count, options.row_count)?;
+        options
+            .validate_counts((exit_x - options.checkpoint_padding.max(1)) * options.row_count)?;
+
+        Ok(Self { options, symmetry })
+    }
+}
+
+impl MazeGenerator for SymmetricGenerator {
+    fn generate_with_rng(&self, rng: &mut dyn RngCore) -> Result<MazeConfig, GeneratorError> {
+        let options = &self.options;
+        let (col_count, row_count) = (options.col_count, options.row_count);
+        let exit_x = get_exit_column(options);
+
+        let image = |position: &Position| self.symmetry.image(position, col_count, row_count);
+
+        let entrypoint_count = get_random_number_in_range(rng, options.entrypoint_range.clone());
+        let checkpoint_count = get_random_number_in_range(rng, options.checkpoint_range.clone());
+        let exit_count = get_random_number_in_range(rng, options.exit_range.clone());
+        let wall_count = get_random_number_in_range(rng, options.wall_range.clone());
+        let max_soft_wall_count =
+            get_random_number_in_range(rng, options.max_soft_wall_range.clone());
+
+        let entrypoint_positions =
+            get_random_positions(rng, &get_left_wall_positions(row_count), entrypoint_count);
+        // the middle column is the right wall of the left half
+        let exit_positions = get_random_positions(
+            rng,
+            &get_right_wall_positions(exit_x + 1, row_count),
+            exit_count,
+        );
+
+        let empty_positions = get_empty_positions_in_rectangle(
+            Position::new(options.checkpoint_padding, row_count),
+            Position::new(exit_x, 0),
+            &[&entrypoint_positions][..],
+        );
+
+        let checkpoint_positions = get_random_positions(rng, &empty_positions, checkpoint_count);
+
+        let empty_positions = get_empty_positions_in_rectangle(
+            Position::new(options.wall_padding, row_count),
+            Position::new(exit_x, 0),
+            &[&entrypoint_positions, &checkpoint_positions][..],
+        );
+
+        let entrypoints = with_images(&entrypoint_positions, image, |position| *position);
+        let checkpoints = with_images(
+            &get_checkpoints(&checkpoint_positions, &exit_positions),
+            |checkpoint| Checkpoint {
+                position: image(&checkpoint.position),
+                level: checkpoint.level,
+            },
+            |checkpoint| checkpoint.position,
+        );
+
+        let mut config = MazeConfig {
+            col_count,
+            row_count,
+            floor_count: 1,
+            max_soft_wall_count: wall_count as u32 * 2,
+            entrypoints,
+            checkpoints,
+            walls: vec![],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
+        };
+
+        // a wall never lays in the middle column, so it is always walled together with its image
+        let groups: Vec<Vec<Position>> = empty_positions
+            .iter()
+            .map(|position| vec![*position, image(position)])
+            .collect();
+        let walls = get_random_solvable_wall_groups(rng, &config, &groups, wall_count * 2)?;
+
+        config.max_soft_wall_count = max_soft_wall_count as u32;
+        config.walls = walls;
+
+        Ok(config)
+    }
+}
+
+/// Last column of the left half, exits lay in it
+fn get_exit_column(options: &GeneratorOptions) -> usize {
+    options.col_count.saturating_sub(1) / 2
+}
+
+/// Appends images of the items, images of items in the middle column may be the items themselves
+fn with_images<T: Copy>(
+    items: &[T],
+    image: impl Fn(&T) -> T,
+    position: impl Fn(&T) -> Position,
+) -> Vec<T> {
+    let mut result = items.to_vec();
+    for item in items.iter().map(image) {
+        if !result
+            .iter()
+            .any(|other| position(other) == position(&item))
+        {
+            result.push(item);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, error::Error};
+
+    use maze_core::Maze;
+    use maze_runner::MazeRunner;
+
+    use crate::MazeGeneratorType;
+
+    use super::*;
+
+    fn get_options() -> GeneratorOptions {
+        GeneratorOptions::default_for(MazeGeneratorType::Symmetric(Symmetry::Mirror))
+    }
+
+    fn is_symmetric_by(config: &MazeConfig, symmetry: Symmetry) -> bool {
+        let image =
+            |position: &Position| symmetry.image(position, config.col_count, config.row_count);
+        let same_with_images = |positions: &[Position]| {
+            let set: HashSet<Position> = positions.iter().copied().collect();
+            set == positions.iter().map(image).collect()
+        };
+        let checkpoints: HashSet<(Position, i32)> = config
+            .checkpoints
+            .iter()
+            .map(|checkpoint| (checkpoint.position, checkpoint.level))
+            .collect();
+        let checkpoint_images: HashSet<(Position, i32)> = config
+            .checkpoints
+            .iter()
+            .map(|checkpoint| (image(&checkpoint.position), checkpoint.level))
+            .collect();
+
+        same_with_images(&config.entrypoints)
+            && same_with_images(&config.walls)
+            && checkpoints == checkpoint_images
+    }
+
+    #[test]
+    fn test_symmetric_generator() -> Result<(), Box<dyn Error>> {
+        for (col_count, symmetry) in [
+            (21, Symmetry::Mirror),
+            (20, Symmetry::Mirror),
+            (21, Symmetry::Point),
+            (20, Symmetry::Point),
+        ] {
+            let generator = SymmetricGenerator::new(
+                GeneratorOptions {
+                    col_count,
+                    ..get_options()
+                },
+                symmetry,
+            )?;
+
+            for seed in 0..4 {
+                let config = generator.generate_with_seed(seed)?.config;
+                assert!(is_symmetric_by(&config, symmetry));
+
+                let maze = Maze::new(&config)?;
+                let runs = MazeRunner::new(&maze).run_entrypoints(&vec![])?;
+                let score_of = |position: &Position| {
+                    runs.iter()
+                        .find(|run| run.entrypoint == *position)
+                        .and_then(|run| run.result.as_ref().map(|result| result.score()))
+                };
+
+                // every entrypoint is solvable and scores the same as its image on the other side
+                for entrypoint in config.entrypoints.iter() {
+                    let image = symmetry.image(entrypoint, col_count, config.row_count);
+
+                    assert!(score_of(entrypoint).is_some());
+                    assert_eq!(score_of(entrypoint), score_of(&image));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_symmetry_validator_rejects_asymmetric_config() -> Result<(), Box<dyn Error>> {
+        let generator = SymmetricGenerator::new(get_options(), Symmetry::Mirror)?;
+        let mut config = generator.generate_with_seed(5)?.config;
+        assert!(is_symmetric_by(&config, Symmetry::Mirror));
+
+        let occupied: HashSet<Position> = config
+            .walls
+            .iter()
+            .chain(config.entrypoints.iter())
+            .copied()
+            .chain(
+                config
+                    .checkpoints
+                    .iter()
+                    .map(|checkpoint| checkpoint.position),
+            )
+            .collect();
+        let free = (2..config.col_count / 2)
+            .flat_map(|x| (0..config.row_count).map(move |y| Position::new(x, y)))
+            .find(|position| !occupied.contains(position))
+            .ok_or("board should have a free tile")?;
+        config.walls.push(free);
+
+        assert!(!is_symmetric_by(&config, Symmetry::Mirror));
+        assert!(!is_symmetric_by(&config, Symmetry::Point));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_symmetric_generator_with_invalid_options() {
+        assert!(matches!(
+            SymmetricGenerator::new(
+                GeneratorOptions {
+                    col_count: 6,
+                    ..get_options()
+                },
+                Symmetry::Mirror
+            ),
+            Err(GeneratorError::BoardTooSmall { .. })
+        ));
+        assert!(matches!(
+            SymmetricGenerator::new(
+                GeneratorOptions {
+                    col_count: 7,
+                    checkpoint_range: 1..12,
+                    ..get_options()
+                },
+                Symmetry::Mirror
+            ),
+            Err(GeneratorError::TooManyTiles {
+                name: "checkpoint_range",
+                ..
+            })
+        ));
+    }
+}
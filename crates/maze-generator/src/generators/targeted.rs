@@ -0,0 +1,144 @@
+use maze_core::MazeConfig;
+use rand::RngCore;
+
+use crate::{measure_difficulty, DifficultyTarget, GeneratorError};
+
+use super::MazeGenerator;
+
+/// Generator rejecting mazes of the inner generator until one hits the difficulty target
+pub(crate) struct TargetedGenerator {
+    generator: Box<dyn MazeGenerator>,
+    target: DifficultyTarget,
+}
+
+impl TargetedGenerator {
+    pub(crate) fn new(
+        generator: Box<dyn MazeGenerator>,
+        target: DifficultyTarget,
+    ) -> Result<Self, GeneratorError> {
+        target.validate()?;
+
+        Ok(Self { generator, target })
+    }
+}
+
+impl MazeGenerator for TargetedGenerator {
+    fn generate_with_rng(&self, rng: &mut dyn RngCore) -> Result<MazeConfig, GeneratorError> {
+        let target = &self.target;
+        let mut closest: Option<f64> = None;
+
+        for _ in 0..target.max_attempts {
+            // candidates are drawn from the same generator, so seeded generation stays reproducible
+            let config = self.generator.generate_with_rng(rng)?;
+            let difficulty = measure_difficulty(&config, target.metric)?;
+            if target.contains(difficulty) {
+                return Ok(config);
+            }
+
+            let distance = |value: f64| (value - target.min).abs().min((value - target.max).abs());
+            if closest.is_none_or(|closest| distance(difficulty) < distance(closest)) {
+                closest = Some(difficulty);
+            }
+        }
+
+        Err(GeneratorError::DifficultyNotReached {
+            min: target.min,
+            max: target.max,
+            attempts: target.max_attempts,
+            closest: closest.unwrap_or_default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use crate::{create_generator, DifficultyMetric, GeneratorOptions, MazeGeneratorType};
+
+    use super::*;
+
+    fn get_generator(target: DifficultyTarget) -> Result<TargetedGenerator, GeneratorError> {
+        let options = GeneratorOptions {
+            col_count: 8,
+            row_count: 5,
+            wall_range: 2..6,
+            max_soft_wall_range: 4..6,
+            ..GeneratorOptions::default_for(MazeGeneratorType::Vanilla)
+        };
+        TargetedGenerator::new(
+            create_generator(MazeGeneratorType::Vanilla, options)?,
+            target,
+        )
+    }
+
+    #[test]
+    fn test_targeted_generator_hits_target() -> Result<(), Box<dyn Error>> {
+        let target = DifficultyTarget {
+            metric: DifficultyMetric::ScoreRatio,
+            min: 1.2,
+            max: f64::INFINITY,
+            max_attempts: 50,
+        };
+        let generator = get_generator(target)?;
+
+        for seed in 0..3 {
+            let generated = generator.generate_with_seed(seed)?;
+            let difficulty = measure_difficulty(&generated.config, target.metric)?;
+
+            assert!(difficulty >= 1.2);
+            assert_eq!(generated, generator.generate_with_seed(seed)?);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_targeted_generator_runs_out_of_attempts() -> Result<(), Box<dyn Error>> {
+        let generator = get_generator(DifficultyTarget {
+            metric: DifficultyMetric::UsefulWallCells,
+            min: 1000.0,
+            max: 2000.0,
+            max_attempts: 3,
+        })?;
+
+        assert!(matches!(
+            generator.generate_with_seed(1),
+            Err(GeneratorError::DifficultyNotReached {
+                attempts: 3,
+                closest,
+                ..
+            }) if closest < 1000.0
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_targeted_generator_with_invalid_target() {
+        let target = DifficultyTarget {
+            metric: DifficultyMetric::ScoreRatio,
+            min: 1.0,
+            max: 2.0,
+            max_attempts: 10,
+        };
+        let cases = [
+            DifficultyTarget { min: 3.0, ..target },
+            DifficultyTarget {
+                max: f64::NAN,
+                ..target
+            },
+            DifficultyTarget {
+                max_attempts: 0,
+                ..target
+            },
+        ];
+
+        for target in cases {
+            assert!(matches!(
+                get_generator(target),
+                Err(GeneratorError::InvalidDifficultyTarget { .. })
+            ));
+        }
+    }
+}
@@ -0,0 +1,486 @@
+use std::collections::{HashSet, VecDeque};
+
+use maze_core::{Checkpoint, Maze, MazeConfig, Position, ScoringRuleType};
+use rand::{Rng, RngCore};
+
+use crate::GeneratorError;
+
+use super::{
+    helpers::{get_random_shuffle, is_solvable},
+    MazeGenerator,
+};
+
+/// Combinations of checkpoint candidates tried before the search is given up
+const MAX_CANDIDATE_COMBINATIONS: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TemplateTile {
+    Wall,
+    MaybeWall,
+    Entrypoint,
+    Checkpoint { level: i32 },
+    CheckpointCandidate { level: i32 },
+}
+
+/// Constraint of the template no generated maze can satisfy
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnsatisfiedConstraint {
+    /// None of the cells marked for the checkpoint of the level is reachable from an entrypoint
+    CheckpointCandidates {
+        level: i32,
+        positions: Vec<Position>,
+    },
+    /// Fixed tiles of the template can't be solved whatever the wildcards become
+    Solvable,
+    /// None of the first combinations of checkpoint candidates made the maze solvable,
+    /// the ones left untried may still do
+    SearchExhausted { combinations: usize },
+}
+
+/// Generator filling wildcards of an ASCII template, every line of the template is a row
+/// of the board starting with the row 0 and every character is a tile:
+///
+/// - `.` empty tile
+/// - `#` wall
+/// - `?` wall that may go here, it is left out when it would make the maze unsolvable
+/// - `E` entrypoint
+/// - `1` to `9` checkpoint of the level
+/// - `a` to `i` one of the tiles with the same letter becomes checkpoint of level 1 to 9
+///
+/// Whitespace around lines and blank lines are ignored.
+pub struct TemplateGenerator {
+    col_count: usize,
+    row_count: usize,
+    max_soft_wall_count: u32,
+    tiles: Vec<(Position, TemplateTile)>,
+}
+
+impl TemplateGenerator {
+    pub fn new(template: &str, max_soft_wall_count: u32) -> Result<Self, GeneratorError> {
+        let rows: Vec<(usize, &str)> = template
+            .lines()
+            .enumerate()
+            .map(|(line, row)| (line + 1, row.trim()))
+            .filter(|(_, row)| !row.is_empty())
+            .collect();
+
+        let col_count = rows.first().map_or(0, |(_, row)| row.chars().count());
+        let mut tiles = vec![];
+
+        for (y, (line, row)) in rows.iter().enumerate() {
+            if row.chars().count() != col_count {
+                return Err(GeneratorError::InvalidTemplate {
+                    reason: format!("line {} is not {} tiles long", line, col_count),
+                });
+            }
+
+            for (x, tile) in row.chars().enumerate() {
+                let position = Position::new(x, y);
+                let tile = match tile {
+                    '.' => continue,
+                    '#' => TemplateTile::Wall,
+                    '?' => TemplateTile::MaybeWall,
+                    'E' => TemplateTile::Entrypoint,
+                    '1'..='9' => TemplateTile::Checkpoint {
+                        level: tile as i32 - '0' as i32,
+                    },
+                    'a'..='i' => TemplateTile::CheckpointCandidate {
+                        level: tile as i32 - 'a' as i32 + 1,
+                    },
+                    _ => {
+                        return Err(GeneratorError::InvalidTemplate {
+                            reason: format!(
+                                "unknown tile '{}' at line {}, column {}",
+                                tile,
+                                line,
+                                x + 1
+                            ),
+                        })
+                    }
+                };
+                tiles.push((position, tile));
+            }
+        }
+
+        let generator = Self {
+            col_count,
+            row_count: rows.len(),
+            max_soft_wall_count,
+            tiles,
+        };
+        // fixed tiles and one of each candidate group make the smallest maze the template allows
+        let mut config = generator.get_config();
+        for (level, positions) in generator.get_candidate_groups() {
+            config.checkpoints.push(Checkpoint {
+                position: positions[0],
+                level,
+            });
+        }
+        Maze::new(&config).map_err(|error| GeneratorError::InvalidTemplate {
+            reason: error.to_string(),
+        })?;
+
+        Ok(generator)
+    }
+
+    /// Config with the fixed tiles of the template only
+    fn get_config(&self) -> MazeConfig {
+        let mut config = MazeConfig {
+            col_count: self.col_count,
+            row_count: self.row_count,
+            floor_count: 1,
+            max_soft_wall_count: self.max_soft_wall_count,
+            entrypoints: vec![],
+            checkpoints: vec![],
+            walls: vec![],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
+        };
+
+        for (position, tile) in self.tiles.iter() {
+            match *tile {
+                TemplateTile::Wall => config.walls.push(*position),
+                TemplateTile::Entrypoint => config.entrypoints.push(*position),
+                TemplateTile::Checkpoint { level } => config.checkpoints.push(Checkpoint {
+                    position: *position,
+                    level,
+                }),
+                _ => {}
+            }
+        }
+
+        config
+    }
+
+    fn get_positions(&self, filter: impl Fn(&TemplateTile) -> bool) -> Vec<Position> {
+        self.tiles
+            .iter()
+            .filter(|(_, tile)| filter(tile))
+            .map(|(position, _)| *position)
+            .collect()
+    }
+
+    /// Candidate positions grouped by the checkpoint level, ordered by level
+    fn get_candidate_groups(&self) -> Vec<(i32, Vec<Position>)> {
+        let mut levels: Vec<i32> = self
+            .tiles
+            .iter()
+            .filter_map(|(_, tile)| match tile {
+                TemplateTile::CheckpointCandidate { level } => Some(*level),
+                _ => None,
+            })
+            .collect();
+        levels.sort_unstable();
+        levels.dedup();
+
+        levels
+            .into_iter()
+            .map(|level| {
+                let positions = self.get_positions(|tile| {
+                    matches!(tile, TemplateTile::CheckpointCandidate { level: l } if *l == level)
+                });
+                (level, positions)
+            })
+            .collect()
+    }
+
+    /// Tiles reachable from any entrypoint when only the fixed walls stand
+    fn get_reachable_positions(&self, config: &MazeConfig) -> HashSet<Position> {
+        let walls: HashSet<&Position> = config.walls.iter().collect();
+        let mut reachable: HashSet<Position> = config.entrypoints.iter().copied().collect();
+        let mut queue: VecDeque<Position> = config.entrypoints.iter().copied().collect();
+
+        while let Some(position) = queue.pop_front() {
+            let (x, y) = (position.x, position.y);
+            let neighbors = [
+                (x + 1 < self.col_count).then(|| Position::new(x + 1, y)),
+                x.checked_sub(1).map(|x| Position::new(x, y)),
+                (y + 1 < self.row_count).then(|| Position::new(x, y + 1)),
+                y.checked_sub(1).map(|y| Position::new(x, y)),
+            ];
+
+            for neighbor in neighbors.iter().flatten().copied() {
+                if !walls.contains(&neighbor) && reachable.insert(neighbor) {
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        reachable
+    }
+}
+
+impl MazeGenerator for TemplateGenerator {
+    fn generate_with_rng(&self, rng: &mut dyn RngCore) -> Result<MazeConfig, GeneratorError> {
+        let config = self.get_config();
+        let reachable = self.get_reachable_positions(&config);
+        let mut groups = vec![];
+        let mut unsatisfied = vec![];
+
+        for (level, positions) in self.get_candidate_groups() {
+            let candidates: Vec<Position> = positions
+                .iter()
+                .filter(|position| reachable.contains(position))
+                .copied()
+                .collect();
+
+            if candidates.is_empty() {
+                unsatisfied.push(UnsatisfiedConstraint::CheckpointCandidates { level, positions });
+            } else {
+                groups.push((level, get_random_shuffle(rng, &candidates)));
+            }
+        }
+
+        if !unsatisfied.is_empty() {
+            return Err(GeneratorError::UnsatisfiableTemplate {
+                constraints: unsatisfied,
+            });
+        }
+
+        let mut config = with_solvable_checkpoints(&config, &groups)?;
+
+        for position in self.get_positions(|tile| *tile == TemplateTile::MaybeWall) {
+            if !rng.gen_bool(0.5) {
+                continue;
+            }
+
+            config.walls.push(position);
+            if !is_solvable(&config)? {
+                config.walls.pop();
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+/// Picks one candidate of every group, going through the combinations of the shuffled groups
+/// until the maze is solvable
+fn with_solvable_checkpoints(
+    config: &MazeConfig,
+    groups: &[(i32, Vec<Position>)],
+) -> Result<MazeConfig, GeneratorError> {
+    let unsatisfiable = |constraint: UnsatisfiedConstraint| {
+        Err(GeneratorError::UnsatisfiableTemplate {
+            constraints: vec![constraint],
+        })
+    };
+    let mut indices = vec![0; groups.len()];
+
+    for _ in 0..MAX_CANDIDATE_COMBINATIONS {
+        let mut candidate = config.clone();
+        for ((level, positions), index) in groups.iter().zip(indices.iter()) {
+            candidate.checkpoints.push(Checkpoint {
+                position: positions[*index],
+                level: *level,
+            });
+        }
+        if is_solvable(&candidate)? {
+            return Ok(candidate);
+        }
+
+        // next combination, the first group changes fastest
+        let mut group = 0;
+        loop {
+            // every combination was tried
+            if group == groups.len() {
+                return unsatisfiable(UnsatisfiedConstraint::Solvable);
+            }
+            indices[group] += 1;
+            if indices[group] < groups[group].1.len() {
+                break;
+            }
+            indices[group] = 0;
+            group += 1;
+        }
+    }
+
+    unsatisfiable(UnsatisfiedConstraint::SearchExhausted {
+        combinations: MAX_CANDIDATE_COMBINATIONS,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use super::*;
+
+    const TEMPLATE: &str = "
+        E..?..b.
+        #.??#.b2
+        E.a?#..#
+        ..a.#...
+    ";
+
+    #[test]
+    fn test_template_generator() -> Result<(), Box<dyn Error>> {
+        let generator = TemplateGenerator::new(TEMPLATE, 4)?;
+
+        for seed in 0..10 {
+            let config = generator.generate_with_seed(seed)?.config;
+
+            assert_eq!((config.col_count, config.row_count), (8, 4));
+            assert_eq!(config.max_soft_wall_count, 4);
+            assert_eq!(
+                config.entrypoints,
+                vec![Position::new(0, 0), Position::new(0, 2)]
+            );
+
+            // fixed tiles stay, one candidate of each group is picked
+            let fixed_walls = [
+                Position::new(0, 1),
+                Position::new(4, 1),
+                Position::new(4, 2),
+                Position::new(7, 2),
+                Position::new(4, 3),
+            ];
+            assert!(fixed_walls.iter().all(|wall| config.walls.contains(wall)));
+            let maybe_walls = [
+                Position::new(3, 0),
+                Position::new(2, 1),
+                Position::new(3, 1),
+                Position::new(3, 2),
+            ];
+            assert!(config
+                .walls
+                .iter()
+                .all(|wall| fixed_walls.contains(wall) || maybe_walls.contains(wall)));
+
+            let level = |level: i32| {
+                config
+                    .checkpoints
+                    .iter()
+                    .filter(|checkpoint| checkpoint.level == level)
+                    .map(|checkpoint| checkpoint.position)
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(level(1).len(), 1);
+            assert!(level(1)[0].x == 2);
+            assert_eq!(level(2).len(), 2);
+            assert!(level(2).contains(&Position::new(7, 1)));
+
+            assert!(is_solvable(&config)?);
+        }
+
+        let first = generator.generate_with_seed(1)?;
+        assert_eq!(first, generator.generate_with_seed(1)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_generator_keeps_maze_solvable() -> Result<(), Box<dyn Error>> {
+        // walling every wildcard would cut the entrypoint off
+        let generator = TemplateGenerator::new("E??1", 0)?;
+
+        for seed in 0..10 {
+            let config = generator.generate_with_seed(seed)?.config;
+            assert!(config.walls.is_empty());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_generator_tries_other_candidates() -> Result<(), Box<dyn Error>> {
+        // only the candidate next to the exit makes the maze solvable
+        let generator = TemplateGenerator::new("Ea#aE2", 0)?;
+
+        for seed in 0..10 {
+            let config = generator.generate_with_seed(seed)?.config;
+            assert!(config.checkpoints.contains(&Checkpoint {
+                position: Position::new(3, 0),
+                level: 1
+            }));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_generator_reports_unsatisfied_constraints() -> Result<(), Box<dyn Error>> {
+        let generator = TemplateGenerator::new(
+            "
+            E.#a
+            ..#.
+            a.#2
+            ",
+            2,
+        )?;
+
+        match generator.generate_with_seed(1) {
+            Err(GeneratorError::UnsatisfiableTemplate { constraints }) => {
+                // candidate (0,2) is reachable, only the exit is walled off
+                assert_eq!(constraints, vec![UnsatisfiedConstraint::Solvable]);
+            }
+            _ => panic!("template should be unsatisfiable"),
+        }
+
+        let generator = TemplateGenerator::new("E#a.1", 2)?;
+        match generator.generate_with_seed(1) {
+            Err(GeneratorError::UnsatisfiableTemplate { constraints }) => {
+                assert_eq!(
+                    constraints,
+                    vec![UnsatisfiedConstraint::CheckpointCandidates {
+                        level: 1,
+                        positions: vec![Position::new(2, 0)]
+                    }]
+                );
+            }
+            _ => panic!("template should be unsatisfiable"),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_generator_reports_exhausted_search() -> Result<(), Box<dyn Error>> {
+        let unsatisfied = |template: &str| -> Result<Vec<UnsatisfiedConstraint>, Box<dyn Error>> {
+            match TemplateGenerator::new(template, 0)?.generate_with_seed(1) {
+                Err(GeneratorError::UnsatisfiableTemplate { constraints }) => Ok(constraints),
+                _ => Err("template should be unsatisfiable".into()),
+            }
+        };
+
+        // exit is walled off, 16 by 16 candidates are all tried
+        assert_eq!(
+            unsatisfied(
+                "
+                Eaaaaaaaaaaaaaaaa#3
+                .bbbbbbbbbbbbbbbb##
+                "
+            )?,
+            vec![UnsatisfiedConstraint::Solvable]
+        );
+        // 17 by 17 candidates are more combinations than are tried
+        assert_eq!(
+            unsatisfied(
+                "
+                Eaaaaaaaaaaaaaaaaa#3
+                .bbbbbbbbbbbbbbbbb##
+                "
+            )?,
+            vec![UnsatisfiedConstraint::SearchExhausted {
+                combinations: MAX_CANDIDATE_COMBINATIONS
+            }]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_generator_with_invalid_template() {
+        let cases = ["", "E..\n..", "E.x1", "E...", "...1", "...a"];
+
+        for template in cases {
+            assert!(matches!(
+                TemplateGenerator::new(template, 1),
+                Err(GeneratorError::InvalidTemplate { .. })
+            ));
+        }
+
+        // a candidate group stands in for the exit
+        assert!(TemplateGenerator::new("E..a", 1).is_ok());
+    }
+}
@@ -1,8 +1,7 @@
-use std::ops::Range;
+use maze_core::{MazeConfig, ScoringRuleType};
+use rand::RngCore;
 
-use maze_core::MazeConfig;
-
-use crate::GeneratorError;
+use crate::{GeneratorError, GeneratorOptions};
 
 use super::{
     helpers::{
@@ -13,49 +12,54 @@ use super::{
     MazeGenerator,
 };
 
-pub(crate) struct VanillaGenerator;
-
-const COL_COUNT: usize = 20;
-const ROW_COUNT: usize = 10;
-const ENTRYPOINT_RANGE: Range<usize> = 1..4;
-const CHECKPOINT_RANGE: Range<usize> = 2..4;
-const EXIT_RANGE: Range<usize> = 1..4;
-const WALL_RANGE: Range<usize> = 10..21;
-const MAX_SOFT_WALL_RANGE: Range<u32> = 15..26;
+pub(crate) struct VanillaGenerator {
+    options: GeneratorOptions,
+}
 
 impl VanillaGenerator {
-    pub(crate) fn new() -> Self {
-        Self
+    pub(crate) fn new(options: GeneratorOptions) -> Result<Self, GeneratorError> {
+        options.validate(options.col_count, options.row_count)?;
+        options.validate_sides(options.row_count, options.row_count)?;
+
+        Ok(Self { options })
     }
 }
 
 impl MazeGenerator for VanillaGenerator {
-    fn generate(&self) -> Result<MazeConfig, GeneratorError> {
-        let entrypoint_count = get_random_number_in_range(ENTRYPOINT_RANGE);
-        let checkpoint_count = get_random_number_in_range(CHECKPOINT_RANGE);
-        let exit_count = get_random_number_in_range(EXIT_RANGE);
-        let wall_count = get_random_number_in_range(WALL_RANGE);
-        let max_soft_wall_count = get_random_number_in_range(MAX_SOFT_WALL_RANGE);
-
-        let entrypoint_positions =
-            get_random_positions(&get_left_wall_positions(ROW_COUNT), entrypoint_count);
-        let exit_positions =
-            get_random_positions(&get_right_wall_positions(COL_COUNT, ROW_COUNT), exit_count);
+    fn generate_with_rng(&self, rng: &mut dyn RngCore) -> Result<MazeConfig, GeneratorError> {
+        let options = &self.options;
+        let entrypoint_count = get_random_number_in_range(rng, options.entrypoint_range.clone());
+        let checkpoint_count = get_random_number_in_range(rng, options.checkpoint_range.clone());
+        let exit_count = get_random_number_in_range(rng, options.exit_range.clone());
+        let wall_count = get_random_number_in_range(rng, options.wall_range.clone());
+        let max_soft_wall_count =
+            get_random_number_in_range(rng, options.max_soft_wall_range.clone());
+
+        let entrypoint_positions = get_random_positions(
+            rng,
+            &get_left_wall_positions(options.row_count),
+            entrypoint_count,
+        );
+        let exit_positions = get_random_positions(
+            rng,
+            &get_right_wall_positions(options.col_count, options.row_count),
+            exit_count,
+        );
 
         let empty_positions = get_empty_positions_with_padding(
-            COL_COUNT,
-            ROW_COUNT,
-            2,
+            options.col_count,
+            options.row_count,
+            options.checkpoint_padding,
             0,
             &[&entrypoint_positions, &exit_positions][..],
         );
 
-        let checkpoint_positions = get_random_positions(&empty_positions, checkpoint_count);
+        let checkpoint_positions = get_random_positions(rng, &empty_positions, checkpoint_count);
 
         let empty_positions = get_empty_positions_with_padding(
-            COL_COUNT,
-            ROW_COUNT,
-            1,
+            options.col_count,
+            options.row_count,
+            options.wall_padding,
             0,
             &[
                 &entrypoint_positions,
@@ -67,17 +71,20 @@ impl MazeGenerator for VanillaGenerator {
         let checkpoints = get_checkpoints(&checkpoint_positions, &exit_positions);
 
         let mut config = MazeConfig {
-            col_count: COL_COUNT,
-            row_count: ROW_COUNT,
+            col_count: options.col_count,
+            row_count: options.row_count,
+            floor_count: 1,
             max_soft_wall_count: wall_count as u32,
             entrypoints: entrypoint_positions,
             checkpoints,
             walls: vec![],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
         };
 
-        let walls = get_random_solvable_walls(&config, &empty_positions, wall_count)?;
+        let walls = get_random_solvable_walls(rng, &config, &empty_positions, wall_count)?;
 
-        config.max_soft_wall_count = max_soft_wall_count;
+        config.max_soft_wall_count = max_soft_wall_count as u32;
         config.walls = walls;
 
         Ok(config)
@@ -86,14 +93,100 @@ impl MazeGenerator for VanillaGenerator {
 
 #[cfg(test)]
 mod tests {
+    use std::error::Error;
+
+    use crate::MazeGeneratorType;
+
     use super::*;
 
+    fn get_options() -> GeneratorOptions {
+        GeneratorOptions::default_for(MazeGeneratorType::Vanilla)
+    }
+
     #[test]
-    fn test_vanilla_generator() {
-        let generator = VanillaGenerator::new();
+    fn test_vanilla_generator() -> Result<(), Box<dyn Error>> {
+        let generator = VanillaGenerator::new(get_options())?;
         for _ in 0..5 {
             let config = generator.generate();
             assert!(config.is_ok());
         }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_vanilla_generator_with_options() -> Result<(), Box<dyn Error>> {
+        let generator = VanillaGenerator::new(GeneratorOptions {
+            col_count: 8,
+            row_count: 5,
+            entrypoint_range: 2..3,
+            checkpoint_range: 1..2,
+            exit_range: 1..2,
+            wall_range: 0..4,
+            max_soft_wall_range: 3..4,
+            checkpoint_padding: 1,
+            wall_padding: 1,
+        })?;
+
+        for seed in 0..5 {
+            let config = generator.generate_with_seed(seed)?.config;
+
+            assert_eq!((config.col_count, config.row_count), (8, 5));
+            assert_eq!(config.entrypoints.len(), 2);
+            assert_eq!(config.checkpoints.len(), 2);
+            assert!(config.walls.len() < 4);
+            assert_eq!(config.max_soft_wall_count, 3);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_vanilla_generator_with_invalid_options() {
+        let cases = [
+            GeneratorOptions {
+                entrypoint_range: 0..2,
+                ..get_options()
+            },
+            GeneratorOptions {
+                wall_range: 5..5,
+                ..get_options()
+            },
+        ];
+        for options in cases {
+            assert!(matches!(
+                VanillaGenerator::new(options),
+                Err(GeneratorError::InvalidRange { .. })
+            ));
+        }
+
+        let cases = [
+            GeneratorOptions {
+                exit_range: 1..12,
+                ..get_options()
+            },
+            GeneratorOptions {
+                col_count: 6,
+                checkpoint_range: 1..22,
+                ..get_options()
+            },
+        ];
+        for options in cases {
+            assert!(matches!(
+                VanillaGenerator::new(options),
+                Err(GeneratorError::TooManyTiles { .. })
+            ));
+        }
+
+        assert!(matches!(
+            VanillaGenerator::new(GeneratorOptions {
+                col_count: 4,
+                ..get_options()
+            }),
+            Err(GeneratorError::BoardTooSmall {
+                col_count: 4,
+                row_count: 10
+            })
+        ));
     }
 }
@@ -1,8 +1,7 @@
-use std::ops::Range;
+use maze_core::{MazeConfig, ScoringRuleType};
+use rand::RngCore;
 
-use maze_core::MazeConfig;
-
-use crate::GeneratorError;
+use crate::{GeneratorError, GeneratorOptions};
 
 use super::{
     helpers::{
@@ -13,45 +12,45 @@ use super::{
     MazeGenerator,
 };
 
-pub(crate) struct WaterfallGenerator;
-
-const COL_COUNT: usize = 10;
-const ROW_COUNT: usize = 15;
-const CHECKPOINT_RANGE: Range<usize> = 3..4;
-const WALL_RANGE: Range<usize> = 10..16;
-const MAX_SOFT_WALL_RANGE: Range<u32> = 10..21;
+pub(crate) struct WaterfallGenerator {
+    options: GeneratorOptions,
+}
 
 impl WaterfallGenerator {
-    pub(crate) fn new() -> Self {
-        Self
+    pub(crate) fn new(options: GeneratorOptions) -> Result<Self, GeneratorError> {
+        options.validate(options.row_count, options.col_count)?;
+
+        Ok(Self { options })
     }
 }
 
 impl MazeGenerator for WaterfallGenerator {
-    fn generate(&self) -> Result<MazeConfig, GeneratorError> {
-        let checkpoint_count = get_random_number_in_range(CHECKPOINT_RANGE);
-        let wall_count = get_random_number_in_range(WALL_RANGE);
-        let max_soft_wall_count = get_random_number_in_range(MAX_SOFT_WALL_RANGE);
+    fn generate_with_rng(&self, rng: &mut dyn RngCore) -> Result<MazeConfig, GeneratorError> {
+        let options = &self.options;
+        let checkpoint_count = get_random_number_in_range(rng, options.checkpoint_range.clone());
+        let wall_count = get_random_number_in_range(rng, options.wall_range.clone());
+        let max_soft_wall_count =
+            get_random_number_in_range(rng, options.max_soft_wall_range.clone());
 
-        let entrypoint_positions = get_top_wall_positions(COL_COUNT, ROW_COUNT);
+        let entrypoint_positions = get_top_wall_positions(options.col_count, options.row_count);
 
-        let exit_positions = get_bottom_wall_positions(COL_COUNT);
+        let exit_positions = get_bottom_wall_positions(options.col_count);
 
         let empty_positions = get_empty_positions_with_padding(
-            COL_COUNT,
-            ROW_COUNT,
+            options.col_count,
+            options.row_count,
             0,
-            2,
+            options.checkpoint_padding,
             &[&entrypoint_positions, &exit_positions][..],
         );
 
-        let checkpoint_positions = get_random_positions(&empty_positions, checkpoint_count);
+        let checkpoint_positions = get_random_positions(rng, &empty_positions, checkpoint_count);
 
         let empty_positions = get_empty_positions_with_padding(
-            COL_COUNT,
-            ROW_COUNT,
+            options.col_count,
+            options.row_count,
             0,
-            1,
+            options.wall_padding,
             &[
                 &entrypoint_positions,
                 &exit_positions,
@@ -62,17 +61,20 @@ impl MazeGenerator for WaterfallGenerator {
         let checkpoints = get_checkpoints(&checkpoint_positions, &exit_positions);
 
         let mut config = MazeConfig {
-            col_count: COL_COUNT,
-            row_count: ROW_COUNT,
+            col_count: options.col_count,
+            row_count: options.row_count,
+            floor_count: 1,
             max_soft_wall_count: wall_count as u32,
             entrypoints: entrypoint_positions,
             checkpoints,
             walls: vec![],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
         };
 
-        let walls = get_random_solvable_walls(&config, &empty_positions, wall_count)?;
+        let walls = get_random_solvable_walls(rng, &config, &empty_positions, wall_count)?;
 
-        config.max_soft_wall_count = max_soft_wall_count;
+        config.max_soft_wall_count = max_soft_wall_count as u32;
         config.walls = walls;
 
         Ok(config)
@@ -81,14 +83,90 @@ impl MazeGenerator for WaterfallGenerator {
 
 #[cfg(test)]
 mod tests {
+    use std::error::Error;
+
+    use crate::MazeGeneratorType;
+
     use super::*;
 
+    fn get_options() -> GeneratorOptions {
+        GeneratorOptions::default_for(MazeGeneratorType::Waterfall)
+    }
+
     #[test]
-    fn test_waterfall_generator() {
-        let generator = WaterfallGenerator::new();
+    fn test_waterfall_generator() -> Result<(), Box<dyn Error>> {
+        let generator = WaterfallGenerator::new(get_options())?;
         for _ in 0..5 {
             let config = generator.generate();
             assert!(config.is_ok());
         }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_waterfall_generator_with_options() -> Result<(), Box<dyn Error>> {
+        let generator = WaterfallGenerator::new(GeneratorOptions {
+            col_count: 6,
+            row_count: 9,
+            checkpoint_range: 2..3,
+            wall_range: 2..5,
+            ..get_options()
+        })?;
+
+        for seed in 0..5 {
+            let config = generator.generate_with_seed(seed)?.config;
+
+            assert_eq!((config.col_count, config.row_count), (6, 9));
+            // whole top row enters and whole bottom row exits
+            assert_eq!(config.entrypoints.len(), 6);
+            assert_eq!(config.checkpoints.len(), 2 + 6);
+            assert!(config.walls.len() < 5);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_waterfall_generator_with_invalid_options() {
+        // entrypoint and exit ranges are not used by waterfall
+        assert!(WaterfallGenerator::new(GeneratorOptions {
+            entrypoint_range: 0..0,
+            exit_range: 40..50,
+            ..get_options()
+        })
+        .is_ok());
+
+        assert!(matches!(
+            WaterfallGenerator::new(GeneratorOptions {
+                max_soft_wall_range: 3..3,
+                ..get_options()
+            }),
+            Err(GeneratorError::InvalidRange {
+                name: "max_soft_wall_range",
+                start: 3,
+                end: 3
+            })
+        ));
+        assert!(matches!(
+            WaterfallGenerator::new(GeneratorOptions {
+                col_count: 2,
+                row_count: 6,
+                checkpoint_range: 3..6,
+                ..get_options()
+            }),
+            Err(GeneratorError::TooManyTiles {
+                name: "checkpoint_range",
+                requested: 5,
+                available: 4
+            })
+        ));
+        assert!(matches!(
+            WaterfallGenerator::new(GeneratorOptions {
+                row_count: 4,
+                ..get_options()
+            }),
+            Err(GeneratorError::BoardTooSmall { .. })
+        ));
     }
 }
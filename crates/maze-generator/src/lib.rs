@@ -1,15 +1,202 @@
+mod difficulty;
 mod generator_error;
+mod generator_options;
 mod generator_type;
 mod generators;
+mod mutation;
+mod registry;
 
-use generators::{vanilla::VanillaGenerator, waterfall::WaterfallGenerator, MazeGenerator};
+use generators::targeted::TargetedGenerator;
+use registry::create_registered_generator;
 
+pub use difficulty::*;
 pub use generator_error::*;
+pub use generator_options::*;
 pub use generator_type::*;
+pub use generators::{
+    campaign::{Campaign, CampaignGenerator, CampaignLesson, CampaignLevel},
+    symmetric::Symmetry,
+    template::{TemplateGenerator, UnsatisfiedConstraint},
+    GeneratedMaze, MazeGenerator,
+};
+// wall placements timed against each other by the generation bench
+#[doc(hidden)]
+pub use generators::helpers::{
+    get_random_solvable_wall_groups, get_reference_solvable_wall_groups,
+};
+pub use mutation::mutate;
+pub use registry::{
+    get_generator_info, list_generators, register_generator, GeneratorFactory, GeneratorInfo,
+    GeneratorRegistry,
+};
 
-pub fn create_generator(generator_type: MazeGeneratorType) -> Box<dyn MazeGenerator> {
-    match generator_type {
-        MazeGeneratorType::Vanilla => Box::new(VanillaGenerator::new()),
-        MazeGeneratorType::Waterfall => Box::new(WaterfallGenerator::new()),
+/// Creates generator of the type, fails if the options are impossible to satisfy
+pub fn create_generator(
+    generator_type: MazeGeneratorType,
+    options: GeneratorOptions,
+) -> Result<Box<dyn MazeGenerator>, GeneratorError> {
+    create_generator_by_id(generator_type.id(), Some(options))
+}
+
+/// Creates generator registered under the id, with its default options if none are given
+pub fn create_generator_by_id(
+    id: &str,
+    options: Option<GeneratorOptions>,
+) -> Result<Box<dyn MazeGenerator>, GeneratorError> {
+    create_registered_generator(id, options)
+}
+
+/// Creates generator of the type that retries until the generated maze hits the difficulty target
+pub fn create_targeted_generator(
+    generator_type: MazeGeneratorType,
+    options: GeneratorOptions,
+    target: DifficultyTarget,
+) -> Result<Box<dyn MazeGenerator>, GeneratorError> {
+    create_targeted_generator_by_id(generator_type.id(), Some(options), target)
+}
+
+/// Same as `create_targeted_generator` for the generator registered under the id
+pub fn create_targeted_generator_by_id(
+    id: &str,
+    options: Option<GeneratorOptions>,
+    target: DifficultyTarget,
+) -> Result<Box<dyn MazeGenerator>, GeneratorError> {
+    let generator = create_generator_by_id(id, options)?;
+    Ok(Box::new(TargetedGenerator::new(generator, target)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{error::Error, fmt::Display};
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    fn join<T: Display>(items: impl Iterator<Item = T>) -> String {
+        items
+            .map(|item| item.to_string())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    #[test]
+    fn test_generate_with_seed_is_reproducible() -> Result<(), Box<dyn Error>> {
+        for generator_type in [
+            MazeGeneratorType::Vanilla,
+            MazeGeneratorType::Waterfall,
+            MazeGeneratorType::Spiral,
+            MazeGeneratorType::Arena,
+            MazeGeneratorType::Islands,
+            MazeGeneratorType::Symmetric(Symmetry::Mirror),
+            MazeGeneratorType::Symmetric(Symmetry::Point),
+        ] {
+            let generator = create_generator(
+                generator_type,
+                GeneratorOptions::default_for(generator_type),
+            )?;
+
+            let first = generator.generate_with_seed(7)?;
+            let second = generator.generate_with_seed(7)?;
+            let injected = generator.generate_with_rng(&mut ChaCha8Rng::seed_from_u64(7))?;
+
+            assert_eq!(first.seed, 7);
+            assert_eq!(first, second);
+            assert_eq!(first.config, injected);
+            assert_ne!(first, generator.generate_with_seed(8)?);
+
+            let random = generator.generate()?;
+            assert_eq!(random, generator.generate_with_seed(random.seed)?);
+        }
+
+        Ok(())
+    }
+
+    // golden outputs pin the seeds, a change here breaks reproduction of already published mazes
+
+    #[test]
+    fn test_vanilla_golden_seeds() -> Result<(), Box<dyn Error>> {
+        let generator = create_generator(
+            MazeGeneratorType::Vanilla,
+            GeneratorOptions::default_for(MazeGeneratorType::Vanilla),
+        )?;
+        let cases = [
+            (
+                42,
+                23,
+                "(0,2)",
+                "(17,6):1 (6,0):2 (5,2):3 (19,0):4",
+                "(11,1) (3,8) (18,5) (13,0) (10,6) (12,3) (16,3) (8,7) (4,9) (9,2) (10,8) (12,5) (3,5) (17,2) (16,5) (10,2) (3,9) (16,7) (5,8) (16,1)",
+            ),
+            (
+                20240601,
+                15,
+                "(0,6)",
+                "(15,6):1 (10,0):2 (7,6):3 (19,1):4 (19,0):4 (19,4):4",
+                "(8,9) (14,9) (9,5) (9,4) (6,1) (11,6) (2,0) (1,4) (6,5) (17,5) (11,0) (11,5)",
+            ),
+        ];
+
+        for (seed, max_soft_wall_count, entrypoints, checkpoints, walls) in cases {
+            let config = generator.generate_with_seed(seed)?.config;
+
+            assert_eq!(config.max_soft_wall_count, max_soft_wall_count);
+            assert_eq!(join(config.entrypoints.iter()), entrypoints);
+            assert_eq!(
+                join(
+                    config
+                        .checkpoints
+                        .iter()
+                        .map(|checkpoint| format!("{}:{}", checkpoint.position, checkpoint.level))
+                ),
+                checkpoints
+            );
+            assert_eq!(join(config.walls.iter()), walls);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_waterfall_golden_seeds() -> Result<(), Box<dyn Error>> {
+        let generator = create_generator(
+            MazeGeneratorType::Waterfall,
+            GeneratorOptions::default_for(MazeGeneratorType::Waterfall),
+        )?;
+        let cases = [
+            (
+                42,
+                11,
+                "(9,6) (1,2) (1,11)",
+                "(0,9) (1,13) (8,3) (5,5) (5,13) (0,2) (7,13) (6,12) (4,10) (6,13) (5,2) (4,4) (7,5) (4,13)",
+            ),
+            (
+                20240601,
+                19,
+                "(1,2) (3,6) (4,12)",
+                "(0,3) (6,2) (3,11) (0,6) (4,6) (5,2) (1,12) (9,2) (1,7) (1,5) (2,6) (2,3) (1,9)",
+            ),
+        ];
+
+        for (seed, max_soft_wall_count, checkpoints, walls) in cases {
+            let config = generator.generate_with_seed(seed)?.config;
+
+            assert_eq!(config.max_soft_wall_count, max_soft_wall_count);
+            // entrypoints and exits are whole rows, only the checkpoints in between are random
+            assert_eq!(
+                join(
+                    config
+                        .checkpoints
+                        .iter()
+                        .filter(|checkpoint| checkpoint.level < 4)
+                        .map(|checkpoint| checkpoint.position)
+                ),
+                checkpoints
+            );
+            assert_eq!(join(config.walls.iter()), walls);
+        }
+
+        Ok(())
     }
 }
@@ -0,0 +1,235 @@
+use maze_core::{Maze, MazeConfig, Position, TileKind};
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    generators::helpers::{get_random_number_in_range, is_solvable},
+    GeneratedMaze, GeneratorError,
+};
+
+/// Number of picks a single mutation step gets to find a change that keeps the maze solvable
+const MAX_STEP_ATTEMPTS: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mutation {
+    /// Moves a wall to an empty tile of the same floor
+    MoveWall,
+    /// Swaps positions of two checkpoints of different levels
+    SwapCheckpoints,
+    /// Raises or lowers the soft wall budget by one
+    AdjustSoftWallCount,
+}
+
+const MUTATIONS: [Mutation; 3] = [
+    Mutation::MoveWall,
+    Mutation::SwapCheckpoints,
+    Mutation::AdjustSoftWallCount,
+];
+
+/// Produces a nearby variant of the maze by applying `strength` small changes, the same seed
+/// gives the same variant. Every change keeps the maze solvable, a step that finds no such
+/// change in its attempts is skipped, so the variant may differ by fewer changes.
+pub fn mutate(
+    config: &MazeConfig,
+    strength: usize,
+    seed: u64,
+) -> Result<GeneratedMaze, GeneratorError> {
+    if !is_solvable(config)? {
+        return Err(GeneratorError::UnsolvableMaze);
+    }
+
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut config = config.clone();
+
+    for _ in 0..strength {
+        for _ in 0..MAX_STEP_ATTEMPTS {
+            let mutation = MUTATIONS[get_random_number_in_range(&mut rng, 0..MUTATIONS.len())];
+            let mutated = match get_mutated_config(&mut rng, &config, mutation)? {
+                Some(mutated) => mutated,
+                None => continue,
+            };
+
+            if is_solvable(&mutated)? {
+                config = mutated;
+                break;
+            }
+        }
+    }
+
+    Ok(GeneratedMaze { seed, config })
+}
+
+/// Config with the mutation applied, `None` if the maze has nothing the mutation could change
+fn get_mutated_config(
+    rng: &mut dyn RngCore,
+    config: &MazeConfig,
+    mutation: Mutation,
+) -> Result<Option<MazeConfig>, GeneratorError> {
+    let mut mutated = config.clone();
+
+    match mutation {
+        Mutation::MoveWall => {
+            if config.walls.is_empty() {
+                return Ok(None);
+            }
+
+            let index = get_random_number_in_range(rng, 0..config.walls.len());
+            let floor = config.walls[index].floor_index();
+            let maze = Maze::new(config)?;
+            let empty_positions = get_empty_positions(&maze, floor);
+            if empty_positions.is_empty() {
+                return Ok(None);
+            }
+
+            mutated.walls[index] =
+                empty_positions[get_random_number_in_range(rng, 0..empty_positions.len())];
+        }
+        Mutation::SwapCheckpoints => {
+            let checkpoints = &config.checkpoints;
+            let pairs: Vec<(usize, usize)> = (0..checkpoints.len())
+                .flat_map(|i| (i + 1..checkpoints.len()).map(move |j| (i, j)))
+                .filter(|(i, j)| checkpoints[*i].level != checkpoints[*j].level)
+                .collect();
+            if pairs.is_empty() {
+                return Ok(None);
+            }
+
+            let (i, j) = pairs[get_random_number_in_range(rng, 0..pairs.len())];
+            mutated.checkpoints[i].position = checkpoints[j].position;
+            mutated.checkpoints[j].position = checkpoints[i].position;
+        }
+        Mutation::AdjustSoftWallCount => {
+            let lower =
+                config.max_soft_wall_count > 1 && get_random_number_in_range(rng, 0..2) == 0;
+            mutated.max_soft_wall_count = match lower {
+                true => config.max_soft_wall_count - 1,
+                false => config.max_soft_wall_count + 1,
+            };
+        }
+    }
+
+    Ok(Some(mutated))
+}
+
+fn get_empty_positions(maze: &Maze, floor: usize) -> Vec<Position> {
+    maze.floors()[floor]
+        .iter()
+        .enumerate()
+        .flat_map(|(x, column)| {
+            column
+                .iter()
+                .enumerate()
+                .filter(|(_, kind)| **kind == TileKind::Empty)
+                .map(move |(y, _)| Position::on_floor(x, y, floor))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use maze_core::{Checkpoint, ScoringRuleType};
+
+    use crate::create_generator_by_id;
+
+    use super::*;
+
+    fn get_config() -> Result<MazeConfig, GeneratorError> {
+        Ok(create_generator_by_id("vanilla", None)?
+            .generate_with_seed(42)?
+            .config)
+    }
+
+    #[test]
+    fn test_mutate() -> Result<(), Box<dyn Error>> {
+        let config = get_config()?;
+        assert_eq!(mutate(&config, 0, 1)?.config, config);
+
+        let mut changed = 0;
+        for seed in 0..10 {
+            let mutated = mutate(&config, 3, seed)?;
+
+            assert_eq!(mutated.seed, seed);
+            assert_eq!(mutated, mutate(&config, 3, seed)?);
+            assert!(is_solvable(&mutated.config)?);
+
+            // changes move tiles around but never add or remove them
+            let mutated = &mutated.config;
+            assert_eq!(mutated.walls.len(), config.walls.len());
+            assert_eq!(mutated.checkpoints.len(), config.checkpoints.len());
+            assert_eq!(mutated.entrypoints, config.entrypoints);
+
+            let moved_walls = mutated
+                .walls
+                .iter()
+                .filter(|wall| !config.walls.contains(wall))
+                .count();
+            assert!(moved_walls <= 3);
+
+            if *mutated != config {
+                changed += 1;
+            }
+        }
+        assert!(changed > 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mutate_keeps_maze_solvable() -> Result<(), Box<dyn Error>> {
+        // moving the wall in the corner into the gap (1,3) would cut the exit off,
+        // the only checkpoint level leaves nothing to swap
+        let config = MazeConfig {
+            col_count: 4,
+            row_count: 4,
+            floor_count: 1,
+            max_soft_wall_count: 1,
+            entrypoints: vec![Position::new(0, 0)],
+            checkpoints: vec![Checkpoint {
+                position: Position::new(3, 3),
+                level: 1,
+            }],
+            walls: vec![
+                Position::new(1, 0),
+                Position::new(1, 1),
+                Position::new(1, 2),
+                Position::new(3, 0),
+            ],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
+        };
+
+        for seed in 0..40 {
+            let mutated = mutate(&config, 5, seed)?.config;
+            assert!(is_solvable(&mutated)?);
+            assert_eq!(mutated.checkpoints, config.checkpoints);
+            assert!(mutated.max_soft_wall_count >= 1);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mutate_unsolvable_maze() {
+        let config = MazeConfig {
+            col_count: 4,
+            row_count: 4,
+            floor_count: 1,
+            max_soft_wall_count: 1,
+            entrypoints: vec![Position::new(0, 0)],
+            checkpoints: vec![Checkpoint {
+                position: Position::new(3, 3),
+                level: 1,
+            }],
+            walls: (0..4).map(|y| Position::new(1, y)).collect(),
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
+        };
+
+        assert!(matches!(
+            mutate(&config, 2, 1),
+            Err(GeneratorError::UnsolvableMaze)
+        ));
+    }
+}
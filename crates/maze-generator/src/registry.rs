@@ -0,0 +1,399 @@
+use std::sync::{OnceLock, PoisonError, RwLock, RwLockReadGuard};
+
+use crate::{
+    generators::{
+        arena::ArenaGenerator, islands::IslandsGenerator, spiral::SpiralGenerator,
+        symmetric::SymmetricGenerator, vanilla::VanillaGenerator, waterfall::WaterfallGenerator,
+        MazeGenerator,
+    },
+    GeneratorError, GeneratorOptions, Symmetry,
+};
+
+/// Creates generator from the options, fails if the options are impossible to satisfy
+pub type GeneratorFactory = fn(GeneratorOptions) -> Result<Box<dyn MazeGenerator>, GeneratorError>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratorInfo {
+    pub id: String,
+    pub description: String,
+    pub default_options: GeneratorOptions,
+}
+
+struct GeneratorEntry {
+    info: GeneratorInfo,
+    factory: GeneratorFactory,
+}
+
+/// Generators addressable by their string ids, listed in the order of registration
+#[derive(Default)]
+pub struct GeneratorRegistry {
+    entries: Vec<GeneratorEntry>,
+}
+
+impl GeneratorRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registry with all generators of this crate, `MazeGeneratorType::id` gives their ids
+    pub fn with_builtin_generators() -> Self {
+        let mut registry = Self::new();
+        let builtin: [(&str, &str, GeneratorOptions, GeneratorFactory); 7] = [
+            (
+                "vanilla",
+                "Left to right board with checkpoints between the entrypoints and exits",
+                GeneratorOptions {
+                    col_count: 20,
+                    row_count: 10,
+                    entrypoint_range: 1..4,
+                    checkpoint_range: 2..4,
+                    exit_range: 1..4,
+                    wall_range: 10..21,
+                    max_soft_wall_range: 15..26,
+                    checkpoint_padding: 2,
+                    wall_padding: 1,
+                },
+                |options| Ok(Box::new(VanillaGenerator::new(options)?)),
+            ),
+            (
+                "waterfall",
+                "Top to bottom board entered over the whole top row",
+                GeneratorOptions {
+                    col_count: 10,
+                    row_count: 15,
+                    entrypoint_range: 10..11,
+                    checkpoint_range: 3..4,
+                    exit_range: 10..11,
+                    wall_range: 10..16,
+                    max_soft_wall_range: 10..21,
+                    checkpoint_padding: 2,
+                    wall_padding: 1,
+                },
+                |options| Ok(Box::new(WaterfallGenerator::new(options)?)),
+            ),
+            (
+                "spiral",
+                "Concentric rings with a checkpoint on each, exited in the center",
+                GeneratorOptions {
+                    col_count: 13,
+                    row_count: 13,
+                    entrypoint_range: 1..4,
+                    checkpoint_range: 3..5,
+                    exit_range: 1..2,
+                    wall_range: 12..21,
+                    max_soft_wall_range: 15..26,
+                    checkpoint_padding: 2,
+                    wall_padding: 1,
+                },
+                |options| Ok(Box::new(SpiralGenerator::new(options)?)),
+            ),
+            (
+                "arena",
+                "Open board entered from the whole border with the exit in the center",
+                GeneratorOptions {
+                    col_count: 15,
+                    row_count: 11,
+                    entrypoint_range: 2..6,
+                    checkpoint_range: 2..4,
+                    exit_range: 1..2,
+                    wall_range: 4..9,
+                    max_soft_wall_range: 10..16,
+                    checkpoint_padding: 2,
+                    wall_padding: 1,
+                },
+                |options| Ok(Box::new(ArenaGenerator::new(options)?)),
+            ),
+            (
+                "islands",
+                "Left to right board with walls grouped into islands",
+                GeneratorOptions {
+                    col_count: 20,
+                    row_count: 12,
+                    entrypoint_range: 1..4,
+                    checkpoint_range: 2..4,
+                    exit_range: 1..4,
+                    wall_range: 16..29,
+                    max_soft_wall_range: 15..26,
+                    checkpoint_padding: 2,
+                    wall_padding: 1,
+                },
+                |options| Ok(Box::new(IslandsGenerator::new(options)?)),
+            ),
+            (
+                "symmetric-mirror",
+                "Board for two sides mirrored over the middle column with the exits in it",
+                GeneratorOptions {
+                    col_count: 21,
+                    row_count: 10,
+                    entrypoint_range: 1..3,
+                    checkpoint_range: 1..3,
+                    exit_range: 1..3,
+                    wall_range: 6..11,
+                    max_soft_wall_range: 15..26,
+                    checkpoint_padding: 2,
+                    wall_padding: 1,
+                },
+                |options| Ok(Box::new(SymmetricGenerator::new(options, Symmetry::Mirror)?)),
+            ),
+            (
+                "symmetric-point",
+                "Board for two sides rotated by half a turn around the center with the exits in the middle",
+                GeneratorOptions {
+                    col_count: 21,
+                    row_count: 10,
+                    entrypoint_range: 1..3,
+                    checkpoint_range: 1..3,
+                    exit_range: 1..3,
+                    wall_range: 6..11,
+                    max_soft_wall_range: 15..26,
+                    checkpoint_padding: 2,
+                    wall_padding: 1,
+                },
+                |options| Ok(Box::new(SymmetricGenerator::new(options, Symmetry::Point)?)),
+            ),
+        ];
+
+        for (id, description, default_options, factory) in builtin {
+            registry.entries.push(GeneratorEntry {
+                info: GeneratorInfo {
+                    id: id.to_string(),
+                    description: description.to_string(),
+                    default_options,
+                },
+                factory,
+            });
+        }
+
+        registry
+    }
+
+    pub fn register(
+        &mut self,
+        id: &str,
+        description: &str,
+        default_options: GeneratorOptions,
+        factory: GeneratorFactory,
+    ) -> Result<(), GeneratorError> {
+        if self.get(id).is_some() {
+            return Err(GeneratorError::DuplicateGenerator { id: id.to_string() });
+        }
+
+        self.entries.push(GeneratorEntry {
+            info: GeneratorInfo {
+                id: id.to_string(),
+                description: description.to_string(),
+                default_options,
+            },
+            factory,
+        });
+        Ok(())
+    }
+
+    pub fn get(&self, id: &str) -> Option<&GeneratorInfo> {
+        self.get_entry(id).map(|entry| &entry.info)
+    }
+
+    pub fn list(&self) -> Vec<GeneratorInfo> {
+        self.entries
+            .iter()
+            .map(|entry| entry.info.clone())
+            .collect()
+    }
+
+    /// Creates generator registered under the id, with its default options if none are given
+    pub fn create(
+        &self,
+        id: &str,
+        options: Option<GeneratorOptions>,
+    ) -> Result<Box<dyn MazeGenerator>, GeneratorError> {
+        let (factory, options) = self.get_factory(id, options)?;
+        factory(options)
+    }
+
+    /// Factory of the generator together with the options it is created with
+    fn get_factory(
+        &self,
+        id: &str,
+        options: Option<GeneratorOptions>,
+    ) -> Result<(GeneratorFactory, GeneratorOptions), GeneratorError> {
+        let entry = self
+            .get_entry(id)
+            .ok_or_else(|| GeneratorError::UnknownGenerator { id: id.to_string() })?;
+        let options = options.unwrap_or_else(|| entry.info.default_options.clone());
+
+        Ok((entry.factory, options))
+    }
+
+    fn get_entry(&self, id: &str) -> Option<&GeneratorEntry> {
+        self.entries.iter().find(|entry| entry.info.id == id)
+    }
+}
+
+/// Registry shared by the whole process, starts with the builtin generators
+fn shared_registry() -> &'static RwLock<GeneratorRegistry> {
+    static REGISTRY: OnceLock<RwLock<GeneratorRegistry>> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(GeneratorRegistry::with_builtin_generators()))
+}
+
+fn read_shared_registry() -> RwLockReadGuard<'static, GeneratorRegistry> {
+    // the registry is never left half updated, so a panic of another thread doesn't matter
+    shared_registry()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Registers generator for the whole process, it is then created by id the same way as the builtin ones
+pub fn register_generator(
+    id: &str,
+    description: &str,
+    default_options: GeneratorOptions,
+    factory: GeneratorFactory,
+) -> Result<(), GeneratorError> {
+    shared_registry()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .register(id, description, default_options, factory)
+}
+
+/// Generators of the process, builtin ones first in a stable order, meant for generator pickers of UIs
+pub fn list_generators() -> Vec<GeneratorInfo> {
+    read_shared_registry().list()
+}
+
+pub fn get_generator_info(id: &str) -> Option<GeneratorInfo> {
+    read_shared_registry().get(id).cloned()
+}
+
+/// Creates generator registered for the process under the id
+pub(crate) fn create_registered_generator(
+    id: &str,
+    options: Option<GeneratorOptions>,
+) -> Result<Box<dyn MazeGenerator>, GeneratorError> {
+    // the lock is released before the factory runs, factories may create other generators
+    let (factory, options) = read_shared_registry().get_factory(id, options)?;
+    factory(options)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use crate::MazeGeneratorType;
+
+    use super::*;
+
+    #[test]
+    fn test_builtin_generators_are_listed() {
+        let ids: Vec<String> = list_generators().into_iter().map(|info| info.id).collect();
+
+        // other tests may register generators of their own after the builtin ones
+        assert_eq!(
+            ids[..7],
+            [
+                "vanilla",
+                "waterfall",
+                "spiral",
+                "arena",
+                "islands",
+                "symmetric-mirror",
+                "symmetric-point"
+            ]
+        );
+        assert!(list_generators()
+            .iter()
+            .all(|info| !info.description.is_empty()));
+    }
+
+    #[test]
+    fn test_create_by_id() -> Result<(), Box<dyn Error>> {
+        let registry = GeneratorRegistry::with_builtin_generators();
+
+        for info in registry.list() {
+            let generator = registry.create(&info.id, None)?;
+            let config = generator.generate_with_seed(3)?.config;
+            assert_eq!(config.col_count, info.default_options.col_count);
+        }
+
+        let options = GeneratorOptions {
+            col_count: 9,
+            ..GeneratorOptions::default_for(MazeGeneratorType::Vanilla)
+        };
+        let generator = registry.create("vanilla", Some(options))?;
+        assert_eq!(generator.generate_with_seed(3)?.config.col_count, 9);
+
+        assert!(matches!(
+            registry.create("labyrinth", None),
+            Err(GeneratorError::UnknownGenerator { id }) if id == "labyrinth"
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_register_custom_generator() -> Result<(), Box<dyn Error>> {
+        let mut registry = GeneratorRegistry::new();
+        let options = GeneratorOptions {
+            col_count: 10,
+            row_count: 6,
+            ..GeneratorOptions::default_for(MazeGeneratorType::Vanilla)
+        };
+
+        registry.register("small", "Small vanilla board", options.clone(), |options| {
+            Ok(Box::new(VanillaGenerator::new(options)?))
+        })?;
+
+        assert_eq!(
+            registry.get("small").map(|info| &info.default_options),
+            Some(&options)
+        );
+        assert_eq!(
+            registry
+                .create("small", None)?
+                .generate_with_seed(1)?
+                .config
+                .row_count,
+            6
+        );
+        assert!(matches!(
+            registry.register("small", "Again", options, |options| {
+                Ok(Box::new(VanillaGenerator::new(options)?))
+            }),
+            Err(GeneratorError::DuplicateGenerator { .. })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_register_generator_for_process() -> Result<(), Box<dyn Error>> {
+        let options = GeneratorOptions {
+            col_count: 9,
+            row_count: 5,
+            ..GeneratorOptions::default_for(MazeGeneratorType::Vanilla)
+        };
+
+        register_generator("narrow", "Narrow vanilla board", options, |options| {
+            Ok(Box::new(VanillaGenerator::new(options)?))
+        })?;
+
+        assert!(list_generators().iter().any(|info| info.id == "narrow"));
+        assert_eq!(
+            crate::create_generator_by_id("narrow", None)?
+                .generate_with_seed(1)?
+                .config
+                .col_count,
+            9
+        );
+        assert!(matches!(
+            register_generator(
+                "vanilla",
+                "Again",
+                GeneratorOptions::default_for(MazeGeneratorType::Vanilla),
+                |options| { Ok(Box::new(VanillaGenerator::new(options)?)) }
+            ),
+            Err(GeneratorError::DuplicateGenerator { .. })
+        ));
+
+        Ok(())
+    }
+}
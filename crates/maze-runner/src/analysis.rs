@@ -0,0 +1,640 @@
+use maze_core::{Maze, Position, TileKind};
+
+use crate::{
+    nodes::{Nodes, TileGrid},
+    runner::{create_board_with_soft_walls, get_ascending_checkpoint_levels},
+    runner_error::MazeRunnerError,
+    MazeRunResult, MazeRunner,
+};
+
+/// Flags of tiles of all floors indexed by floor, column and row
+pub type TileMask = Vec<Vec<Vec<bool>>>;
+
+/// Tiles a run can make use of while heading for the checkpoints of a single level
+pub struct SegmentReachability {
+    pub level: i32,
+    /// Tiles connected both to some start of the segment and to some checkpoint of the level
+    pub relevant: TileMask,
+}
+
+pub struct Reachability {
+    /// Tiles that can be entered from any entrypoint
+    pub reachable: TileMask,
+    /// Enterable tiles that no segment can make use of, walls are never dead
+    pub dead: TileMask,
+    /// Segments in the order of ascending checkpoint levels
+    pub segments: Vec<SegmentReachability>,
+}
+
+impl Reachability {
+    pub fn dead_cells(&self) -> Vec<Position> {
+        get_mask_positions(&self.dead)
+    }
+}
+
+/// Finds empty tiles whose walling would make the maze unsolvable with the current soft walls.
+///
+/// A tile can only disconnect the maze if it separates the start and the checkpoint of some
+/// segment of the current solved path, otherwise every segment can be walked around it.
+/// Rules scoring over every entrypoint are broken by cutting off any of them, so the paths
+/// of all entrypoints are searched for separators then.
+/// Such separators are found as articulation points per segment and each of them is confirmed
+/// by a run, as another entrypoint or checkpoint of the same level may still be reachable.
+/// Returns no tiles if the maze is already unsolvable.
+pub fn disconnecting_cells(
+    maze: &Maze,
+    current_walls: &[Position],
+) -> Result<Vec<Position>, MazeRunnerError> {
+    let runner = MazeRunner::new(maze);
+    let walls = current_walls.to_vec();
+
+    let runs: Vec<MazeRunResult> = match runner.scoring_rule().is_lowest_score() {
+        true => runner.run(&walls)?.into_iter().collect(),
+        false if runner.run_score(&walls)?.is_some() => runner
+            .run_entrypoints(&walls)?
+            .into_iter()
+            .filter_map(|run| run.result)
+            .collect(),
+        false => vec![],
+    };
+    if runs.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let nodes = Nodes::new(&create_board_with_soft_walls(maze, &walls)?);
+    let mut candidates: Vec<Position> = vec![];
+    for run in runs.iter() {
+        for segment in run.segments()? {
+            candidates.extend(
+                get_segment_separators(&nodes, &segment.start, &segment.checkpoint, segment.level)
+                    .into_iter()
+                    .filter(|position| get_maze_tile(maze, position) == TileKind::Empty),
+            );
+        }
+    }
+    candidates.sort_by_key(|position| (position.floor_index(), position.x, position.y));
+    candidates.dedup();
+
+    let wall_sets: Vec<Vec<Position>> = candidates
+        .iter()
+        .map(|position| [current_walls, &[*position]].concat())
+        .collect();
+
+    let mut disconnecting = vec![];
+    for (position, score) in candidates
+        .into_iter()
+        .zip(runner.run_score_batch(&wall_sets))
+    {
+        if score?.is_none() {
+            disconnecting.push(position);
+        }
+    }
+
+    Ok(disconnecting)
+}
+
+/// Computes which tiles can be reached from the entrypoints with the current soft walls,
+/// overall and per level segment.
+///
+/// Segment of the first level starts at the entrypoints, every next one starts at the checkpoints
+/// of the previous level its segment reached. Entering a checkpoint of the level ends the segment,
+/// so the search never continues past them. Tiles outside of every segment are dead, walling
+/// them can't change any score.
+pub fn reachability(
+    maze: &Maze,
+    current_walls: &[Position],
+) -> Result<Reachability, MazeRunnerError> {
+    let floors = create_board_with_soft_walls(maze, &current_walls.to_vec())?;
+    let nodes = Nodes::new(&floors);
+
+    let entrypoints = get_entrypoints(maze, &nodes);
+    let reachable = get_flooded_grid(&nodes, &entrypoints, |_| true);
+    let segments = get_segment_grids(
+        &nodes,
+        &get_ascending_checkpoint_levels(&floors),
+        entrypoints,
+    );
+    let dead = get_dead_grid(&nodes, &segments);
+
+    Ok(Reachability {
+        reachable: reachable.into_inner(),
+        dead: dead.into_inner(),
+        segments: segments
+            .into_iter()
+            .map(|(level, relevant)| SegmentReachability {
+                level,
+                relevant: relevant.into_inner(),
+            })
+            .collect(),
+    })
+}
+
+/// Same as the dead tiles of `reachability` on nodes that already have the soft walls
+pub(crate) fn get_dead_tiles(
+    maze: &Maze,
+    nodes: &Nodes,
+    ascending_checkpoint_levels: &[i32],
+) -> TileGrid<bool> {
+    let segments = get_segment_grids(
+        nodes,
+        ascending_checkpoint_levels,
+        get_entrypoints(maze, nodes),
+    );
+
+    get_dead_grid(nodes, &segments)
+}
+
+fn get_entrypoints(maze: &Maze, nodes: &Nodes) -> Vec<Position> {
+    nodes
+        .positions()
+        .filter(|position| get_maze_tile(maze, position) == TileKind::Entrypoint)
+        .collect()
+}
+
+/// Relevant tiles of every level segment, see `SegmentReachability`
+fn get_segment_grids(
+    nodes: &Nodes,
+    ascending_checkpoint_levels: &[i32],
+    entrypoints: Vec<Position>,
+) -> Vec<(i32, TileGrid<bool>)> {
+    let mut segments = vec![];
+    let mut starts = entrypoints;
+    for &level in ascending_checkpoint_levels {
+        let can_expand = |position: &Position| !nodes.get_node(position).is_checkpoint(level);
+
+        let forward = get_flooded_grid(nodes, &starts, can_expand);
+        let reached_checkpoints: Vec<Position> = nodes
+            .positions()
+            .filter(|position| *forward.get(position))
+            .filter(|position| nodes.get_node(position).is_checkpoint(level))
+            .collect();
+        let backward = get_flooded_grid(nodes, &reached_checkpoints, can_expand);
+
+        let mut relevant = nodes.create_grid(false);
+        for position in nodes.positions() {
+            *relevant.get_mut(&position) = *forward.get(&position) && *backward.get(&position);
+        }
+        segments.push((level, relevant));
+        starts = reached_checkpoints;
+    }
+
+    segments
+}
+
+fn get_dead_grid(nodes: &Nodes, segments: &[(i32, TileGrid<bool>)]) -> TileGrid<bool> {
+    let mut dead = nodes.create_grid(false);
+    for position in nodes.positions() {
+        *dead.get_mut(&position) = nodes.get_node(&position).can_enter()
+            && segments
+                .iter()
+                .all(|(_, relevant)| !relevant.get(&position));
+    }
+
+    dead
+}
+
+/// Marks enterable tiles reachable from the starts, tiles failing `can_expand` are marked
+/// but the search doesn't continue from them unless they are starts themselves
+fn get_flooded_grid<F>(nodes: &Nodes, starts: &[Position], can_expand: F) -> TileGrid<bool>
+where
+    F: Fn(&Position) -> bool,
+{
+    let mut grid = nodes.create_grid(false);
+
+    let mut stack = vec![];
+    for start in starts {
+        if nodes.get_node(start).can_enter() && !grid.get(start) {
+            *grid.get_mut(start) = true;
+            stack.push(*start);
+        }
+    }
+
+    while let Some(position) = stack.pop() {
+        for neighbor in nodes.get_neighbors_positions(&position) {
+            if *grid.get(&neighbor) || !nodes.get_node(&neighbor).can_enter() {
+                continue;
+            }
+
+            *grid.get_mut(&neighbor) = true;
+            if can_expand(&neighbor) {
+                stack.push(neighbor);
+            }
+        }
+    }
+
+    grid
+}
+
+fn get_mask_positions(mask: &TileMask) -> Vec<Position> {
+    mask.iter()
+        .enumerate()
+        .flat_map(|(floor, tiles)| {
+            tiles.iter().enumerate().flat_map(move |(x, column)| {
+                column
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, flag)| **flag)
+                    .map(move |(y, _)| Position::on_floor(x, y, floor))
+            })
+        })
+        .collect()
+}
+
+fn get_maze_tile(maze: &Maze, position: &Position) -> TileKind {
+    maze.floors()[position.floor_index()][position.x][position.y]
+}
+
+/// Articulation points lying on every path from the start to the checkpoint of the segment level.
+/// Other checkpoints of the same level are treated as impassable, entering them would end the segment.
+fn get_segment_separators(
+    nodes: &Nodes,
+    start: &Position,
+    checkpoint: &Position,
+    level: i32,
+) -> Vec<Position> {
+    let can_enter = |position: &Position| {
+        let node = nodes.get_node(position);
+        node.can_enter() && (!node.is_checkpoint(level) || position == checkpoint)
+    };
+
+    let mut discovery: TileGrid<Option<u32>> = nodes.create_grid(None);
+    let mut low = nodes.create_grid(0);
+    let mut parent: TileGrid<Option<Position>> = nodes.create_grid(None);
+
+    // iterative depth first search, recursion could overflow the stack on big boards
+    let mut time = 0;
+    let mut stack = vec![(*start, nodes.get_neighbors_positions(start))];
+    *discovery.get_mut(start) = Some(time);
+
+    while let Some((position, neighbors)) = stack.last_mut() {
+        let position = *position;
+
+        match neighbors.pop() {
+            Some(neighbor) if can_enter(&neighbor) => match *discovery.get(&neighbor) {
+                Some(neighbor_discovery) => {
+                    if *parent.get(&position) != Some(neighbor) {
+                        let position_low = low.get_mut(&position);
+                        *position_low = (*position_low).min(neighbor_discovery);
+                    }
+                }
+                None => {
+                    time += 1;
+                    *discovery.get_mut(&neighbor) = Some(time);
+                    *low.get_mut(&neighbor) = time;
+                    *parent.get_mut(&neighbor) = Some(position);
+                    stack.push((neighbor, nodes.get_neighbors_positions(&neighbor)));
+                }
+            },
+            Some(_) => (),
+            None => {
+                stack.pop();
+                if let Some(previous) = *parent.get(&position) {
+                    let position_low = *low.get(&position);
+                    let previous_low = low.get_mut(&previous);
+                    *previous_low = (*previous_low).min(position_low);
+                }
+            }
+        }
+    }
+
+    // walk the tree path back from the checkpoint, a vertex separates the segment
+    // if no back edge from the subtree containing the checkpoint climbs above it
+    let mut separators = vec![];
+    let mut current = *checkpoint;
+    while let Some(previous) = *parent.get(&current) {
+        if previous == *start {
+            break;
+        }
+
+        if discovery
+            .get(&previous)
+            .is_some_and(|d| *low.get(&current) >= d)
+        {
+            separators.push(previous);
+        }
+        current = previous;
+    }
+
+    separators
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use maze_core::{Checkpoint, MazeConfig, ScoringRuleType};
+
+    use super::*;
+
+    fn get_brute_force_disconnecting_cells(
+        maze: &Maze,
+        current_walls: &[Position],
+    ) -> Result<Vec<Position>, Box<dyn Error>> {
+        let runner = MazeRunner::new(maze);
+        let mut disconnecting = vec![];
+        // nothing can disconnect a maze that is already unsolvable
+        if runner.run_score(&current_walls.to_vec())?.is_none() {
+            return Ok(disconnecting);
+        }
+
+        for (x, row) in maze.floors()[0].iter().enumerate() {
+            for (y, kind) in row.iter().enumerate() {
+                let position = Position::new(x, y);
+                if *kind != TileKind::Empty || current_walls.contains(&position) {
+                    continue;
+                }
+
+                let mut walls = current_walls.to_vec();
+                walls.push(position);
+                if runner.run_score(&walls)?.is_none() {
+                    disconnecting.push(position);
+                }
+            }
+        }
+
+        Ok(disconnecting)
+    }
+
+    #[test]
+    fn test_disconnecting_cells_corridor() -> Result<(), Box<dyn Error>> {
+        let maze = Maze::new(&MazeConfig {
+            col_count: 5,
+            row_count: 2,
+            floor_count: 1,
+            max_soft_wall_count: 3,
+            walls: vec![Position::new(1, 1)],
+            entrypoints: vec![Position::new(0, 0)],
+            checkpoints: vec![Checkpoint {
+                position: Position::new(4, 0),
+                level: 1,
+            }],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
+        })?;
+
+        assert_eq!(
+            disconnecting_cells(&maze, &[])?,
+            vec![Position::new(1, 0), Position::new(2, 0)]
+        );
+        assert_eq!(
+            disconnecting_cells(&maze, &[Position::new(3, 1)])?,
+            vec![
+                Position::new(1, 0),
+                Position::new(2, 0),
+                Position::new(3, 0)
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_disconnecting_cells_unsolvable() -> Result<(), Box<dyn Error>> {
+        let maze = Maze::new(&MazeConfig {
+            col_count: 4,
+            row_count: 1,
+            floor_count: 1,
+            max_soft_wall_count: 3,
+            walls: vec![],
+            entrypoints: vec![Position::new(0, 0)],
+            checkpoints: vec![Checkpoint {
+                position: Position::new(3, 0),
+                level: 1,
+            }],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
+        })?;
+
+        assert_eq!(disconnecting_cells(&maze, &[Position::new(1, 0)])?, vec![]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_disconnecting_cells_of_every_entrypoint() -> Result<(), Box<dyn Error>> {
+        // the entrypoint at (0,0) scores worse, so the representative run starts at (3,0)
+        let maze = Maze::new(&MazeConfig {
+            col_count: 4,
+            row_count: 3,
+            floor_count: 1,
+            max_soft_wall_count: 3,
+            walls: vec![Position::new(1, 1), Position::new(1, 0)],
+            entrypoints: vec![Position::new(0, 0), Position::new(3, 0)],
+            checkpoints: vec![Checkpoint {
+                position: Position::new(3, 2),
+                level: 1,
+            }],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::AllEntrypoints,
+        })?;
+
+        let disconnecting = disconnecting_cells(&maze, &[])?;
+        for position in [
+            Position::new(0, 1),
+            Position::new(0, 2),
+            Position::new(1, 2),
+            Position::new(2, 2),
+        ] {
+            assert!(disconnecting.contains(&position), "{} is missing", position);
+        }
+        assert_eq!(
+            disconnecting,
+            get_brute_force_disconnecting_cells(&maze, &[])?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_disconnecting_cells_against_brute_force() -> Result<(), Box<dyn Error>> {
+        let config = MazeConfig {
+            col_count: 9,
+            row_count: 9,
+            floor_count: 1,
+            max_soft_wall_count: 200,
+            walls: vec![
+                Position::new(0, 7),
+                Position::new(1, 7),
+                Position::new(1, 4),
+                Position::new(3, 2),
+                Position::new(6, 6),
+            ],
+            entrypoints: vec![Position::new(0, 0), Position::new(0, 8)],
+            checkpoints: vec![
+                Checkpoint {
+                    position: Position::new(0, 6),
+                    level: 1,
+                },
+                Checkpoint {
+                    position: Position::new(3, 0),
+                    level: 2,
+                },
+                Checkpoint {
+                    position: Position::new(4, 4),
+                    level: 2,
+                },
+                Checkpoint {
+                    position: Position::new(8, 8),
+                    level: 3,
+                },
+            ],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
+        };
+
+        let wall_sets = [
+            vec![],
+            vec![Position::new(1, 6), Position::new(1, 5)],
+            vec![
+                Position::new(1, 6),
+                Position::new(1, 5),
+                Position::new(5, 4),
+                Position::new(3, 4),
+                Position::new(4, 5),
+                Position::new(2, 0),
+                Position::new(7, 8),
+            ],
+            vec![
+                Position::new(1, 8),
+                Position::new(0, 5),
+                Position::new(2, 6),
+                Position::new(8, 7),
+            ],
+        ];
+
+        for scoring_rule in [ScoringRuleType::Min, ScoringRuleType::AllEntrypoints] {
+            let maze = Maze::new(&MazeConfig {
+                scoring_rule,
+                ..config.clone()
+            })?;
+
+            for walls in wall_sets.iter() {
+                assert_eq!(
+                    disconnecting_cells(&maze, walls)?,
+                    get_brute_force_disconnecting_cells(&maze, walls)?,
+                    "disconnecting cells differ for {:?} and walls {:?}",
+                    scoring_rule,
+                    walls
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reachability_corridor() -> Result<(), Box<dyn Error>> {
+        let maze = Maze::new(&MazeConfig {
+            col_count: 5,
+            row_count: 1,
+            floor_count: 1,
+            max_soft_wall_count: 1,
+            walls: vec![],
+            entrypoints: vec![Position::new(0, 0)],
+            checkpoints: vec![Checkpoint {
+                position: Position::new(2, 0),
+                level: 1,
+            }],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
+        })?;
+
+        let reachability = reachability(&maze, &[])?;
+        assert_eq!(reachability.reachable, vec![vec![vec![true]; 5]]);
+        // tiles past the exit can be entered but no run ever walks them
+        assert_eq!(
+            reachability.dead_cells(),
+            vec![Position::new(3, 0), Position::new(4, 0)]
+        );
+
+        let reachability = super::reachability(&maze, &[Position::new(1, 0)])?;
+        assert_eq!(
+            reachability.reachable,
+            vec![vec![
+                vec![true],
+                vec![false],
+                vec![false],
+                vec![false],
+                vec![false]
+            ]]
+        );
+        assert_eq!(reachability.segments.len(), 1);
+        assert_eq!(
+            reachability.segments[0].relevant,
+            vec![vec![vec![false]; 5]]
+        );
+        assert_eq!(reachability.dead_cells().len(), 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reachability_segments() -> Result<(), Box<dyn Error>> {
+        let maze = Maze::new(&MazeConfig {
+            col_count: 6,
+            row_count: 3,
+            floor_count: 1,
+            max_soft_wall_count: 3,
+            walls: vec![Position::new(1, 1), Position::new(1, 2)],
+            entrypoints: vec![Position::new(0, 0)],
+            checkpoints: vec![
+                Checkpoint {
+                    position: Position::new(0, 2),
+                    level: 1,
+                },
+                Checkpoint {
+                    position: Position::new(3, 1),
+                    level: 2,
+                },
+            ],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
+        })?;
+
+        let walls = [
+            Position::new(4, 0),
+            Position::new(4, 1),
+            Position::new(4, 2),
+        ];
+        let reachability = reachability(&maze, &walls)?;
+
+        let segment_levels: Vec<i32> = reachability
+            .segments
+            .iter()
+            .map(|segment| segment.level)
+            .collect();
+        assert_eq!(segment_levels, vec![1, 2]);
+
+        // checkpoints of higher levels can be walked over before their segment starts
+        assert_eq!(
+            get_mask_positions(&reachability.segments[0].relevant),
+            vec![
+                Position::new(0, 0),
+                Position::new(0, 1),
+                Position::new(0, 2),
+                Position::new(1, 0),
+                Position::new(2, 0),
+                Position::new(2, 1),
+                Position::new(2, 2),
+                Position::new(3, 0),
+                Position::new(3, 1),
+                Position::new(3, 2),
+            ]
+        );
+        assert!(reachability.segments[1].relevant[0][2][2]);
+        assert!(reachability.segments[1].relevant[0][3][1]);
+        assert!(!reachability.reachable[0][5][1]);
+        assert_eq!(
+            reachability.dead_cells(),
+            vec![
+                Position::new(5, 0),
+                Position::new(5, 1),
+                Position::new(5, 2)
+            ]
+        );
+
+        assert!(super::reachability(&maze, &[Position::new(1, 1)]).is_err());
+
+        Ok(())
+    }
+}
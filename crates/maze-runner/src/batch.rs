@@ -0,0 +1,24 @@
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+/// Evaluates independent items on the rayon thread pool, results keep the order of the items
+#[cfg(feature = "rayon")]
+pub(crate) fn evaluate_in_order<T, R, F>(items: &[T], evaluate: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync + Send,
+{
+    items.par_iter().map(evaluate).collect()
+}
+
+/// Evaluates independent items one by one, parallel evaluation requires the `rayon` feature
+#[cfg(not(feature = "rayon"))]
+pub(crate) fn evaluate_in_order<T, R, F>(items: &[T], evaluate: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync + Send,
+{
+    items.iter().map(evaluate).collect()
+}
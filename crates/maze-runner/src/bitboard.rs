@@ -0,0 +1,508 @@
+use std::ops::{BitAnd, BitOr, Not, Shl, Shr};
+
+use maze_core::{Position, TileBoard, TileFloors, TileKind};
+
+use super::runner_error::MazeRunnerError;
+
+/// Row of the board packed into a single word, bit `x` stands for the tile in column `x`
+pub(crate) trait BitRow:
+    Copy
+    + PartialEq
+    + BitAnd<Output = Self>
+    + BitOr<Output = Self>
+    + Not<Output = Self>
+    + Shl<u32, Output = Self>
+    + Shr<u32, Output = Self>
+{
+    const BITS: u32;
+    const ZERO: Self;
+    const ONE: Self;
+}
+
+impl BitRow for u64 {
+    const BITS: u32 = u64::BITS;
+    const ZERO: Self = 0;
+    const ONE: Self = 1;
+}
+
+impl BitRow for u128 {
+    const BITS: u32 = u128::BITS;
+    const ZERO: Self = 0;
+    const ONE: Self = 1;
+}
+
+/// Layered breadth first search over bitsets, it is used only to evaluate the score
+/// as the distances of separate tiles are not tracked
+#[derive(Debug, Clone)]
+pub(crate) struct Bitboard<R: BitRow> {
+    col_count: usize,
+    empty: Vec<R>,
+    walkable: Vec<R>,
+    entrypoints: Vec<R>,
+    checkpoints: Vec<Vec<R>>,
+}
+
+impl<R: BitRow> Bitboard<R> {
+    /// Returns `None` if the board doesn't fit into the row word
+    pub(crate) fn new(board: &TileBoard, ascending_checkpoint_levels: &[i32]) -> Option<Self> {
+        let col_count = board.len();
+        let row_count = board.first().map_or(0, |column| column.len());
+        if col_count == 0 || col_count > R::BITS as usize {
+            return None;
+        }
+
+        let mut bitboard = Self {
+            col_count,
+            empty: vec![R::ZERO; row_count],
+            walkable: vec![R::ZERO; row_count],
+            entrypoints: vec![R::ZERO; row_count],
+            checkpoints: vec![vec![R::ZERO; row_count]; ascending_checkpoint_levels.len()],
+        };
+
+        for (x, column) in board.iter().enumerate() {
+            for (y, kind) in column.iter().enumerate() {
+                let bit = R::ONE << x as u32;
+                match kind {
+                    TileKind::Wall => continue,
+                    TileKind::Empty => bitboard.empty[y] = bitboard.empty[y] | bit,
+                    // stairs can't lead anywhere on a single floor board, they are walked like any other tile
+                    TileKind::Stairs { .. } => (),
+                    TileKind::Entrypoint => bitboard.entrypoints[y] = bitboard.entrypoints[y] | bit,
+                    TileKind::Checkpoint { level } => {
+                        if let Ok(index) = ascending_checkpoint_levels.binary_search(level) {
+                            bitboard.checkpoints[index][y] = bitboard.checkpoints[index][y] | bit;
+                        }
+                    }
+                }
+                bitboard.walkable[y] = bitboard.walkable[y] | bit;
+            }
+        }
+
+        Some(bitboard)
+    }
+
+    /// Shortest distance from any entrypoint through all checkpoint levels, the same as the lowest
+    /// score of separate entrypoint runs. Soft walls are validated the same way the general runner does.
+    pub(crate) fn run(
+        &self,
+        soft_walls: &[Position],
+        max_soft_wall_count: u32,
+    ) -> Result<Option<u32>, MazeRunnerError> {
+        let walkable = self.get_walkable_with_soft_walls(soft_walls, max_soft_wall_count)?;
+        Ok(self.search(&self.entrypoints, &walkable))
+    }
+
+    /// Scores of separate entrypoint runs in the order of the given entrypoints
+    pub(crate) fn run_entrypoints(
+        &self,
+        entrypoints: &[Position],
+        soft_walls: &[Position],
+        max_soft_wall_count: u32,
+    ) -> Result<Vec<Option<u32>>, MazeRunnerError> {
+        let walkable = self.get_walkable_with_soft_walls(soft_walls, max_soft_wall_count)?;
+
+        Ok(entrypoints
+            .iter()
+            .map(|entrypoint| {
+                let mut start = vec![R::ZERO; walkable.len()];
+                start[entrypoint.y] = R::ONE << entrypoint.x as u32;
+                self.search(&start, &walkable)
+            })
+            .collect())
+    }
+
+    fn search(&self, start: &[R], walkable: &[R]) -> Option<u32> {
+        let level_count = self.checkpoints.len();
+        if level_count == 0 {
+            return None;
+        }
+
+        // frontier and visited tiles of every level, the level index says how many levels were completed
+        let mut frontiers = vec![vec![R::ZERO; walkable.len()]; level_count];
+        let mut visited = frontiers.clone();
+        frontiers[0] = start.to_vec();
+        visited[0] = start.to_vec();
+
+        let mut distance = 0;
+        loop {
+            if frontiers.iter().all(|frontier| is_empty(frontier)) {
+                return None;
+            }
+            distance += 1;
+
+            let mut next_frontiers = vec![vec![R::ZERO; walkable.len()]; level_count];
+            for level_index in 0..level_count {
+                let reached = self.expand(&frontiers[level_index], walkable);
+                let checkpoints = &self.checkpoints[level_index];
+
+                for y in 0..reached.len() {
+                    let staying = reached[y] & !checkpoints[y] & !visited[level_index][y];
+                    visited[level_index][y] = visited[level_index][y] | staying;
+                    next_frontiers[level_index][y] = next_frontiers[level_index][y] | staying;
+                }
+
+                let advancing: Vec<R> = (0..reached.len())
+                    .map(|y| reached[y] & checkpoints[y])
+                    .collect();
+                if is_empty(&advancing) {
+                    continue;
+                }
+
+                // entering the checkpoint of the last level solves the maze
+                let next_level_index = level_index + 1;
+                if next_level_index == level_count {
+                    return Some(distance);
+                }
+
+                for y in 0..advancing.len() {
+                    let entered = advancing[y] & !visited[next_level_index][y];
+                    visited[next_level_index][y] = visited[next_level_index][y] | entered;
+                    next_frontiers[next_level_index][y] =
+                        next_frontiers[next_level_index][y] | entered;
+                }
+            }
+
+            frontiers = next_frontiers;
+        }
+    }
+
+    fn get_walkable_with_soft_walls(
+        &self,
+        soft_walls: &[Position],
+        max_soft_wall_count: u32,
+    ) -> Result<Vec<R>, MazeRunnerError> {
+        if max_soft_wall_count < soft_walls.len() as u32 {
+            return Err(MazeRunnerError::TooManySoftWalls {
+                limit: max_soft_wall_count,
+            });
+        }
+
+        let mut empty = self.empty.clone();
+        let mut walkable = self.walkable.clone();
+        for &position in soft_walls {
+            if position.x >= self.col_count
+                || position.y >= empty.len()
+                || position.floor_index() > 0
+            {
+                return Err(MazeRunnerError::WallOutOfBounds { position });
+            }
+
+            let bit = R::ONE << position.x as u32;
+            if empty[position.y] & bit == R::ZERO {
+                return Err(MazeRunnerError::OverlappingWall { position });
+            }
+
+            empty[position.y] = empty[position.y] & !bit;
+            walkable[position.y] = walkable[position.y] & !bit;
+        }
+
+        Ok(walkable)
+    }
+
+    /// Walkable tiles next to the frontier, the same neighborhood as `Nodes::get_neighbors_positions`
+    fn expand(&self, frontier: &[R], walkable: &[R]) -> Vec<R> {
+        let row_count = frontier.len();
+
+        (0..row_count)
+            .map(|y| {
+                let mut reached = (frontier[y] << 1) | (frontier[y] >> 1);
+                if y > 0 {
+                    reached = reached | frontier[y - 1];
+                }
+                if y + 1 < row_count {
+                    reached = reached | frontier[y + 1];
+                }
+                // walkable tiles never lay outside the board so the shifted out bits are dropped here
+                reached & walkable[y]
+            })
+            .collect()
+    }
+}
+
+fn is_empty<R: BitRow>(rows: &[R]) -> bool {
+    rows.iter().all(|row| *row == R::ZERO)
+}
+
+/// Bitboard with the narrowest row word the board fits into
+#[derive(Debug, Clone)]
+pub(crate) enum FastBoard {
+    U64(Bitboard<u64>),
+    U128(Bitboard<u128>),
+}
+
+impl FastBoard {
+    /// Returns `None` for boards with several floors, stairs are not supported by the bitsets
+    pub(crate) fn new(floors: &TileFloors, ascending_checkpoint_levels: &[i32]) -> Option<Self> {
+        let board = match floors.as_slice() {
+            [board] => board,
+            _ => return None,
+        };
+
+        Bitboard::new(board, ascending_checkpoint_levels)
+            .map(FastBoard::U64)
+            .or_else(|| Bitboard::new(board, ascending_checkpoint_levels).map(FastBoard::U128))
+    }
+
+    pub(crate) fn run(
+        &self,
+        soft_walls: &[Position],
+        max_soft_wall_count: u32,
+    ) -> Result<Option<u32>, MazeRunnerError> {
+        match self {
+            FastBoard::U64(bitboard) => bitboard.run(soft_walls, max_soft_wall_count),
+            FastBoard::U128(bitboard) => bitboard.run(soft_walls, max_soft_wall_count),
+        }
+    }
+
+    pub(crate) fn run_entrypoints(
+        &self,
+        entrypoints: &[Position],
+        soft_walls: &[Position],
+        max_soft_wall_count: u32,
+    ) -> Result<Vec<Option<u32>>, MazeRunnerError> {
+        match self {
+            FastBoard::U64(bitboard) => {
+                bitboard.run_entrypoints(entrypoints, soft_walls, max_soft_wall_count)
+            }
+            FastBoard::U128(bitboard) => {
+                bitboard.run_entrypoints(entrypoints, soft_walls, max_soft_wall_count)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use maze_core::{Checkpoint, Maze, MazeConfig, ScoringRuleType};
+
+    use crate::{create_scoring_rule, runner::get_ascending_checkpoint_levels, MazeRunner};
+
+    use super::*;
+
+    /// Deterministic pseudo random generator, so the differential tests are reproducible
+    struct Lcg(u64);
+
+    impl Lcg {
+        fn next(&mut self, n: usize) -> usize {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (self.0 >> 33) as usize % n
+        }
+    }
+
+    fn get_random_maze(
+        rng: &mut Lcg,
+        col_count: usize,
+        row_count: usize,
+    ) -> Result<(Maze, Vec<Position>), Box<dyn Error>> {
+        let mut positions: Vec<Position> = (0..col_count)
+            .flat_map(|x| (0..row_count).map(move |y| Position::new(x, y)))
+            .collect();
+        for i in (1..positions.len()).rev() {
+            positions.swap(i, rng.next(i + 1));
+        }
+        let mut positions = positions.into_iter();
+        let size = col_count * row_count;
+
+        let entrypoints = positions.by_ref().take(1 + rng.next(3)).collect();
+        let mut checkpoints = vec![];
+        for level in 1..=1 + rng.next(3) as i32 {
+            for position in positions.by_ref().take(1 + rng.next(2)) {
+                checkpoints.push(Checkpoint { position, level });
+            }
+        }
+        let walls = positions.by_ref().take(rng.next(size / 4)).collect();
+        let soft_walls: Vec<Position> = positions.by_ref().take(rng.next(size / 5)).collect();
+
+        let maze = Maze::new(&MazeConfig {
+            col_count,
+            row_count,
+            floor_count: 1,
+            max_soft_wall_count: soft_walls.len() as u32,
+            walls,
+            entrypoints,
+            checkpoints,
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
+        })?;
+
+        Ok((maze, soft_walls))
+    }
+
+    fn get_general_score(
+        maze: &Maze,
+        soft_walls: &Vec<Position>,
+    ) -> Result<Option<u32>, MazeRunnerError> {
+        Ok(MazeRunner::new(maze)
+            .run(soft_walls)?
+            .map(|result| result.score()))
+    }
+
+    fn assert_rules_match_general_runner(
+        maze: &Maze,
+        soft_walls: &Vec<Position>,
+    ) -> Result<(), MazeRunnerError> {
+        for rule_type in [
+            ScoringRuleType::Max,
+            ScoringRuleType::Sum,
+            ScoringRuleType::Average,
+            ScoringRuleType::AllEntrypoints,
+        ] {
+            let runner = MazeRunner::with_scoring_rule(maze, create_scoring_rule(rule_type));
+            assert_eq!(
+                runner.score(soft_walls)?,
+                runner
+                    .scoring_rule()
+                    .score(&runner.run_entrypoints(soft_walls)?)
+            );
+            assert_eq!(
+                runner.run_score(soft_walls)?,
+                runner.run(soft_walls)?.map(|result| result.score()),
+                "{:?}",
+                rule_type
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_bitboard_word_selection() -> Result<(), Box<dyn Error>> {
+        let get_fast_board = |col_count| -> Result<Option<FastBoard>, Box<dyn Error>> {
+            let maze = Maze::new(&MazeConfig {
+                col_count,
+                row_count: 2,
+                floor_count: 1,
+                max_soft_wall_count: 0,
+                walls: vec![],
+                entrypoints: vec![Position::new(0, 0)],
+                checkpoints: vec![Checkpoint {
+                    position: Position::new(1, 1),
+                    level: 1,
+                }],
+                stairs: vec![],
+                scoring_rule: ScoringRuleType::Min,
+            })?;
+            Ok(FastBoard::new(maze.floors(), &[1]))
+        };
+
+        assert!(matches!(get_fast_board(64)?, Some(FastBoard::U64(_))));
+        assert!(matches!(get_fast_board(65)?, Some(FastBoard::U128(_))));
+        assert!(matches!(get_fast_board(128)?, Some(FastBoard::U128(_))));
+        assert!(get_fast_board(129)?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_bitboard_matches_general_runner() -> Result<(), Box<dyn Error>> {
+        let mut rng = Lcg(42);
+        let mut solved_count = 0;
+
+        for _ in 0..400 {
+            let col_count = 2 + rng.next(20);
+            let row_count = 2 + rng.next(10);
+            let (maze, soft_walls) = get_random_maze(&mut rng, col_count, row_count)?;
+
+            let levels = get_ascending_checkpoint_levels(maze.floors());
+            let narrow =
+                Bitboard::<u64>::new(&maze.floors()[0], &levels).ok_or("board should fit")?;
+            let wide =
+                Bitboard::<u128>::new(&maze.floors()[0], &levels).ok_or("board should fit")?;
+
+            let expected = get_general_score(&maze, &soft_walls)?;
+            let limit = maze.max_soft_wall_count();
+            assert_eq!(narrow.run(&soft_walls, limit)?, expected);
+            assert_eq!(wide.run(&soft_walls, limit)?, expected);
+            assert_eq!(MazeRunner::new(&maze).run_score(&soft_walls)?, expected);
+
+            let entrypoint_scores: Vec<Option<u32>> = MazeRunner::new(&maze)
+                .run_entrypoints(&soft_walls)?
+                .into_iter()
+                .map(|run| run.result.map(|result| result.score()))
+                .collect();
+            assert_eq!(
+                narrow.run_entrypoints(maze.entrypoints(), &soft_walls, limit)?,
+                entrypoint_scores
+            );
+            assert_rules_match_general_runner(&maze, &soft_walls)?;
+
+            if expected.is_some() {
+                solved_count += 1;
+            }
+        }
+
+        // make sure the comparison isn't dominated by unsolvable mazes
+        assert!(solved_count > 100);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_bitboard_matches_general_runner_on_wide_boards() -> Result<(), Box<dyn Error>> {
+        let mut rng = Lcg(7);
+
+        for &(col_count, row_count) in
+            [(63, 3), (64, 4), (65, 3), (100, 2), (128, 3), (130, 2)].iter()
+        {
+            for _ in 0..5 {
+                let (maze, soft_walls) = get_random_maze(&mut rng, col_count, row_count)?;
+
+                assert_eq!(
+                    MazeRunner::new(&maze).run_score(&soft_walls)?,
+                    get_general_score(&maze, &soft_walls)?
+                );
+                assert_rules_match_general_runner(&maze, &soft_walls)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_bitboard_rejects_invalid_soft_walls() -> Result<(), Box<dyn Error>> {
+        let maze = Maze::new(&MazeConfig {
+            col_count: 4,
+            row_count: 3,
+            floor_count: 1,
+            max_soft_wall_count: 2,
+            walls: vec![Position::new(1, 1)],
+            entrypoints: vec![Position::new(0, 0)],
+            checkpoints: vec![Checkpoint {
+                position: Position::new(3, 2),
+                level: 1,
+            }],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
+        })?;
+
+        let runner = MazeRunner::new(&maze);
+        let wall_sets = [
+            vec![
+                Position::new(1, 0),
+                Position::new(2, 0),
+                Position::new(3, 0),
+            ],
+            vec![Position::new(4, 0)],
+            vec![Position::new(0, 3)],
+            vec![Position::on_floor(1, 0, 1)],
+            vec![Position::new(1, 1)],
+            vec![Position::new(0, 0)],
+            vec![Position::new(2, 1), Position::new(2, 1)],
+        ];
+
+        for walls in wall_sets.iter() {
+            let expected = runner.run(walls).err().ok_or("walls should be invalid")?;
+            assert_eq!(
+                format!("{:?}", runner.run_score(walls).err()),
+                format!("{:?}", Some(expected))
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,392 @@
+use std::collections::{HashSet, VecDeque};
+
+use maze_core::{Maze, Position, ScoringRuleType};
+
+use crate::{
+    nodes::Nodes,
+    runner::{create_board_with_soft_walls, get_ascending_checkpoint_levels},
+    runner_error::MazeRunnerError,
+    scoring::{create_scoring_rule, EntrypointScore},
+};
+
+/// Cheap upper bound of the best score achievable on the maze with any soft wall placement.
+///
+/// Shortest path between two consecutive checkpoint levels never visits the same tile twice,
+/// so each level segment is bounded by the number of tiles reachable from its start. Segments
+/// are summed per entrypoint and the bounds of entrypoints are combined by the scoring rule of
+/// the maze, e.g. the best entrypoint is taken for `Min`, because soft walls may cut off all
+/// the others. Returns `0` if the maze cannot be solved even without any soft walls.
+pub fn upper_bound(maze: &Maze) -> u32 {
+    let nodes = Nodes::new(maze.floors());
+    let ascending_checkpoint_levels = get_ascending_checkpoint_levels(maze.floors());
+
+    get_upper_bound(maze, &nodes, &ascending_checkpoint_levels)
+}
+
+/// Same as `upper_bound(maze) > score` for the maze with the soft walls turned into walls.
+///
+/// For rules never scoring over their best entrypoint it stops at the first entrypoint whose bound
+/// exceeds the score, so it is cheaper than computing the bound when checking whether soft walls
+/// placed so far left any room for a better score.
+pub fn exceeds_upper_bound(
+    maze: &Maze,
+    soft_walls: &Vec<Position>,
+    score: u32,
+) -> Result<bool, MazeRunnerError> {
+    let floors = create_board_with_soft_walls(maze, soft_walls)?;
+    let nodes = Nodes::new(&floors);
+    let ascending_checkpoint_levels = get_ascending_checkpoint_levels(&floors);
+
+    Ok(nodes_exceed_upper_bound(
+        maze,
+        &nodes,
+        &ascending_checkpoint_levels,
+        score,
+    ))
+}
+
+/// Same as `exceeds_upper_bound` on nodes that already have the soft walls
+pub(crate) fn nodes_exceed_upper_bound(
+    maze: &Maze,
+    nodes: &Nodes,
+    ascending_checkpoint_levels: &[i32],
+    score: u32,
+) -> bool {
+    match maze.scoring_rule() {
+        ScoringRuleType::Min | ScoringRuleType::Max | ScoringRuleType::Average => {
+            maze.entrypoints().iter().any(|entrypoint| {
+                matches!(
+                    get_entrypoint_upper_bound(nodes, ascending_checkpoint_levels, entrypoint),
+                    Some(bound) if bound > score
+                )
+            })
+        }
+        _ => get_upper_bound(maze, nodes, ascending_checkpoint_levels) > score,
+    }
+}
+
+fn get_upper_bound(maze: &Maze, nodes: &Nodes, ascending_checkpoint_levels: &[i32]) -> u32 {
+    let entrypoint_bounds: Vec<EntrypointScore> = maze
+        .entrypoints()
+        .iter()
+        .map(|&entrypoint| EntrypointScore {
+            entrypoint,
+            score: get_entrypoint_upper_bound(nodes, ascending_checkpoint_levels, &entrypoint),
+        })
+        .collect();
+
+    create_scoring_rule(maze.scoring_rule()).upper_bound(&entrypoint_bounds)
+}
+
+fn get_entrypoint_upper_bound(
+    nodes: &Nodes,
+    ascending_checkpoint_levels: &[i32],
+    entrypoint: &Position,
+) -> Option<u32> {
+    let mut segment_starts = vec![*entrypoint];
+    let mut bound = 0;
+
+    for &level in ascending_checkpoint_levels {
+        let mut segment_bound = 0;
+        let mut reached_checkpoints = HashSet::new();
+
+        for start in segment_starts.iter() {
+            let (tile_count, checkpoints) = explore_segment(nodes, start, level);
+            segment_bound = segment_bound.max(tile_count);
+            reached_checkpoints.extend(checkpoints);
+        }
+
+        // no checkpoint of this level is reachable so there is no solution from this entrypoint
+        if reached_checkpoints.is_empty() {
+            return None;
+        }
+
+        bound += segment_bound;
+        segment_starts = reached_checkpoints.into_iter().collect();
+    }
+
+    Some(bound)
+}
+
+/// Counts tiles reachable from the segment start without passing through a checkpoint of the segment level
+/// and collects checkpoints of that level that terminate the segment
+fn explore_segment(nodes: &Nodes, start: &Position, level: i32) -> (u32, Vec<Position>) {
+    let mut visited = nodes.create_grid(false);
+    *visited.get_mut(start) = true;
+    let mut queue = VecDeque::from([*start]);
+
+    let mut tile_count = 0;
+    let mut checkpoints = vec![];
+
+    while let Some(position) = queue.pop_front() {
+        if nodes.get_node(&position).is_checkpoint(level) {
+            checkpoints.push(position);
+            continue;
+        }
+
+        tile_count += 1;
+        for neighbor_position in nodes.get_neighbors_positions(&position) {
+            if nodes.get_node(&neighbor_position).can_enter() && !visited.get(&neighbor_position) {
+                *visited.get_mut(&neighbor_position) = true;
+                queue.push_back(neighbor_position);
+            }
+        }
+    }
+
+    (tile_count, checkpoints)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use maze_core::{Checkpoint, MazeConfig, ScoringRuleType, TileKind};
+
+    use crate::MazeRunner;
+
+    use super::*;
+
+    const SCORING_RULES: [ScoringRuleType; 5] = [
+        ScoringRuleType::Min,
+        ScoringRuleType::Max,
+        ScoringRuleType::Sum,
+        ScoringRuleType::Average,
+        ScoringRuleType::AllEntrypoints,
+    ];
+
+    fn get_brute_force_best_score(maze: &Maze) -> Result<u32, Box<dyn Error>> {
+        let empty_positions: Vec<Position> = maze.floors()[0]
+            .iter()
+            .enumerate()
+            .flat_map(|(x, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(|(_, kind)| **kind == TileKind::Empty)
+                    .map(move |(y, _)| Position::new(x, y))
+            })
+            .collect();
+
+        let runner = MazeRunner::new(maze);
+        let mut best_score = 0;
+
+        for mask in 0u32..(1 << empty_positions.len()) {
+            if mask.count_ones() > maze.max_soft_wall_count() {
+                continue;
+            }
+
+            let walls = empty_positions
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| mask & (1 << i) != 0)
+                .map(|(_, position)| *position)
+                .collect();
+
+            if let Some(result) = runner.run(&walls)? {
+                best_score = best_score.max(result.score());
+            }
+        }
+
+        Ok(best_score)
+    }
+
+    #[test]
+    fn test_upper_bound_corridor_is_exact() -> Result<(), Box<dyn Error>> {
+        let maze = Maze::new(&MazeConfig {
+            col_count: 5,
+            row_count: 1,
+            floor_count: 1,
+            max_soft_wall_count: 2,
+            walls: vec![],
+            entrypoints: vec![Position::new(0, 0)],
+            checkpoints: vec![Checkpoint {
+                position: Position::new(4, 0),
+                level: 1,
+            }],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
+        })?;
+
+        assert_eq!(upper_bound(&maze), 4);
+        assert_eq!(get_brute_force_best_score(&maze)?, 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_upper_bound_unsolvable() -> Result<(), Box<dyn Error>> {
+        let maze = Maze::new(&MazeConfig {
+            col_count: 3,
+            row_count: 2,
+            floor_count: 1,
+            max_soft_wall_count: 2,
+            walls: vec![Position::new(1, 0), Position::new(1, 1)],
+            entrypoints: vec![Position::new(0, 0)],
+            checkpoints: vec![Checkpoint {
+                position: Position::new(2, 1),
+                level: 1,
+            }],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
+        })?;
+
+        assert_eq!(upper_bound(&maze), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_exceeds_upper_bound() -> Result<(), Box<dyn Error>> {
+        let config = MazeConfig {
+            col_count: 4,
+            row_count: 3,
+            floor_count: 1,
+            max_soft_wall_count: 3,
+            walls: vec![],
+            entrypoints: vec![Position::new(0, 0), Position::new(0, 2)],
+            checkpoints: vec![Checkpoint {
+                position: Position::new(3, 1),
+                level: 1,
+            }],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
+        };
+        let wall_sets = [
+            vec![],
+            vec![Position::new(1, 0), Position::new(1, 1)],
+            vec![
+                Position::new(1, 0),
+                Position::new(1, 1),
+                Position::new(1, 2),
+            ],
+        ];
+
+        for scoring_rule in SCORING_RULES {
+            let config = MazeConfig {
+                scoring_rule,
+                ..config.clone()
+            };
+            let maze = Maze::new(&config)?;
+
+            for soft_walls in wall_sets.iter() {
+                let walled = Maze::new(&MazeConfig {
+                    walls: soft_walls.clone(),
+                    ..config.clone()
+                })?;
+                let bound = upper_bound(&walled);
+
+                for score in [0, bound.saturating_sub(1), bound, bound + 1] {
+                    assert_eq!(
+                        exceeds_upper_bound(&maze, soft_walls, score)?,
+                        bound > score
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_upper_bound_against_brute_force() -> Result<(), Box<dyn Error>> {
+        let configs = [
+            MazeConfig {
+                col_count: 3,
+                row_count: 3,
+                floor_count: 1,
+                max_soft_wall_count: 4,
+                walls: vec![],
+                entrypoints: vec![Position::new(0, 0)],
+                checkpoints: vec![Checkpoint {
+                    position: Position::new(2, 2),
+                    level: 1,
+                }],
+                stairs: vec![],
+                scoring_rule: ScoringRuleType::Min,
+            },
+            MazeConfig {
+                col_count: 4,
+                row_count: 3,
+                floor_count: 1,
+                max_soft_wall_count: 5,
+                walls: vec![Position::new(1, 1)],
+                entrypoints: vec![Position::new(0, 0), Position::new(0, 2)],
+                checkpoints: vec![Checkpoint {
+                    position: Position::new(3, 1),
+                    level: 1,
+                }],
+                stairs: vec![],
+                scoring_rule: ScoringRuleType::Min,
+            },
+            MazeConfig {
+                col_count: 4,
+                row_count: 4,
+                floor_count: 1,
+                max_soft_wall_count: 3,
+                walls: vec![Position::new(2, 2)],
+                entrypoints: vec![Position::new(0, 0)],
+                checkpoints: vec![
+                    Checkpoint {
+                        position: Position::new(3, 0),
+                        level: 1,
+                    },
+                    Checkpoint {
+                        position: Position::new(0, 3),
+                        level: 2,
+                    },
+                    Checkpoint {
+                        position: Position::new(3, 3),
+                        level: 2,
+                    },
+                ],
+                stairs: vec![],
+                scoring_rule: ScoringRuleType::Min,
+            },
+            MazeConfig {
+                col_count: 4,
+                row_count: 3,
+                floor_count: 1,
+                max_soft_wall_count: 4,
+                walls: vec![],
+                entrypoints: vec![Position::new(1, 1)],
+                checkpoints: vec![
+                    Checkpoint {
+                        position: Position::new(3, 2),
+                        level: 1,
+                    },
+                    Checkpoint {
+                        position: Position::new(0, 0),
+                        level: 1,
+                    },
+                    Checkpoint {
+                        position: Position::new(3, 0),
+                        level: 2,
+                    },
+                ],
+                stairs: vec![],
+                scoring_rule: ScoringRuleType::Min,
+            },
+        ];
+
+        for config in configs.iter() {
+            for scoring_rule in SCORING_RULES {
+                let config = MazeConfig {
+                    scoring_rule,
+                    ..config.clone()
+                };
+                let maze = Maze::new(&config)?;
+                let best_score = get_brute_force_best_score(&maze)?;
+
+                assert!(best_score > 0);
+                assert!(
+                    upper_bound(&maze) >= best_score,
+                    "upper bound {} is lower than best score {} for {:?}",
+                    upper_bound(&maze),
+                    best_score,
+                    config
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,309 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    mem::size_of,
+    sync::Arc,
+};
+
+use maze_core::Position;
+
+use crate::{run::MazeRunResult, runner::MazeRunner, runner_error::MazeRunnerError};
+
+/// Order independent hash of a soft wall set, every wall toggles its own key in or out
+/// so the hash of a neighboring wall set is derived without rehashing all walls
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct WallSetHash(u64);
+
+impl WallSetHash {
+    pub fn new(soft_walls: &[Position]) -> Self {
+        soft_walls
+            .iter()
+            .fold(Self::default(), |hash, position| hash.toggled(position))
+    }
+
+    /// Hash of the wall set with the wall added, or removed if it already was in the set
+    pub fn toggled(self, position: &Position) -> Self {
+        Self(self.0 ^ get_position_key(position))
+    }
+
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub entries: usize,
+    pub memory_size: usize,
+}
+
+struct CacheEntry {
+    sorted_walls: Vec<Position>,
+    result: Option<Arc<MazeRunResult>>,
+    last_used: u64,
+    memory_size: usize,
+}
+
+/// Runner remembering results of recently evaluated soft wall sets,
+/// least recently used results are dropped once their size exceeds the memory cap
+pub struct CachedMazeRunner<'a> {
+    runner: MazeRunner<'a>,
+    max_memory_size: usize,
+    entries: HashMap<WallSetHash, CacheEntry>,
+    // last use tick of entries mapped to their keys, the first one is the least recently used
+    recency: BTreeMap<u64, WallSetHash>,
+    tick: u64,
+    stats: CacheStats,
+}
+
+impl<'a> CachedMazeRunner<'a> {
+    /// Creates cache over the runner that keeps results up to `max_memory_size` bytes in total
+    pub fn new(runner: MazeRunner<'a>, max_memory_size: usize) -> Self {
+        Self {
+            runner,
+            max_memory_size,
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            tick: 0,
+            stats: CacheStats::default(),
+        }
+    }
+
+    pub fn run(
+        &mut self,
+        soft_walls: &Vec<Position>,
+    ) -> Result<Option<Arc<MazeRunResult>>, MazeRunnerError> {
+        self.run_hashed(soft_walls, WallSetHash::new(soft_walls))
+    }
+
+    /// Same as `run` with the hash of the soft walls maintained by the caller,
+    /// a wrong hash only causes a cache miss
+    pub fn run_hashed(
+        &mut self,
+        soft_walls: &Vec<Position>,
+        hash: WallSetHash,
+    ) -> Result<Option<Arc<MazeRunResult>>, MazeRunnerError> {
+        let mut sorted_walls = soft_walls.clone();
+        sorted_walls.sort_by_key(|position| (position.floor_index(), position.x, position.y));
+        self.tick += 1;
+
+        if let Some(entry) = self.entries.get_mut(&hash) {
+            // keys of different wall sets may collide so the walls are compared as well
+            if entry.sorted_walls == sorted_walls {
+                self.recency.remove(&entry.last_used);
+                self.recency.insert(self.tick, hash);
+                entry.last_used = self.tick;
+                self.stats.hits += 1;
+
+                return Ok(entry.result.clone());
+            }
+        }
+
+        self.stats.misses += 1;
+        // errors are not cached, invalid wall sets are rejected before any search
+        let result = self.runner.run(soft_walls)?.map(Arc::new);
+        self.insert(hash, sorted_walls, result.clone());
+
+        Ok(result)
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    pub fn runner(&self) -> &MazeRunner<'a> {
+        &self.runner
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+        self.stats.entries = 0;
+        self.stats.memory_size = 0;
+    }
+
+    fn insert(
+        &mut self,
+        hash: WallSetHash,
+        sorted_walls: Vec<Position>,
+        result: Option<Arc<MazeRunResult>>,
+    ) {
+        self.remove(&hash);
+
+        let memory_size = size_of::<CacheEntry>()
+            + sorted_walls.capacity() * size_of::<Position>()
+            + result.as_ref().map_or(0, |result| result.memory_size());
+        if memory_size > self.max_memory_size {
+            return;
+        }
+
+        while self.stats.memory_size + memory_size > self.max_memory_size {
+            let oldest = match self.recency.values().next() {
+                Some(oldest) => *oldest,
+                None => break,
+            };
+            self.remove(&oldest);
+            self.stats.evictions += 1;
+        }
+
+        self.recency.insert(self.tick, hash);
+        self.entries.insert(
+            hash,
+            CacheEntry {
+                sorted_walls,
+                result,
+                last_used: self.tick,
+                memory_size,
+            },
+        );
+        self.stats.entries += 1;
+        self.stats.memory_size += memory_size;
+    }
+
+    fn remove(&mut self, hash: &WallSetHash) {
+        if let Some(entry) = self.entries.remove(hash) {
+            self.recency.remove(&entry.last_used);
+            self.stats.entries -= 1;
+            self.stats.memory_size -= entry.memory_size;
+        }
+    }
+}
+
+/// Pseudo random key of the position, stable across runs and platforms
+fn get_position_key(position: &Position) -> u64 {
+    mix(mix(mix(position.x as u64) ^ position.y as u64) ^ position.floor_index() as u64)
+}
+
+/// Finalizer of splitmix64
+fn mix(value: u64) -> u64 {
+    let mut value = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    value = (value ^ (value >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    value ^ (value >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use maze_core::{Checkpoint, Maze, MazeConfig, ScoringRuleType};
+
+    use super::*;
+
+    fn get_maze() -> Result<Maze, Box<dyn Error>> {
+        Ok(Maze::new(&MazeConfig {
+            col_count: 6,
+            row_count: 4,
+            floor_count: 1,
+            max_soft_wall_count: 3,
+            walls: vec![],
+            entrypoints: vec![Position::new(0, 0)],
+            checkpoints: vec![Checkpoint {
+                position: Position::new(5, 3),
+                level: 1,
+            }],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
+        })?)
+    }
+
+    #[test]
+    fn test_hash_is_order_independent() {
+        let a = Position::new(1, 2);
+        let b = Position::new(2, 1);
+        let c = Position::new(0, 3);
+
+        assert_eq!(WallSetHash::new(&[a, b, c]), WallSetHash::new(&[c, a, b]));
+        assert_ne!(WallSetHash::new(&[a]), WallSetHash::new(&[b]));
+        assert_ne!(WallSetHash::new(&[a, b]), WallSetHash::default());
+
+        let hash = WallSetHash::new(&[a, b]);
+        assert_eq!(hash.toggled(&c), WallSetHash::new(&[a, b, c]));
+        assert_eq!(hash.toggled(&b), WallSetHash::new(&[a]));
+        assert_eq!(hash.toggled(&c).toggled(&c), hash);
+    }
+
+    #[test]
+    fn test_run_hits_and_misses() -> Result<(), Box<dyn Error>> {
+        let maze = get_maze()?;
+        let mut cache = CachedMazeRunner::new(MazeRunner::new(&maze), usize::MAX);
+
+        let walls = vec![Position::new(1, 0), Position::new(1, 1)];
+        let reversed: Vec<_> = walls.iter().rev().copied().collect();
+
+        let first = cache.run(&walls)?.ok_or("maze should be solvable")?;
+        let second = cache.run(&reversed)?.ok_or("maze should be solvable")?;
+        let expected = MazeRunner::new(&maze)
+            .run(&walls)?
+            .ok_or("maze should be solvable")?;
+
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(first.score(), expected.score());
+        assert_eq!(first.solved_path()?, expected.solved_path()?);
+
+        let hash = WallSetHash::new(&walls).toggled(&Position::new(1, 2));
+        let mut extended = walls.clone();
+        extended.push(Position::new(1, 2));
+        cache.run_hashed(&extended, hash)?;
+        cache.run(&extended)?;
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses), (2, 2));
+        assert_eq!(stats.entries, 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_does_not_cache_errors() -> Result<(), Box<dyn Error>> {
+        let maze = get_maze()?;
+        let mut cache = CachedMazeRunner::new(MazeRunner::new(&maze), usize::MAX);
+
+        let wall = Position::new(2, 2);
+        cache.run(&vec![])?;
+        // duplicate walls cancel out in the hash, the cached empty set must not be returned
+        assert!(cache.run(&vec![wall, wall]).is_err());
+        assert!(cache.run(&vec![wall, wall]).is_err());
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses), (0, 3));
+        assert_eq!(stats.entries, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_evicts_least_recently_used() -> Result<(), Box<dyn Error>> {
+        let maze = get_maze()?;
+        let wall_sets: Vec<Vec<Position>> = (0..4).map(|y| vec![Position::new(2, y)]).collect();
+
+        let mut probe = CachedMazeRunner::new(MazeRunner::new(&maze), usize::MAX);
+        probe.run(&wall_sets[0])?;
+        let entry_size = probe.stats().memory_size;
+
+        let mut cache = CachedMazeRunner::new(MazeRunner::new(&maze), entry_size * 2);
+        cache.run(&wall_sets[0])?;
+        cache.run(&wall_sets[1])?;
+        cache.run(&wall_sets[0])?;
+        // the second set is the least recently used one now
+        cache.run(&wall_sets[2])?;
+        cache.run(&wall_sets[0])?;
+        cache.run(&wall_sets[1])?;
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses), (2, 4));
+        assert_eq!(stats.evictions, 2);
+        assert_eq!(stats.entries, 2);
+        assert!(stats.memory_size <= entry_size * 2);
+
+        let mut tiny = CachedMazeRunner::new(MazeRunner::new(&maze), 1);
+        tiny.run(&wall_sets[3])?;
+        tiny.run(&wall_sets[3])?;
+        assert_eq!(tiny.stats().hits, 0);
+        assert_eq!(tiny.stats().memory_size, 0);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,246 @@
+use std::{cmp::Reverse, collections::HashSet};
+
+use maze_core::{Maze, Position, TileKind};
+
+use crate::{run::MazeRunResult, runner_error::MazeRunnerError, MazeRunner};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WallHint {
+    pub position: Position,
+    pub score: u32,
+    pub score_gain: u32,
+}
+
+/// Evaluates every soft wall that can be placed next to the current ones and ranks them by score gain.
+///
+/// Only empty tiles on the solved paths of the entrypoints are candidates, walling any other tile
+/// leaves the shortest paths intact and can't change the score. If the scoring rule of the maze scores
+/// only the shortest run, the solved path of the best entrypoint is enough. Candidates that make the maze
+/// unsolvable are skipped, and no hints are returned if the maze is already unsolvable or the soft wall limit is reached.
+pub fn best_next_wall(
+    maze: &Maze,
+    current_walls: &[Position],
+) -> Result<Vec<WallHint>, MazeRunnerError> {
+    let runner = MazeRunner::new(maze);
+    let walls = current_walls.to_vec();
+
+    let current_score = match runner.run_score(&walls)? {
+        Some(score) if (current_walls.len() as u32) < maze.max_soft_wall_count() => score,
+        _ => return Ok(vec![]),
+    };
+
+    let runs: Vec<MazeRunResult> = match runner.scoring_rule().is_lowest_score() {
+        true => runner.run(&walls)?.into_iter().collect(),
+        false => runner
+            .run_entrypoints(&walls)?
+            .into_iter()
+            .filter_map(|run| run.result)
+            .collect(),
+    };
+
+    let mut evaluated = HashSet::new();
+    let mut candidates: Vec<Position> = vec![];
+    for run in runs.iter() {
+        candidates.extend(
+            run.solved_path()?
+                .into_iter()
+                .filter(|position| {
+                    maze.floors()[position.floor_index()][position.x][position.y] == TileKind::Empty
+                })
+                .filter(|position| evaluated.insert(*position)),
+        );
+    }
+
+    let wall_sets: Vec<Vec<Position>> = candidates
+        .iter()
+        .map(|position| [current_walls, &[*position]].concat())
+        .collect();
+
+    let mut hints = vec![];
+    for (position, score) in candidates
+        .into_iter()
+        .zip(runner.run_score_batch(&wall_sets))
+    {
+        if let Some(score) = score? {
+            hints.push(WallHint {
+                position,
+                score,
+                score_gain: score.saturating_sub(current_score),
+            });
+        }
+    }
+
+    // stable sort keeps the path order for hints with the same gain
+    hints.sort_by_key(|hint| Reverse(hint.score_gain));
+
+    Ok(hints)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use maze_core::{Checkpoint, MazeConfig, ScoringRuleType};
+
+    use super::*;
+
+    #[test]
+    fn test_best_next_wall_forces_detour() -> Result<(), Box<dyn Error>> {
+        let maze = Maze::new(&MazeConfig {
+            col_count: 3,
+            row_count: 3,
+            floor_count: 1,
+            max_soft_wall_count: 3,
+            walls: vec![],
+            entrypoints: vec![Position::new(0, 0)],
+            checkpoints: vec![Checkpoint {
+                position: Position::new(2, 0),
+                level: 1,
+            }],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
+        })?;
+
+        let hints = best_next_wall(&maze, &[])?;
+
+        assert_eq!(
+            hints,
+            vec![WallHint {
+                position: Position::new(1, 0),
+                score: 4,
+                score_gain: 2,
+            }]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_best_next_wall_is_ranked() -> Result<(), Box<dyn Error>> {
+        let maze = Maze::new(&MazeConfig {
+            col_count: 6,
+            row_count: 6,
+            floor_count: 1,
+            max_soft_wall_count: 10,
+            walls: vec![Position::new(2, 2)],
+            entrypoints: vec![Position::new(0, 0)],
+            checkpoints: vec![
+                Checkpoint {
+                    position: Position::new(5, 1),
+                    level: 1,
+                },
+                Checkpoint {
+                    position: Position::new(1, 5),
+                    level: 2,
+                },
+            ],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
+        })?;
+
+        let current_walls = vec![Position::new(4, 0), Position::new(4, 1)];
+        let hints = best_next_wall(&maze, &current_walls)?;
+        let current_score = MazeRunner::new(&maze)
+            .run(&current_walls)?
+            .map(|run| run.score());
+
+        assert!(!hints.is_empty());
+        assert!(hints.windows(2).all(|w| w[0].score_gain >= w[1].score_gain));
+        for hint in hints.iter() {
+            assert_eq!(Some(hint.score - hint.score_gain), current_score);
+            assert!(!current_walls.contains(&hint.position));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_best_next_wall_skips_unsolvable_placements() -> Result<(), Box<dyn Error>> {
+        let maze = Maze::new(&MazeConfig {
+            col_count: 4,
+            row_count: 1,
+            floor_count: 1,
+            max_soft_wall_count: 3,
+            walls: vec![],
+            entrypoints: vec![Position::new(0, 0)],
+            checkpoints: vec![Checkpoint {
+                position: Position::new(3, 0),
+                level: 1,
+            }],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
+        })?;
+
+        assert_eq!(best_next_wall(&maze, &[])?, vec![]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_best_next_wall_respects_soft_wall_limit() -> Result<(), Box<dyn Error>> {
+        let maze = Maze::new(&MazeConfig {
+            col_count: 3,
+            row_count: 3,
+            floor_count: 1,
+            max_soft_wall_count: 1,
+            walls: vec![],
+            entrypoints: vec![Position::new(0, 0)],
+            checkpoints: vec![Checkpoint {
+                position: Position::new(2, 0),
+                level: 1,
+            }],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
+        })?;
+
+        assert_eq!(best_next_wall(&maze, &[Position::new(0, 2)])?, vec![]);
+        assert!(best_next_wall(&maze, &[Position::new(0, 2), Position::new(1, 2)]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_best_next_wall_with_scoring_rule_of_maze() -> Result<(), Box<dyn Error>> {
+        for scoring_rule in [ScoringRuleType::Max, ScoringRuleType::Sum] {
+            let maze = Maze::new(&MazeConfig {
+                col_count: 4,
+                row_count: 4,
+                floor_count: 1,
+                max_soft_wall_count: 3,
+                walls: vec![Position::new(1, 1)],
+                entrypoints: vec![Position::new(0, 0), Position::new(0, 3)],
+                checkpoints: vec![Checkpoint {
+                    position: Position::new(3, 0),
+                    level: 1,
+                }],
+                stairs: vec![],
+                scoring_rule,
+            })?;
+
+            let runner = MazeRunner::new(&maze);
+            let current_score = runner.run_score(&vec![])?.ok_or("maze should be solved")?;
+            let hints = best_next_wall(&maze, &[])?;
+
+            // every wall changing the score of any entrypoint is hinted
+            for x in 0..4 {
+                for y in 0..4 {
+                    let position = Position::new(x, y);
+                    if maze.floors()[0][x][y] != TileKind::Empty {
+                        continue;
+                    }
+
+                    let hint = hints.iter().find(|hint| hint.position == position);
+                    match runner.run_score(&vec![position])? {
+                        Some(score) if score != current_score => {
+                            assert_eq!(hint.map(|hint| hint.score), Some(score))
+                        }
+                        Some(score) => assert!(hint.is_none_or(|hint| hint.score == score)),
+                        None => assert!(hint.is_none()),
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
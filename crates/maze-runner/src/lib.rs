@@ -1,9 +1,28 @@
+pub mod analysis;
+mod batch;
+mod bitboard;
+mod bounds;
+mod cache;
+pub mod hints;
 mod node;
 mod nodes;
+mod paths;
+mod placement;
 mod run;
 mod runner;
 mod runner_error;
+mod scoring;
+mod trace;
+mod verify;
 
-pub use run::MazeRunResult;
+pub use bounds::{exceeds_upper_bound, upper_bound};
+pub use cache::{CacheStats, CachedMazeRunner, WallSetHash};
+pub use placement::WallPlacement;
+pub use run::{DistanceField, DistanceFloors, EntrypointRun, MazeRunResult, PathSegment};
 pub use runner::MazeRunner;
 pub use runner_error::MazeRunnerError;
+pub use scoring::{
+    create_scoring_rule, EntrypointScore, ScoreBreakdown, ScoringRule, ScoringRuleType,
+};
+pub use trace::{MazeTrace, SearchEvent};
+pub use verify::{verify, ClaimedRun, Discrepancy, Verdict};
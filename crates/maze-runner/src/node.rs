@@ -18,17 +18,27 @@ impl Node {
         }
     }
 
+    pub(crate) fn kind(&self) -> TileKind {
+        self.kind
+    }
+
+    pub(crate) fn set_kind(&mut self, kind: TileKind) {
+        self.kind = kind;
+    }
+
     pub(crate) fn can_enter(&self) -> bool {
         self.kind != TileKind::Wall
     }
 
     pub(crate) fn is_checkpoint(&self, checkpoint_level: i32) -> bool {
-        match self.kind {
-            TileKind::Checkpoint { level } if level == checkpoint_level => true,
-            _ => false,
-        }
+        matches!(self.kind, TileKind::Checkpoint { level } if level == checkpoint_level)
     }
 
+    pub(crate) fn is_stairs_to(&self, floor: usize) -> bool {
+        matches!(self.kind, TileKind::Stairs { to_floor } if to_floor == floor)
+    }
+
+    #[cfg(test)]
     pub(crate) fn is_entrypoint(&self) -> bool {
         self.kind == TileKind::Entrypoint
     }
@@ -41,6 +51,10 @@ impl Node {
         self.distances.get(&checkpoint_level).copied()
     }
 
+    pub(crate) fn lowest_distance(&self) -> Option<u32> {
+        self.distances.values().min().copied()
+    }
+
     pub(crate) fn set_distance(&mut self, checkpoint_level: i32, distance: u32) {
         self.distances.insert(checkpoint_level, distance);
     }
@@ -54,6 +68,11 @@ impl Node {
     pub(crate) fn position(&self) -> &Position {
         &self.position
     }
+
+    /// Approximate number of bytes the node occupies including its distances
+    pub(crate) fn memory_size(&self) -> usize {
+        std::mem::size_of::<Self>() + self.distances.capacity() * std::mem::size_of::<(i32, u32)>()
+    }
 }
 
 #[cfg(test)]
@@ -62,63 +81,66 @@ mod tests {
 
     #[test]
     fn test_empty_node_methods() {
-        let node = Node::new(TileKind::Empty, Position { x: 1, y: 1 });
+        let node = Node::new(TileKind::Empty, Position::new(1, 1));
 
-        assert_eq!(node.position(), &Position { x: 1, y: 1 });
-        assert_eq!(node.can_enter(), true);
-        assert_eq!(node.has_distance(1), false);
+        assert_eq!(node.position(), &Position::new(1, 1));
+        assert!(node.can_enter());
+        assert!(!node.has_distance(1));
         assert_eq!(node.distance(1), None);
-        assert_eq!(node.is_checkpoint(1), false);
-        assert_eq!(node.is_entrypoint(), false);
+        assert!(!node.is_checkpoint(1));
+        assert!(!node.is_entrypoint());
     }
 
     #[test]
     fn test_entrypoint_node_methods() {
-        let node = Node::new(TileKind::Entrypoint, Position { x: 2, y: 2 });
+        let node = Node::new(TileKind::Entrypoint, Position::new(2, 2));
 
-        assert_eq!(node.position(), &Position { x: 2, y: 2 });
-        assert_eq!(node.can_enter(), true);
-        assert_eq!(node.has_distance(1), false);
+        assert_eq!(node.position(), &Position::new(2, 2));
+        assert!(node.can_enter());
+        assert!(!node.has_distance(1));
         assert_eq!(node.distance(1), None);
-        assert_eq!(node.is_checkpoint(1), false);
-        assert_eq!(node.is_entrypoint(), true);
+        assert!(!node.is_checkpoint(1));
+        assert!(node.is_entrypoint());
     }
 
     #[test]
     fn test_checkpoint_node_methods() {
-        let node = Node::new(TileKind::Checkpoint { level: 1 }, Position { x: 2, y: 2 });
+        let node = Node::new(TileKind::Checkpoint { level: 1 }, Position::new(2, 2));
 
-        assert_eq!(node.position(), &Position { x: 2, y: 2 });
-        assert_eq!(node.can_enter(), true);
-        assert_eq!(node.has_distance(1), false);
+        assert_eq!(node.position(), &Position::new(2, 2));
+        assert!(node.can_enter());
+        assert!(!node.has_distance(1));
         assert_eq!(node.distance(1), None);
-        assert_eq!(node.is_checkpoint(1), true);
-        assert_eq!(node.is_checkpoint(2), false);
-        assert_eq!(node.is_entrypoint(), false);
+        assert!(node.is_checkpoint(1));
+        assert!(!node.is_checkpoint(2));
+        assert!(!node.is_entrypoint());
     }
 
     #[test]
     fn test_node_wall_node_methods() {
-        let node = Node::new(TileKind::Wall, Position { x: 2, y: 2 });
+        let node = Node::new(TileKind::Wall, Position::new(2, 2));
 
-        assert_eq!(node.position(), &Position { x: 2, y: 2 });
-        assert_eq!(node.can_enter(), false);
-        assert_eq!(node.has_distance(1), false);
+        assert_eq!(node.position(), &Position::new(2, 2));
+        assert!(!node.can_enter());
+        assert!(!node.has_distance(1));
         assert_eq!(node.distance(1), None);
-        assert_eq!(node.is_checkpoint(1), false);
-        assert_eq!(node.is_entrypoint(), false);
+        assert!(!node.is_checkpoint(1));
+        assert!(!node.is_entrypoint());
     }
 
     #[test]
     fn test_node_distance_mutation_methods() {
-        let mut node = Node::new(TileKind::Empty, Position { x: 2, y: 2 });
+        let mut node = Node::new(TileKind::Empty, Position::new(2, 2));
 
-        assert_eq!(node.has_distance(1), false);
+        assert!(!node.has_distance(1));
         assert_eq!(node.distance(1), None);
         node.set_distance(1, 5);
-        assert_eq!(node.has_distance(1), true);
+        assert!(node.has_distance(1));
         assert_eq!(node.distance(1), Some(5));
-        assert_eq!(node.has_distance(2), false);
+        assert!(!node.has_distance(2));
         assert_eq!(node.distance(2), None);
+        assert_eq!(node.lowest_distance(), Some(5));
+        node.set_distance(2, 3);
+        assert_eq!(node.lowest_distance(), Some(3));
     }
 }
@@ -1,21 +1,27 @@
-use maze_core::{Position, TileBoard};
+use maze_core::{Position, TileFloors};
 
-use super::node::Node;
+use super::{node::Node, run::DistanceFloors};
 
 #[derive(Debug, Clone)]
 pub(crate) struct Nodes {
-    data: Vec<Vec<Node>>,
+    data: Vec<Vec<Vec<Node>>>,
 }
 
 impl Nodes {
-    pub(crate) fn new(tiles: &TileBoard) -> Self {
-        let nodes = tiles
+    pub(crate) fn new(floors: &TileFloors) -> Self {
+        let nodes = floors
             .iter()
             .enumerate()
-            .map(|(x, row)| {
-                row.iter()
+            .map(|(floor, tiles)| {
+                tiles
+                    .iter()
                     .enumerate()
-                    .map(|(y, kind)| Node::new(*kind, Position { x, y }))
+                    .map(|(x, row)| {
+                        row.iter()
+                            .enumerate()
+                            .map(|(y, kind)| Node::new(*kind, Position::on_floor(x, y, floor)))
+                            .collect()
+                    })
                     .collect()
             })
             .collect();
@@ -24,53 +30,140 @@ impl Nodes {
     }
 
     pub(crate) fn get_node(&self, position: &Position) -> &Node {
-        &self.data[position.x][position.y]
+        &self.data[position.floor_index()][position.x][position.y]
     }
 
     pub(crate) fn get_node_mut(&mut self, position: &Position) -> &mut Node {
-        &mut self.data[position.x][position.y]
+        &mut self.data[position.floor_index()][position.x][position.y]
     }
 
-    pub(crate) fn get_neighbors_positions(&self, &Position { x, y }: &Position) -> Vec<Position> {
+    /// Number of columns and rows of every floor
+    pub(crate) fn dimensions(&self) -> (usize, usize) {
+        (self.data[0].len(), self.data[0][0].len())
+    }
+
+    pub(crate) fn floor_count(&self) -> usize {
+        self.data.len()
+    }
+
+    /// All positions ordered by floor, column and row
+    pub(crate) fn positions(&self) -> impl Iterator<Item = Position> + '_ {
+        self.data
+            .iter()
+            .flatten()
+            .flatten()
+            .map(|node| *node.position())
+    }
+
+    /// Grid of the same shape as the nodes with every tile set to the value
+    pub(crate) fn create_grid<T: Clone>(&self, value: T) -> TileGrid<T> {
+        let (col_count, row_count) = self.dimensions();
+        TileGrid {
+            data: vec![vec![vec![value; row_count]; col_count]; self.floor_count()],
+        }
+    }
+
+    /// Approximate number of bytes the nodes occupy
+    pub(crate) fn memory_size(&self) -> usize {
+        self.data
+            .iter()
+            .flatten()
+            .map(|column| {
+                std::mem::size_of::<Vec<Node>>()
+                    + column.iter().map(Node::memory_size).sum::<usize>()
+            })
+            .sum()
+    }
+
+    pub(crate) fn get_distance_field(&self, checkpoint_level: i32) -> DistanceFloors {
+        self.map_nodes(|node| node.distance(checkpoint_level))
+    }
+
+    pub(crate) fn get_lowest_distance_field(&self) -> DistanceFloors {
+        self.map_nodes(|node| node.lowest_distance())
+    }
+
+    fn map_nodes<T>(&self, f: impl Fn(&Node) -> T) -> Vec<Vec<Vec<T>>> {
+        self.data
+            .iter()
+            .map(|tiles| {
+                tiles
+                    .iter()
+                    .map(|row| row.iter().map(&f).collect())
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Neighbors on the same floor followed by tiles connected by stairs in either direction
+    pub(crate) fn get_neighbors_positions(&self, position: &Position) -> Vec<Position> {
+        let Position { x, y, .. } = *position;
+        let floor = position.floor_index();
+        let tiles = &self.data[floor];
         let mut neighbors = Vec::with_capacity(4);
 
-        if y + 1 < self.data[0].len() {
-            neighbors.push(Position { x, y: y + 1 });
+        if y + 1 < tiles[0].len() {
+            neighbors.push(Position::on_floor(x, y + 1, floor));
         }
         if y > 0 {
-            neighbors.push(Position { x, y: y - 1 });
+            neighbors.push(Position::on_floor(x, y - 1, floor));
         }
 
-        if x + 1 < self.data.len() {
-            neighbors.push(Position { x: x + 1, y });
+        if x + 1 < tiles.len() {
+            neighbors.push(Position::on_floor(x + 1, y, floor));
         }
         if x > 0 {
-            neighbors.push(Position { x: x - 1, y });
+            neighbors.push(Position::on_floor(x - 1, y, floor));
+        }
+
+        if self.data.len() > 1 {
+            for other_floor in 0..self.data.len() {
+                let connected = tiles[x][y].is_stairs_to(other_floor)
+                    || self.data[other_floor][x][y].is_stairs_to(floor);
+                if connected {
+                    neighbors.push(Position::on_floor(x, y, other_floor));
+                }
+            }
         }
 
         neighbors
     }
 
-    pub(crate) fn get_lowest_distance_neighbor(
+    /// Finds neighbor the search came from to the position with the given distance,
+    /// checkpoints of the level are never expanded within the level so they can't precede anything
+    pub(crate) fn get_previous_neighbor(
         &self,
         position: &Position,
         checkpoint_level: i32,
+        distance: u32,
     ) -> Option<&Node> {
-        let mut lowest: Option<&Node> = None;
+        self.get_neighbors_positions(position)
+            .iter()
+            .map(|neighbor_position| self.get_node(neighbor_position))
+            .find(|neighbor| {
+                !neighbor.is_checkpoint(checkpoint_level)
+                    && neighbor.distance(checkpoint_level) == Some(distance - 1)
+            })
+    }
+}
+
+/// Values of tiles of all floors indexed by floor, column and row
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TileGrid<T> {
+    data: Vec<Vec<Vec<T>>>,
+}
 
-        for neighbor_position in self.get_neighbors_positions(position) {
-            let neighbor = self.get_node(&neighbor_position);
+impl<T> TileGrid<T> {
+    pub(crate) fn get(&self, position: &Position) -> &T {
+        &self.data[position.floor_index()][position.x][position.y]
+    }
 
-            if let Some(neighbor_distance) = neighbor.distance(checkpoint_level) {
-                let lowest_dist = lowest.and_then(|node| node.distance(checkpoint_level));
-                lowest = match lowest_dist {
-                    Some(lowest_distance) if lowest_distance <= neighbor_distance => lowest,
-                    _ => Some(neighbor),
-                }
-            }
-        }
+    pub(crate) fn get_mut(&mut self, position: &Position) -> &mut T {
+        &mut self.data[position.floor_index()][position.x][position.y]
+    }
 
-        lowest.filter(|node| node.distance(checkpoint_level).is_some())
+    pub(crate) fn into_inner(self) -> Vec<Vec<Vec<T>>> {
+        self.data
     }
 }
 
@@ -82,63 +175,94 @@ mod tests {
 
     #[test]
     fn test_nodes_get_node_from_maze() {
-        let nodes = Nodes::new(&vec![
+        let nodes = Nodes::new(&vec![vec![
             vec![TileKind::Entrypoint, TileKind::Empty],
             vec![TileKind::Wall, TileKind::Checkpoint { level: 1 }],
-        ]);
+        ]]);
 
-        let node = nodes.get_node(&Position { x: 1, y: 1 });
+        let node = nodes.get_node(&Position::new(1, 1));
 
         assert_eq!(
             node,
-            &Node::new(TileKind::Checkpoint { level: 1 }, Position { x: 1, y: 1 })
+            &Node::new(TileKind::Checkpoint { level: 1 }, Position::new(1, 1))
         )
     }
 
     #[test]
     #[should_panic]
     fn test_nodes_get_node_out_of_bounds() {
-        let nodes = Nodes::new(&vec![
+        let nodes = Nodes::new(&vec![vec![
             vec![TileKind::Entrypoint, TileKind::Empty],
             vec![TileKind::Wall, TileKind::Checkpoint { level: 1 }],
-        ]);
+        ]]);
 
-        nodes.get_node(&Position { x: 5, y: 1 });
+        nodes.get_node(&Position::new(5, 1));
     }
 
     #[test]
     fn test_nodes_get_neighbors_on_the_edge_of_maze() {
-        let nodes = Nodes::new(&vec![
+        let nodes = Nodes::new(&vec![vec![
             vec![TileKind::Entrypoint, TileKind::Empty],
             vec![TileKind::Wall, TileKind::Checkpoint { level: 1 }],
-        ]);
+        ]]);
 
-        let neighbors = nodes.get_neighbors_positions(&Position { x: 0, y: 1 });
+        let neighbors = nodes.get_neighbors_positions(&Position::new(0, 1));
 
-        assert_eq!(
-            neighbors,
-            vec![Position { x: 0, y: 0 }, Position { x: 1, y: 1 }]
-        )
+        assert_eq!(neighbors, vec![Position::new(0, 0), Position::new(1, 1)])
     }
 
     #[test]
     fn test_nodes_get_neighbors_in_the_center_of_maze() {
-        let nodes = Nodes::new(&vec![
+        let nodes = Nodes::new(&vec![vec![
             vec![TileKind::Empty, TileKind::Empty, TileKind::Empty],
             vec![TileKind::Empty, TileKind::Empty, TileKind::Empty],
             vec![TileKind::Empty, TileKind::Empty, TileKind::Empty],
-        ]);
+        ]]);
 
-        let neighbors = nodes.get_neighbors_positions(&Position { x: 1, y: 1 });
+        let neighbors = nodes.get_neighbors_positions(&Position::new(1, 1));
 
         assert_eq!(
             neighbors,
             vec![
-                Position { x: 1, y: 2 },
-                Position { x: 1, y: 0 },
-                Position { x: 2, y: 1 },
-                Position { x: 0, y: 1 },
+                Position::new(1, 2),
+                Position::new(1, 0),
+                Position::new(2, 1),
+                Position::new(0, 1),
             ]
         )
     }
+
+    #[test]
+    fn test_nodes_get_neighbors_over_stairs() {
+        let nodes = Nodes::new(&vec![
+            vec![
+                vec![TileKind::Entrypoint, TileKind::Stairs { to_floor: 1 }],
+                vec![TileKind::Empty, TileKind::Empty],
+            ],
+            vec![
+                vec![TileKind::Empty, TileKind::Empty],
+                vec![TileKind::Empty, TileKind::Empty],
+            ],
+        ]);
+
+        let below = nodes.get_neighbors_positions(&Position::new(0, 1));
+        let above = nodes.get_neighbors_positions(&Position::on_floor(0, 1, 1));
+
+        assert_eq!(
+            below,
+            vec![
+                Position::new(0, 0),
+                Position::new(1, 1),
+                Position::on_floor(0, 1, 1)
+            ]
+        );
+        assert_eq!(
+            above,
+            vec![
+                Position::on_floor(0, 0, 1),
+                Position::on_floor(1, 1, 1),
+                Position::new(0, 1)
+            ]
+        );
+    }
 }
@@ -0,0 +1,345 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use maze_core::Position;
+
+use super::nodes::Nodes;
+
+/// Position on the path together with the number of checkpoint levels completed when standing on it
+type State = (Position, usize);
+
+/// Walks the evaluated nodes of a solved run backwards over all predecessors with the exact previous distance
+pub(crate) struct ShortestPaths<'a> {
+    nodes: &'a Nodes,
+    ascending_checkpoint_levels: &'a [i32],
+    entrypoint: Position,
+    exit: Position,
+    distance: u32,
+}
+
+impl<'a> ShortestPaths<'a> {
+    pub(crate) fn new(
+        nodes: &'a Nodes,
+        ascending_checkpoint_levels: &'a [i32],
+        entrypoint: Position,
+        exit: Position,
+        distance: u32,
+    ) -> Self {
+        Self {
+            nodes,
+            ascending_checkpoint_levels,
+            entrypoint,
+            exit,
+            distance,
+        }
+    }
+
+    /// Number of distinct shortest paths, saturates at `u64::MAX`
+    pub(crate) fn count(&self) -> u64 {
+        // collect states lying on some shortest path, they are ordered by distance from the exits
+        let mut states = vec![];
+        let mut visited = HashSet::new();
+        let mut queue: VecDeque<State> = self.get_exits().into_iter().collect();
+        visited.extend(queue.iter().copied());
+
+        while let Some(state) = queue.pop_front() {
+            states.push(state);
+            for predecessor in self.get_predecessors(state) {
+                if visited.insert(predecessor) {
+                    queue.push_back(predecessor);
+                }
+            }
+        }
+
+        let mut counts: HashMap<State, u64> = HashMap::new();
+        for &state in states.iter().rev() {
+            let count = if state == (self.entrypoint, 0) {
+                1
+            } else {
+                self.get_predecessors(state)
+                    .iter()
+                    .filter_map(|predecessor| counts.get(predecessor))
+                    .fold(0u64, |sum, count| sum.saturating_add(*count))
+            };
+            counts.insert(state, count);
+        }
+
+        self.get_exits()
+            .iter()
+            .filter_map(|exit| counts.get(exit))
+            .fold(0, |sum, count| sum.saturating_add(*count))
+    }
+
+    /// Up to `limit` distinct shortest paths, the first one is the same as the solved path of the run
+    pub(crate) fn enumerate(&self, limit: usize) -> Vec<Vec<Position>> {
+        let mut paths = vec![];
+
+        // the path is built from the exit backwards, every state has a predecessor
+        // until the entrypoint is reached so each branch completes into a shortest path
+        let mut path: Vec<State> = vec![];
+        // untried states of every path depth, reversed so that popping yields the preferred one first
+        let mut candidates: Vec<Vec<State>> = vec![self.get_exits().into_iter().rev().collect()];
+
+        while paths.len() < limit {
+            let state = match candidates.last_mut().map(|states| states.pop()) {
+                Some(Some(state)) => state,
+                Some(None) => {
+                    // all branches of the last state were walked, backtrack
+                    candidates.pop();
+                    path.pop();
+                    continue;
+                }
+                None => break,
+            };
+
+            path.push(state);
+            if state == (self.entrypoint, 0) {
+                paths.push(path.iter().rev().map(|(position, _)| *position).collect());
+                path.pop();
+                continue;
+            }
+
+            candidates.push(self.get_predecessors(state).into_iter().rev().collect());
+        }
+
+        paths
+    }
+
+    /// Exits reached with the run distance, the exit the run ended on goes first
+    fn get_exits(&self) -> Vec<State> {
+        let completed = self.ascending_checkpoint_levels.len();
+
+        let mut exits: Vec<State> = self
+            .nodes
+            .positions()
+            .map(|position| (position, completed))
+            .filter(|&state| self.get_distance(state) == Some(self.distance))
+            .collect();
+        exits.sort_by_key(|(position, _)| *position != self.exit);
+
+        exits
+    }
+
+    fn get_distance(&self, (position, completed): State) -> Option<u32> {
+        let node = self.nodes.get_node(&position);
+
+        let distance = match self.ascending_checkpoint_levels.get(completed) {
+            // checkpoint of the level completes it, so the path can't stand on it with the level unfinished
+            Some(&level) if node.is_checkpoint(level) => None,
+            Some(&level) => node.distance(level),
+            None => {
+                let last_level = *self
+                    .ascending_checkpoint_levels
+                    .get(completed.checked_sub(1)?)?;
+                if node.is_checkpoint(last_level) {
+                    node.distance(last_level)
+                } else {
+                    None
+                }
+            }
+        };
+
+        distance.filter(|&distance| distance <= self.distance)
+    }
+
+    /// Predecessors ordered the same way the solved path prefers them,
+    /// completing the previous level first and then by neighbor order
+    fn get_predecessors(&self, state: State) -> Vec<State> {
+        let (position, completed) = state;
+        let distance = match self.get_distance(state) {
+            Some(distance) if distance > 0 => distance,
+            _ => return vec![],
+        };
+
+        let node = self.nodes.get_node(&position);
+        let neighbors = self.nodes.get_neighbors_positions(&position);
+        let mut predecessors = vec![];
+
+        let completes_previous_level =
+            completed > 0 && node.is_checkpoint(self.ascending_checkpoint_levels[completed - 1]);
+        if completes_previous_level {
+            predecessors.extend(neighbors.iter().map(|&neighbor| (neighbor, completed - 1)));
+        }
+        if completed < self.ascending_checkpoint_levels.len() {
+            predecessors.extend(neighbors.iter().map(|&neighbor| (neighbor, completed)));
+        }
+
+        predecessors.retain(|&predecessor| self.get_distance(predecessor) == Some(distance - 1));
+        predecessors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use maze_core::{Checkpoint, Maze, MazeConfig, ScoringRuleType};
+
+    use crate::{runner::create_board_with_soft_walls, MazeRunner};
+
+    use super::*;
+
+    /// Counts walks of the score length following the game rules, every shortest path is such walk
+    fn get_brute_force_count(maze: &Maze, walls: &[Position]) -> Result<u64, Box<dyn Error>> {
+        let runner = MazeRunner::new(maze);
+        let run = runner
+            .run(&walls.to_vec())?
+            .ok_or("maze should be solvable")?;
+        let levels = run.checkpoint_levels().to_vec();
+
+        let board = create_board_with_soft_walls(maze, &walls.to_vec())?;
+        let nodes = Nodes::new(&board);
+
+        let mut count = 0;
+        let mut stack = vec![(*run.entrypoint(), 0, 0)];
+        while let Some((position, completed, distance)) = stack.pop() {
+            if completed == levels.len() {
+                if distance == run.score() {
+                    count += 1;
+                }
+                continue;
+            }
+            if distance == run.score() {
+                continue;
+            }
+
+            for neighbor in nodes.get_neighbors_positions(&position) {
+                let node = nodes.get_node(&neighbor);
+                if !node.can_enter() {
+                    continue;
+                }
+                let completed = if node.is_checkpoint(levels[completed]) {
+                    completed + 1
+                } else {
+                    completed
+                };
+                stack.push((neighbor, completed, distance + 1));
+            }
+        }
+
+        Ok(count)
+    }
+
+    #[test]
+    fn test_count_open_square() -> Result<(), Box<dyn Error>> {
+        let maze = Maze::new(&MazeConfig {
+            col_count: 3,
+            row_count: 3,
+            floor_count: 1,
+            max_soft_wall_count: 3,
+            walls: vec![],
+            entrypoints: vec![Position::new(0, 0)],
+            checkpoints: vec![Checkpoint {
+                position: Position::new(2, 2),
+                level: 1,
+            }],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
+        })?;
+
+        let runner = MazeRunner::new(&maze);
+        let run = runner.run(&vec![])?.ok_or("maze should be solvable")?;
+        assert_eq!(run.shortest_path_count(), 6);
+
+        let paths = run.shortest_paths(10);
+        assert_eq!(paths.len(), 6);
+        assert_eq!(paths[0], run.solved_path()?);
+        assert_eq!(paths.iter().collect::<HashSet<_>>().len(), 6);
+        assert_eq!(run.shortest_paths(2).len(), 2);
+
+        let run = runner
+            .run(&vec![Position::new(1, 1)])?
+            .ok_or("maze should be solvable")?;
+        assert_eq!(run.shortest_path_count(), 2);
+
+        let run = runner
+            .run(&vec![Position::new(1, 1), Position::new(0, 1)])?
+            .ok_or("maze should be solvable")?;
+        assert_eq!(run.shortest_path_count(), 1);
+        assert_eq!(run.shortest_paths(5), vec![run.solved_path()?]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_count_against_brute_force() -> Result<(), Box<dyn Error>> {
+        let maze = Maze::new(&MazeConfig {
+            col_count: 5,
+            row_count: 4,
+            floor_count: 1,
+            max_soft_wall_count: 10,
+            walls: vec![Position::new(2, 1)],
+            entrypoints: vec![Position::new(0, 0)],
+            checkpoints: vec![
+                Checkpoint {
+                    position: Position::new(3, 0),
+                    level: 1,
+                },
+                Checkpoint {
+                    position: Position::new(1, 2),
+                    level: 1,
+                },
+                Checkpoint {
+                    position: Position::new(2, 3),
+                    level: 2,
+                },
+                Checkpoint {
+                    position: Position::new(4, 3),
+                    level: 3,
+                },
+                Checkpoint {
+                    position: Position::new(4, 0),
+                    level: 3,
+                },
+            ],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
+        })?;
+
+        let wall_sets = [
+            vec![],
+            vec![Position::new(1, 1)],
+            vec![Position::new(0, 2), Position::new(3, 2)],
+            vec![Position::new(2, 2), Position::new(3, 3)],
+        ];
+
+        for walls in wall_sets.iter() {
+            let run = MazeRunner::new(&maze)
+                .run(walls)?
+                .ok_or("maze should be solvable")?;
+            let count = run.shortest_path_count();
+
+            assert_eq!(count, get_brute_force_count(&maze, walls)?);
+            assert_eq!(run.shortest_paths(usize::MAX).len() as u64, count);
+            assert_eq!(run.shortest_paths(1), vec![run.solved_path()?]);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_count_saturates() -> Result<(), Box<dyn Error>> {
+        let maze = Maze::new(&MazeConfig {
+            col_count: 40,
+            row_count: 40,
+            floor_count: 1,
+            max_soft_wall_count: 0,
+            walls: vec![],
+            entrypoints: vec![Position::new(0, 0)],
+            checkpoints: vec![Checkpoint {
+                position: Position::new(39, 39),
+                level: 1,
+            }],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
+        })?;
+
+        let run = MazeRunner::new(&maze)
+            .run(&vec![])?
+            .ok_or("maze should be solvable")?;
+
+        assert_eq!(run.shortest_path_count(), u64::MAX);
+        assert_eq!(run.shortest_paths(3).len(), 3);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,190 @@
+use maze_core::{Maze, Position, TileKind};
+
+use crate::{
+    analysis::get_dead_tiles,
+    bounds::nodes_exceed_upper_bound,
+    nodes::{Nodes, TileGrid},
+    runner::get_ascending_checkpoint_levels,
+    runner_error::MazeRunnerError,
+};
+
+/// Soft walls placed group by group on a board kept between the checks, so trying a group
+/// doesn't rebuild the board.
+///
+/// Walls only ever shrink the segments, so tiles once dead stay dead while walls are added.
+/// Dead tiles are therefore recomputed only when a tile isn't dead with the walls they were
+/// last computed for.
+pub struct WallPlacement<'a> {
+    maze: &'a Maze,
+    nodes: Nodes,
+    ascending_checkpoint_levels: Vec<i32>,
+    walls: Vec<Position>,
+    dead: TileGrid<bool>,
+    // number of walls the dead tiles were computed with, they are a prefix of the current walls
+    dead_wall_count: Option<usize>,
+}
+
+impl<'a> WallPlacement<'a> {
+    pub fn new(maze: &'a Maze) -> Self {
+        let nodes = Nodes::new(maze.floors());
+        let dead = nodes.create_grid(false);
+
+        Self {
+            maze,
+            nodes,
+            ascending_checkpoint_levels: get_ascending_checkpoint_levels(maze.floors()),
+            walls: vec![],
+            dead,
+            dead_wall_count: None,
+        }
+    }
+
+    pub fn walls(&self) -> &Vec<Position> {
+        &self.walls
+    }
+
+    /// Walls all tiles of the group, nothing is walled if any of them can't be
+    pub fn push_walls(&mut self, group: &[Position]) -> Result<(), MazeRunnerError> {
+        let max_soft_wall_count = self.maze.max_soft_wall_count();
+        if max_soft_wall_count < (self.walls.len() + group.len()) as u32 {
+            return Err(MazeRunnerError::TooManySoftWalls {
+                limit: max_soft_wall_count,
+            });
+        }
+
+        let (col_count, row_count) = self.nodes.dimensions();
+        for &position in group {
+            if position.x >= col_count
+                || position.y >= row_count
+                || position.floor_index() >= self.nodes.floor_count()
+            {
+                return Err(MazeRunnerError::WallOutOfBounds { position });
+            }
+            if self.nodes.get_node(&position).kind() != TileKind::Empty
+                || group.iter().filter(|&&wall| wall == position).count() > 1
+            {
+                return Err(MazeRunnerError::OverlappingWall { position });
+            }
+        }
+
+        for position in group {
+            self.nodes.get_node_mut(position).set_kind(TileKind::Wall);
+        }
+        self.walls.extend_from_slice(group);
+
+        Ok(())
+    }
+
+    /// Removes the last `count` walls
+    pub fn pop_walls(&mut self, count: usize) {
+        for position in self.walls.split_off(self.walls.len().saturating_sub(count)) {
+            self.nodes.get_node_mut(&position).set_kind(TileKind::Empty);
+        }
+
+        // dead tiles of more walls don't hold for fewer of them
+        if matches!(self.dead_wall_count, Some(count) if count > self.walls.len()) {
+            self.dead = self.nodes.create_grid(false);
+            self.dead_wall_count = None;
+        }
+    }
+
+    /// Same as `reachability(maze, walls)?.dead` at the position
+    pub fn is_dead(&mut self, position: &Position) -> bool {
+        if *self.dead.get(position) || self.dead_wall_count == Some(self.walls.len()) {
+            return *self.dead.get(position);
+        }
+
+        self.dead = get_dead_tiles(self.maze, &self.nodes, &self.ascending_checkpoint_levels);
+        self.dead_wall_count = Some(self.walls.len());
+
+        *self.dead.get(position)
+    }
+
+    /// Same as `exceeds_upper_bound(maze, walls, score)`
+    pub fn exceeds_upper_bound(&self, score: u32) -> bool {
+        nodes_exceed_upper_bound(
+            self.maze,
+            &self.nodes,
+            &self.ascending_checkpoint_levels,
+            score,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use maze_core::{Checkpoint, MazeConfig, ScoringRuleType};
+
+    use crate::{analysis::reachability, exceeds_upper_bound, upper_bound};
+
+    use super::*;
+
+    #[test]
+    fn test_wall_placement_matches_full_analysis() -> Result<(), Box<dyn Error>> {
+        let config = MazeConfig {
+            col_count: 5,
+            row_count: 4,
+            floor_count: 1,
+            max_soft_wall_count: 4,
+            walls: vec![],
+            entrypoints: vec![Position::new(0, 0)],
+            checkpoints: vec![Checkpoint {
+                position: Position::new(4, 3),
+                level: 1,
+            }],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
+        };
+        let maze = Maze::new(&config)?;
+        let mut placement = WallPlacement::new(&maze);
+
+        let assert_matches = |placement: &mut WallPlacement| -> Result<(), Box<dyn Error>> {
+            let walls = placement.walls().clone();
+            let dead = reachability(&maze, &walls)?.dead;
+            let walled = Maze::new(&MazeConfig {
+                walls: walls.clone(),
+                ..config.clone()
+            })?;
+            let bound = upper_bound(&walled);
+
+            for position in placement.nodes.positions().collect::<Vec<_>>() {
+                assert_eq!(
+                    placement.is_dead(&position),
+                    dead[position.floor_index()][position.x][position.y]
+                );
+            }
+            for score in [bound.saturating_sub(1), bound] {
+                assert_eq!(
+                    placement.exceeds_upper_bound(score),
+                    exceeds_upper_bound(&maze, &walls, score)?
+                );
+            }
+
+            Ok(())
+        };
+
+        assert_matches(&mut placement)?;
+        // the wall cuts the corner off, its tiles become dead
+        placement.push_walls(&[Position::new(1, 0), Position::new(1, 1)])?;
+        placement.push_walls(&[Position::new(0, 2)])?;
+        assert_matches(&mut placement)?;
+        placement.pop_walls(1);
+        assert_matches(&mut placement)?;
+        placement.pop_walls(2);
+        assert_matches(&mut placement)?;
+
+        assert!(matches!(
+            placement.push_walls(&[Position::new(4, 3)]),
+            Err(MazeRunnerError::OverlappingWall { .. })
+        ));
+        assert!(matches!(
+            placement.push_walls(&[Position::new(5, 0)]),
+            Err(MazeRunnerError::WallOutOfBounds { .. })
+        ));
+        assert!(placement.walls().is_empty());
+
+        Ok(())
+    }
+}
@@ -1,41 +1,147 @@
 use std::collections::VecDeque;
 
-use maze_core::{Position, TileBoard};
+use maze_core::{Position, TileFloors};
 
-use super::nodes::Nodes;
+use super::{
+    nodes::Nodes,
+    paths::ShortestPaths,
+    runner_error::MazeRunnerError,
+    trace::{SearchEvent, SearchObserver},
+};
 
 pub(super) fn run_maze(
-    board: &TileBoard,
-    ascending_checkpoint_levels: &Vec<i32>,
+    floors: &TileFloors,
+    ascending_checkpoint_levels: &[i32],
     entrypoint_position: &Position,
 ) -> Option<MazeRunResult> {
-    let mut nodes = Nodes::new(board);
-    let entrypoint_node = nodes.get_node_mut(entrypoint_position);
-    entrypoint_node.set_distance(ascending_checkpoint_levels[0], 0);
+    // unit observer ignores all events so the plain run doesn't pay for tracing
+    run_maze_observed(
+        floors,
+        ascending_checkpoint_levels,
+        entrypoint_position,
+        &mut (),
+    )
+}
 
-    let mut queue = VecDeque::new();
-    queue.push_back((*entrypoint_position, 0, 0));
+pub(super) fn run_maze_observed<O: SearchObserver>(
+    floors: &TileFloors,
+    ascending_checkpoint_levels: &[i32],
+    entrypoint_position: &Position,
+    observer: &mut O,
+) -> Option<MazeRunResult> {
+    let mut search = Search::start(
+        floors,
+        ascending_checkpoint_levels,
+        entrypoint_position,
+        observer,
+    )?;
+
+    loop {
+        match search.step(observer) {
+            SearchStatus::Running => continue,
+            SearchStatus::Solved {
+                exit_position,
+                distance,
+            } => return Some(search.into_result(exit_position, distance)),
+            SearchStatus::Unsolvable => return None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum SearchStatus {
+    Running,
+    Solved {
+        exit_position: Position,
+        distance: u32,
+    },
+    Unsolvable,
+}
+
+/// Breadth first search from a single entrypoint advanced by one dequeued node per step
+pub(super) struct Search<'a> {
+    ascending_checkpoint_levels: &'a [i32],
+    entrypoint_position: Position,
+    nodes: Nodes,
+    queue: VecDeque<(Position, u32, usize)>,
+}
+
+impl<'a> Search<'a> {
+    pub(super) fn start<O: SearchObserver>(
+        floors: &TileFloors,
+        ascending_checkpoint_levels: &'a [i32],
+        entrypoint_position: &Position,
+        observer: &mut O,
+    ) -> Option<Self> {
+        // maze without checkpoints has no exit to reach
+        let first_level = *ascending_checkpoint_levels.first()?;
+
+        let mut nodes = Nodes::new(floors);
+        let entrypoint_node = nodes.get_node_mut(entrypoint_position);
+        entrypoint_node.set_distance(first_level, 0);
+
+        let mut queue = VecDeque::new();
+        queue.push_back((*entrypoint_position, 0, 0));
+        observer.observe(SearchEvent::Started {
+            entrypoint: *entrypoint_position,
+        });
+
+        Some(Self {
+            ascending_checkpoint_levels,
+            entrypoint_position: *entrypoint_position,
+            nodes,
+            queue,
+        })
+    }
+
+    pub(super) fn step<O: SearchObserver>(&mut self, observer: &mut O) -> SearchStatus {
+        let ascending_checkpoint_levels = self.ascending_checkpoint_levels;
 
-    let (exit_position, distance) = loop {
         // if the queue is empty we can safely say that there is no solution to this maze
-        let (current_position, current_distance, current_level_index) = queue.pop_front()?;
+        let (current_position, current_distance, current_level_index) = match self.queue.pop_front()
+        {
+            Some(item) => item,
+            None => {
+                observer.observe(SearchEvent::Unsolvable {
+                    entrypoint: self.entrypoint_position,
+                });
+                return SearchStatus::Unsolvable;
+            }
+        };
 
         // this means we entered checkpoint of the last layer and the maze is solved
         if current_level_index >= ascending_checkpoint_levels.len() {
-            break (current_position, current_distance);
+            observer.observe(SearchEvent::Solved {
+                entrypoint: self.entrypoint_position,
+                score: current_distance,
+            });
+            return SearchStatus::Solved {
+                exit_position: current_position,
+                distance: current_distance,
+            };
         }
 
         let current_level = ascending_checkpoint_levels[current_level_index];
+        observer.observe(SearchEvent::NodeExpanded {
+            position: current_position,
+            distance: current_distance,
+            level: current_level,
+        });
 
-        get_eligible_neighbors(&nodes, &current_position, current_level)
+        get_eligible_neighbors(&self.nodes, &current_position, current_level)
             .into_iter()
             .for_each(|neighbor_pos| {
-                let neighbor = nodes.get_node_mut(&neighbor_pos);
+                let neighbor = self.nodes.get_node_mut(&neighbor_pos);
                 let mut neighbor_level_index = current_level_index;
                 let neighbor_distance = current_distance + 1;
 
                 if neighbor.is_checkpoint(current_level) {
                     neighbor_level_index += 1;
+                    observer.observe(SearchEvent::LevelAdvanced {
+                        position: neighbor_pos,
+                        distance: neighbor_distance,
+                        level: current_level,
+                    });
 
                     // if we progress to the next level we need to set checkpoint distance for next level as a entrypoint for that level
                     if neighbor_level_index < ascending_checkpoint_levels.len() {
@@ -45,16 +151,28 @@ pub(super) fn run_maze(
                 }
 
                 neighbor.set_distance(current_level, neighbor_distance);
-                queue.push_back((neighbor_pos, neighbor_distance, neighbor_level_index));
-            })
-    };
-
-    Some(MazeRunResult {
-        asc_checkpoint_levels: ascending_checkpoint_levels.clone(),
-        exit_position,
-        evaluated_nodes: nodes,
-        distance,
-    })
+                self.queue
+                    .push_back((neighbor_pos, neighbor_distance, neighbor_level_index));
+                observer.observe(SearchEvent::NodeEnqueued {
+                    position: neighbor_pos,
+                    distance: neighbor_distance,
+                    level: current_level,
+                });
+            });
+
+        SearchStatus::Running
+    }
+
+    fn into_result(self, exit_position: Position, distance: u32) -> MazeRunResult {
+        MazeRunResult {
+            asc_checkpoint_levels: self.ascending_checkpoint_levels.to_vec(),
+            entrypoint_position: self.entrypoint_position,
+            exit_position,
+            evaluated_nodes: self.nodes,
+            distance,
+            score: distance,
+        }
+    }
 }
 
 fn get_eligible_neighbors(
@@ -63,69 +181,187 @@ fn get_eligible_neighbors(
     current_level: i32,
 ) -> Vec<Position> {
     nodes
-        .get_neighbors_positions(&current_position)
+        .get_neighbors_positions(current_position)
         .iter()
         .cloned()
         .filter(|neighbor_pos| {
-            let neighbor = nodes.get_node(&neighbor_pos);
+            let neighbor = nodes.get_node(neighbor_pos);
             neighbor.can_enter() && !neighbor.has_distance(current_level)
         })
         .collect()
 }
 
+/// Distances of tiles of a single floor indexed the same way as the tile board, `None` for tiles the run never reached
+pub type DistanceField = Vec<Vec<Option<u32>>>;
+
+/// Distance fields of all floors of the maze indexed by floor, the ground floor goes first
+pub type DistanceFloors = Vec<DistanceField>;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PathSegment {
+    pub level: i32,
+    pub start: Position,
+    pub checkpoint: Position,
+    pub length: u32,
+}
+
+pub struct EntrypointRun {
+    pub entrypoint: Position,
+    pub result: Option<MazeRunResult>,
+}
+
 pub struct MazeRunResult {
     asc_checkpoint_levels: Vec<i32>,
+    entrypoint_position: Position,
     exit_position: Position,
     evaluated_nodes: Nodes,
     distance: u32,
+    score: u32,
 }
 
 impl MazeRunResult {
-    pub fn solved_path(&self) -> Vec<Position> {
+    /// Reconstructs the shortest path from the entrypoint to the exit,
+    /// fails only if the evaluated nodes are inconsistent with the run distance
+    pub fn solved_path(&self) -> Result<Vec<Position>, MazeRunnerError> {
         let mut best_path = Vec::with_capacity(self.distance as usize + 1);
         best_path.push(self.evaluated_nodes.get_node(&self.exit_position));
 
-        let mut remaining_levels = self.asc_checkpoint_levels.clone();
-
-        let mut previous_level = remaining_levels.pop();
-        let mut current_level = remaining_levels.pop();
-
-        let mut iterations_remaining = self.distance + 2;
-        loop {
-            iterations_remaining -= 1;
-            if iterations_remaining <= 0 {
-                panic!("Unable to get solved path for solved maze run! Too many iterations.");
-            }
+        let mut level_index = self.asc_checkpoint_levels.len().saturating_sub(1);
+        let mut distance = self.distance;
 
+        while distance > 0 {
             let current_node = best_path[best_path.len() - 1];
+            let level = self.asc_checkpoint_levels[level_index];
 
-            match current_level {
-                Some(level) if current_node.is_checkpoint(level) => {
-                    previous_level = current_level;
-                    current_level = remaining_levels.pop()
+            // the path switches to the lower level only on the checkpoint where the lower level was completed,
+            // other checkpoints of the lower level may lay on the path while walking the current level
+            if level_index > 0 {
+                let lower_level = self.asc_checkpoint_levels[level_index - 1];
+                if current_node.is_checkpoint(lower_level)
+                    && current_node.distance(lower_level) == Some(distance)
+                {
+                    level_index -= 1;
+                    continue;
                 }
-                None if current_node.is_entrypoint() => break,
-                _ => (),
             }
 
             let neighbor = self
                 .evaluated_nodes
-                .get_lowest_distance_neighbor(&current_node.position(), previous_level.unwrap())
-                .expect("Expected to find lowest distance neighbor for solved maze run!");
+                .get_previous_neighbor(current_node.position(), level, distance)
+                .ok_or(MazeRunnerError::PathReconstructionFailed {
+                    position: *current_node.position(),
+                })?;
 
             best_path.push(neighbor);
+            distance -= 1;
         }
 
-        best_path
+        Ok(best_path
             .iter()
             .rev()
             .map(|node| node.position())
             .copied()
-            .collect()
+            .collect())
     }
 
+    /// Number of distinct shortest paths from the entrypoint to any exit, saturates at `u64::MAX`.
+    /// A single path means the solution is forced.
+    pub fn shortest_path_count(&self) -> u64 {
+        self.shortest_paths_finder().count()
+    }
+
+    /// Up to `limit` distinct shortest paths, the first one is always the solved path
+    pub fn shortest_paths(&self, limit: usize) -> Vec<Vec<Position>> {
+        self.shortest_paths_finder().enumerate(limit)
+    }
+
+    fn shortest_paths_finder(&self) -> ShortestPaths<'_> {
+        ShortestPaths::new(
+            &self.evaluated_nodes,
+            &self.asc_checkpoint_levels,
+            self.entrypoint_position,
+            self.exit_position,
+            self.distance,
+        )
+    }
+
+    /// Splits the solved path into segments between consecutive checkpoint levels,
+    /// each segment ends on the first checkpoint of its level entered by the path
+    pub fn segments(&self) -> Result<Vec<PathSegment>, MazeRunnerError> {
+        Ok(self.split_path(&self.solved_path()?))
+    }
+
+    /// Same as `segments` for an already reconstructed solved path
+    pub fn split_path(&self, path: &[Position]) -> Vec<PathSegment> {
+        let mut segments = Vec::with_capacity(self.asc_checkpoint_levels.len());
+        let mut start_index = 0;
+
+        for (index, position) in path.iter().enumerate().skip(1) {
+            let level = match self.asc_checkpoint_levels.get(segments.len()) {
+                Some(&level) => level,
+                None => break,
+            };
+
+            if self.evaluated_nodes.get_node(position).is_checkpoint(level) {
+                segments.push(PathSegment {
+                    level,
+                    start: path[start_index],
+                    checkpoint: *position,
+                    length: (index - start_index) as u32,
+                });
+                start_index = index;
+            }
+        }
+
+        segments
+    }
+
+    /// Distances from the entrypoint of tiles of every floor visited while searching for the checkpoint of the given level.
+    /// The search stops as soon as the maze is solved, so tiles further than the exit may be missing.
+    pub fn distance_field(&self, checkpoint_level: i32) -> Option<DistanceFloors> {
+        if !self.asc_checkpoint_levels.contains(&checkpoint_level) {
+            return None;
+        }
+
+        Some(self.evaluated_nodes.get_distance_field(checkpoint_level))
+    }
+
+    /// Lowest distance from the entrypoint of every tile of every floor over all checkpoint levels
+    pub fn start_distance_field(&self) -> DistanceFloors {
+        self.evaluated_nodes.get_lowest_distance_field()
+    }
+
+    pub fn floor_count(&self) -> usize {
+        self.evaluated_nodes.floor_count()
+    }
+
+    pub fn checkpoint_levels(&self) -> &[i32] {
+        &self.asc_checkpoint_levels
+    }
+
+    pub fn entrypoint(&self) -> &Position {
+        &self.entrypoint_position
+    }
+
+    /// Approximate number of bytes the result occupies, used to cap memory of cached runs
+    pub(crate) fn memory_size(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.asc_checkpoint_levels.capacity() * std::mem::size_of::<i32>()
+            + self.evaluated_nodes.memory_size()
+    }
+
+    /// Score of the maze the run stands for, it differs from the distance when the scoring rule combines several entrypoints
     pub fn score(&self) -> u32 {
-        // currently score equates to distance but in the future it could deviate with modifiers usage
+        self.score
+    }
+
+    /// Length of the solved path
+    pub fn distance(&self) -> u32 {
         self.distance
     }
+
+    pub(crate) fn with_score(mut self, score: u32) -> Self {
+        self.score = score;
+        self
+    }
 }
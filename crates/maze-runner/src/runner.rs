@@ -1,66 +1,170 @@
-use maze_core::{Maze, Position, TileBoard, TileKind};
+use maze_core::{Maze, Position, TileFloors, TileKind};
 
-use crate::{run::run_maze, runner_error::MazeRunnerError};
+use crate::{
+    batch::evaluate_in_order,
+    bitboard::FastBoard,
+    run::{run_maze, EntrypointRun},
+    runner_error::MazeRunnerError,
+    scoring::{create_scoring_rule, EntrypointScore, ScoreBreakdown, ScoringRule},
+    trace::MazeTrace,
+};
 
 use super::run::MazeRunResult;
 
 pub struct MazeRunner<'a> {
     maze: &'a Maze,
     ascending_checkpoint_levels: Vec<i32>,
+    scoring_rule: Box<dyn ScoringRule>,
+    fast_board: Option<FastBoard>,
 }
 
 impl<'a> MazeRunner<'a> {
+    /// Creates runner scoring the maze with the scoring rule of the maze
     pub fn new(maze: &'a Maze) -> Self {
-        let mut checkpoint_levels: Vec<i32> = maze
-            .board()
-            .iter()
-            .flat_map(|row| {
-                row.iter()
-                    .filter(|kind| match kind {
-                        TileKind::Checkpoint { level: _ } => true,
-                        _ => false,
-                    })
-                    .map(|kind| match kind {
-                        TileKind::Checkpoint { level } => *level,
-                        _ => todo!(),
-                    })
-            })
-            .collect();
-        checkpoint_levels.sort_by(|a, b| a.cmp(b));
-        checkpoint_levels.dedup();
+        Self::with_scoring_rule(maze, create_scoring_rule(maze.scoring_rule()))
+    }
+
+    /// Creates runner that combines scores of separate entrypoints with the given rule instead of the rule of the maze
+    pub fn with_scoring_rule(maze: &'a Maze, scoring_rule: Box<dyn ScoringRule>) -> Self {
+        let ascending_checkpoint_levels = get_ascending_checkpoint_levels(maze.floors());
+        let fast_board = FastBoard::new(maze.floors(), &ascending_checkpoint_levels);
 
         Self {
             maze,
-            ascending_checkpoint_levels: checkpoint_levels,
+            ascending_checkpoint_levels,
+            scoring_rule,
+            fast_board,
         }
     }
 
+    /// Run of the entrypoint the scoring rule picks, carrying the score the rule combines from all entrypoints.
+    /// Returns `None` if the rule considers the maze unsolved.
     pub fn run(
         &self,
         soft_walls: &Vec<Position>,
     ) -> Result<Option<MazeRunResult>, MazeRunnerError> {
-        let board = create_board_with_soft_walls(&self.maze, soft_walls)?;
-        let mut best_result: Option<MazeRunResult> = None;
+        let runs = self.run_entrypoints(soft_walls)?;
+        let breakdown = self.scoring_rule.score(&runs);
+        let score = match breakdown.score {
+            Some(score) => score,
+            None => return Ok(None),
+        };
+
+        Ok(self
+            .scoring_rule
+            .representative(&breakdown.entrypoint_scores)
+            .and_then(|index| runs.into_iter().nth(index))
+            .and_then(|run| run.result)
+            .map(|result| result.with_score(score)))
+    }
+
+    /// Score of the maze together with the scores of separate entrypoints it was combined from,
+    /// boards up to 128 columns are searched over bitsets
+    pub fn score(&self, soft_walls: &Vec<Position>) -> Result<ScoreBreakdown, MazeRunnerError> {
+        let fast_board = match &self.fast_board {
+            Some(fast_board) => fast_board,
+            None => return Ok(self.scoring_rule.score(&self.run_entrypoints(soft_walls)?)),
+        };
+
+        let entrypoints = self.maze.entrypoints();
+        let scores =
+            fast_board.run_entrypoints(entrypoints, soft_walls, self.maze.max_soft_wall_count())?;
+        let entrypoint_scores: Vec<EntrypointScore> = entrypoints
+            .iter()
+            .zip(scores)
+            .map(|(&entrypoint, score)| EntrypointScore { entrypoint, score })
+            .collect();
 
-        for entrypoint in self.maze.entrypoints().iter() {
-            let current_run = run_maze(&board, &self.ascending_checkpoint_levels, entrypoint);
+        Ok(ScoreBreakdown {
+            score: self.scoring_rule.combine(&entrypoint_scores),
+            entrypoint_scores,
+        })
+    }
 
-            if let Some(new) = current_run {
-                best_result = match best_result {
-                    Some(old) if old.score() <= new.score() => Some(old),
-                    _ => Some(new),
-                };
+    /// Score of `run` without the evaluated path, the same as the score of `score` without the breakdown
+    pub fn run_score(&self, soft_walls: &Vec<Position>) -> Result<Option<u32>, MazeRunnerError> {
+        match &self.fast_board {
+            // the shortest run is found by a single search from all entrypoints at once
+            Some(fast_board) if self.scoring_rule.is_lowest_score() => {
+                fast_board.run(soft_walls, self.maze.max_soft_wall_count())
             }
+            _ => Ok(self.score(soft_walls)?.score),
         }
+    }
+
+    pub fn scoring_rule(&self) -> &dyn ScoringRule {
+        self.scoring_rule.as_ref()
+    }
 
-        Ok(best_result)
+    /// Runs every soft wall set separately, results are in the order of the wall sets.
+    /// Wall sets are evaluated in parallel when the `rayon` feature is enabled.
+    pub fn run_batch(
+        &self,
+        soft_wall_sets: &[Vec<Position>],
+    ) -> Vec<Result<Option<MazeRunResult>, MazeRunnerError>> {
+        evaluate_in_order(soft_wall_sets, |soft_walls| self.run(soft_walls))
     }
+
+    /// Same as `run_batch` but evaluates only scores, meant for solvers trying many candidate placements
+    pub fn run_score_batch(
+        &self,
+        soft_wall_sets: &[Vec<Position>],
+    ) -> Vec<Result<Option<u32>, MazeRunnerError>> {
+        evaluate_in_order(soft_wall_sets, |soft_walls| self.run_score(soft_walls))
+    }
+
+    /// Runs the maze from every entrypoint separately, results are in the order of maze entrypoints
+    pub fn run_entrypoints(
+        &self,
+        soft_walls: &Vec<Position>,
+    ) -> Result<Vec<EntrypointRun>, MazeRunnerError> {
+        let floors = create_board_with_soft_walls(self.maze, soft_walls)?;
+
+        Ok(self
+            .maze
+            .entrypoints()
+            .iter()
+            .map(|entrypoint| EntrypointRun {
+                entrypoint: *entrypoint,
+                result: run_maze(&floors, &self.ascending_checkpoint_levels, entrypoint),
+            })
+            .collect())
+    }
+
+    /// Mirrors the search of `run_entrypoints` as a sequence of events, e.g. for animating the search frontier
+    pub fn trace(&self, soft_walls: &Vec<Position>) -> Result<MazeTrace<'_>, MazeRunnerError> {
+        let floors = create_board_with_soft_walls(self.maze, soft_walls)?;
+
+        Ok(MazeTrace::new(
+            floors,
+            &self.ascending_checkpoint_levels,
+            self.maze.entrypoints(),
+        ))
+    }
+}
+
+pub(crate) fn get_ascending_checkpoint_levels(floors: &TileFloors) -> Vec<i32> {
+    let mut checkpoint_levels: Vec<i32> = floors
+        .iter()
+        .flatten()
+        .flat_map(|row| {
+            row.iter().filter_map(|kind| match kind {
+                TileKind::Checkpoint { level } => Some(*level),
+                _ => None,
+            })
+        })
+        .collect();
+    checkpoint_levels.sort();
+    checkpoint_levels.dedup();
+
+    checkpoint_levels
 }
 
-fn create_board_with_soft_walls(
+/// Tiles of all floors with the soft walls placed, soft walls may be placed on any floor
+pub(crate) fn create_board_with_soft_walls(
     maze: &Maze,
     soft_walls: &Vec<Position>,
-) -> Result<TileBoard, MazeRunnerError> {
+) -> Result<TileFloors, MazeRunnerError> {
     let max_soft_wall_count = maze.max_soft_wall_count();
     if max_soft_wall_count < soft_walls.len() as u32 {
         return Err(MazeRunnerError::TooManySoftWalls {
@@ -68,35 +172,31 @@ fn create_board_with_soft_walls(
         });
     }
 
-    let mut tiles: TileBoard = maze.board().clone();
-    for &Position { x, y } in soft_walls {
-        if x >= tiles.len() {
-            return Err(MazeRunnerError::WallOutOfBounds {
-                position: Position { x, y },
-            });
-        }
-        if y >= tiles[x].len() {
-            return Err(MazeRunnerError::WallOutOfBounds {
-                position: Position { x, y },
-            });
-        }
-        if tiles[x][y] != TileKind::Empty {
-            return Err(MazeRunnerError::OverlappingWall {
-                position: Position { x, y },
-            });
+    let mut floors: TileFloors = maze.floors().clone();
+    for &position in soft_walls {
+        let Position { x, y, .. } = position;
+        let tile = floors
+            .get_mut(position.floor_index())
+            .and_then(|tiles| tiles.get_mut(x))
+            .and_then(|column| column.get_mut(y))
+            .ok_or(MazeRunnerError::WallOutOfBounds { position })?;
+        if *tile != TileKind::Empty {
+            return Err(MazeRunnerError::OverlappingWall { position });
         }
 
-        tiles[x][y] = TileKind::Wall
+        *tile = TileKind::Wall
     }
 
-    Ok(tiles)
+    Ok(floors)
 }
 
 #[cfg(test)]
 mod tests {
     use std::error::Error;
 
-    use maze_core::{Checkpoint, MazeConfig};
+    use maze_core::{Checkpoint, MazeConfig, ScoringRuleType, Stairs};
+
+    use crate::PathSegment;
 
     // Note this useful idiom: importing names from outer (for mod tests) scope.
     use super::*;
@@ -106,13 +206,16 @@ mod tests {
         let maze = Maze::new(&MazeConfig {
             col_count: 8,
             row_count: 8,
+            floor_count: 1,
             max_soft_wall_count: 200,
             walls: vec![],
-            entrypoints: vec![Position { x: 0, y: 0 }],
+            entrypoints: vec![Position::new(0, 0)],
             checkpoints: vec![Checkpoint {
-                position: Position { x: 7, y: 7 },
+                position: Position::new(7, 7),
                 level: 1,
             }],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
         })?;
 
         let runner = MazeRunner::new(&maze);
@@ -121,23 +224,23 @@ mod tests {
         assert_eq!(result.as_ref().map(|res| res.score()), Some(14));
 
         assert_eq!(
-            result.map(|res| res.solved_path()),
+            result.map(|res| res.solved_path()).transpose()?,
             Some(vec![
-                Position { x: 0, y: 0 },
-                Position { x: 1, y: 0 },
-                Position { x: 2, y: 0 },
-                Position { x: 3, y: 0 },
-                Position { x: 4, y: 0 },
-                Position { x: 5, y: 0 },
-                Position { x: 6, y: 0 },
-                Position { x: 7, y: 0 },
-                Position { x: 7, y: 1 },
-                Position { x: 7, y: 2 },
-                Position { x: 7, y: 3 },
-                Position { x: 7, y: 4 },
-                Position { x: 7, y: 5 },
-                Position { x: 7, y: 6 },
-                Position { x: 7, y: 7 }
+                Position::new(0, 0),
+                Position::new(1, 0),
+                Position::new(2, 0),
+                Position::new(3, 0),
+                Position::new(4, 0),
+                Position::new(5, 0),
+                Position::new(6, 0),
+                Position::new(7, 0),
+                Position::new(7, 1),
+                Position::new(7, 2),
+                Position::new(7, 3),
+                Position::new(7, 4),
+                Position::new(7, 5),
+                Position::new(7, 6),
+                Position::new(7, 7)
             ])
         );
 
@@ -149,64 +252,67 @@ mod tests {
         let maze = Maze::new(&MazeConfig {
             col_count: 8,
             row_count: 8,
+            floor_count: 1,
             max_soft_wall_count: 200,
             walls: vec![],
-            entrypoints: vec![Position { x: 0, y: 0 }],
+            entrypoints: vec![Position::new(0, 0)],
             checkpoints: vec![Checkpoint {
-                position: Position { x: 7, y: 7 },
+                position: Position::new(7, 7),
                 level: 1,
             }],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
         })?;
 
         let runner = MazeRunner::new(&maze);
         let result = runner.run(&vec![
-            Position { x: 2, y: 0 },
-            Position { x: 2, y: 1 },
-            Position { x: 2, y: 2 },
-            Position { x: 2, y: 3 },
-            Position { x: 2, y: 4 },
-            Position { x: 2, y: 5 },
-            Position { x: 2, y: 6 },
-            Position { x: 4, y: 7 },
-            Position { x: 4, y: 6 },
-            Position { x: 4, y: 5 },
-            Position { x: 4, y: 4 },
-            Position { x: 4, y: 3 },
-            Position { x: 4, y: 2 },
+            Position::new(2, 0),
+            Position::new(2, 1),
+            Position::new(2, 2),
+            Position::new(2, 3),
+            Position::new(2, 4),
+            Position::new(2, 5),
+            Position::new(2, 6),
+            Position::new(4, 7),
+            Position::new(4, 6),
+            Position::new(4, 5),
+            Position::new(4, 4),
+            Position::new(4, 3),
+            Position::new(4, 2),
         ])?;
 
         assert_eq!(result.as_ref().map(|res| res.score()), Some(26));
 
         assert_eq!(
-            result.map(|res| res.solved_path()),
+            result.map(|res| res.solved_path()).transpose()?,
             Some(vec![
-                Position { x: 0, y: 0 },
-                Position { x: 1, y: 0 },
-                Position { x: 1, y: 1 },
-                Position { x: 1, y: 2 },
-                Position { x: 1, y: 3 },
-                Position { x: 1, y: 4 },
-                Position { x: 1, y: 5 },
-                Position { x: 1, y: 6 },
-                Position { x: 1, y: 7 },
-                Position { x: 2, y: 7 },
-                Position { x: 3, y: 7 },
-                Position { x: 3, y: 6 },
-                Position { x: 3, y: 5 },
-                Position { x: 3, y: 4 },
-                Position { x: 3, y: 3 },
-                Position { x: 3, y: 2 },
-                Position { x: 3, y: 1 },
-                Position { x: 4, y: 1 },
-                Position { x: 5, y: 1 },
-                Position { x: 6, y: 1 },
-                Position { x: 7, y: 1 },
-                Position { x: 7, y: 2 },
-                Position { x: 7, y: 3 },
-                Position { x: 7, y: 4 },
-                Position { x: 7, y: 5 },
-                Position { x: 7, y: 6 },
-                Position { x: 7, y: 7 }
+                Position::new(0, 0),
+                Position::new(1, 0),
+                Position::new(1, 1),
+                Position::new(1, 2),
+                Position::new(1, 3),
+                Position::new(1, 4),
+                Position::new(1, 5),
+                Position::new(1, 6),
+                Position::new(1, 7),
+                Position::new(2, 7),
+                Position::new(3, 7),
+                Position::new(3, 6),
+                Position::new(3, 5),
+                Position::new(3, 4),
+                Position::new(3, 3),
+                Position::new(3, 2),
+                Position::new(3, 1),
+                Position::new(4, 1),
+                Position::new(5, 1),
+                Position::new(6, 1),
+                Position::new(7, 1),
+                Position::new(7, 2),
+                Position::new(7, 3),
+                Position::new(7, 4),
+                Position::new(7, 5),
+                Position::new(7, 6),
+                Position::new(7, 7)
             ])
         );
 
@@ -218,25 +324,28 @@ mod tests {
         let maze = Maze::new(&MazeConfig {
             col_count: 8,
             row_count: 8,
+            floor_count: 1,
             max_soft_wall_count: 200,
             walls: vec![],
-            entrypoints: vec![Position { x: 0, y: 0 }],
+            entrypoints: vec![Position::new(0, 0)],
             checkpoints: vec![Checkpoint {
-                position: Position { x: 7, y: 7 },
+                position: Position::new(7, 7),
                 level: 1,
             }],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
         })?;
 
         let runner = MazeRunner::new(&maze);
         let result = runner.run(&vec![
-            Position { x: 2, y: 0 },
-            Position { x: 2, y: 1 },
-            Position { x: 2, y: 2 },
-            Position { x: 2, y: 3 },
-            Position { x: 2, y: 4 },
-            Position { x: 2, y: 5 },
-            Position { x: 2, y: 6 },
-            Position { x: 2, y: 7 },
+            Position::new(2, 0),
+            Position::new(2, 1),
+            Position::new(2, 2),
+            Position::new(2, 3),
+            Position::new(2, 4),
+            Position::new(2, 5),
+            Position::new(2, 6),
+            Position::new(2, 7),
         ])?;
 
         assert!(result.is_none());
@@ -249,41 +358,44 @@ mod tests {
         let maze = Maze::new(&MazeConfig {
             col_count: 8,
             row_count: 8,
+            floor_count: 1,
             max_soft_wall_count: 200,
             walls: vec![],
-            entrypoints: vec![Position { x: 0, y: 0 }, Position { x: 5, y: 5 }],
+            entrypoints: vec![Position::new(0, 0), Position::new(5, 5)],
             checkpoints: vec![Checkpoint {
-                position: Position { x: 7, y: 7 },
+                position: Position::new(7, 7),
                 level: 1,
             }],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
         })?;
 
         let runner = MazeRunner::new(&maze);
         let result = runner.run(&vec![
-            Position { x: 2, y: 0 },
-            Position { x: 2, y: 1 },
-            Position { x: 2, y: 2 },
-            Position { x: 2, y: 3 },
-            Position { x: 2, y: 4 },
-            Position { x: 2, y: 5 },
-            Position { x: 2, y: 6 },
-            Position { x: 4, y: 7 },
-            Position { x: 4, y: 6 },
-            Position { x: 4, y: 5 },
-            Position { x: 4, y: 4 },
-            Position { x: 4, y: 3 },
-            Position { x: 4, y: 2 },
+            Position::new(2, 0),
+            Position::new(2, 1),
+            Position::new(2, 2),
+            Position::new(2, 3),
+            Position::new(2, 4),
+            Position::new(2, 5),
+            Position::new(2, 6),
+            Position::new(4, 7),
+            Position::new(4, 6),
+            Position::new(4, 5),
+            Position::new(4, 4),
+            Position::new(4, 3),
+            Position::new(4, 2),
         ])?;
 
         assert_eq!(result.as_ref().map(|res| res.score()), Some(4));
         assert_eq!(
-            result.as_ref().map(|res| res.solved_path()),
+            result.as_ref().map(|res| res.solved_path()).transpose()?,
             Some(vec![
-                Position { x: 5, y: 5 },
-                Position { x: 6, y: 5 },
-                Position { x: 7, y: 5 },
-                Position { x: 7, y: 6 },
-                Position { x: 7, y: 7 }
+                Position::new(5, 5),
+                Position::new(6, 5),
+                Position::new(7, 5),
+                Position::new(7, 6),
+                Position::new(7, 7)
             ])
         );
 
@@ -295,19 +407,22 @@ mod tests {
         let maze = Maze::new(&MazeConfig {
             col_count: 6,
             row_count: 8,
+            floor_count: 1,
             max_soft_wall_count: 200,
             walls: vec![],
-            entrypoints: vec![Position { x: 0, y: 0 }],
+            entrypoints: vec![Position::new(0, 0)],
             checkpoints: vec![
                 Checkpoint {
-                    position: Position { x: 5, y: 5 },
+                    position: Position::new(5, 5),
                     level: 1,
                 },
                 Checkpoint {
-                    position: Position { x: 1, y: 1 },
+                    position: Position::new(1, 1),
                     level: 2,
                 },
             ],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
         })?;
 
         let runner = MazeRunner::new(&maze);
@@ -316,27 +431,27 @@ mod tests {
         assert_eq!(result.as_ref().map(|res| res.score()), Some(18));
 
         assert_eq!(
-            result.as_ref().map(|res| res.solved_path()),
+            result.as_ref().map(|res| res.solved_path()).transpose()?,
             Some(vec![
-                Position { x: 0, y: 0 },
-                Position { x: 1, y: 0 },
-                Position { x: 2, y: 0 },
-                Position { x: 3, y: 0 },
-                Position { x: 4, y: 0 },
-                Position { x: 5, y: 0 },
-                Position { x: 5, y: 1 },
-                Position { x: 5, y: 2 },
-                Position { x: 5, y: 3 },
-                Position { x: 5, y: 4 },
-                Position { x: 5, y: 5 },
-                Position { x: 4, y: 5 },
-                Position { x: 3, y: 5 },
-                Position { x: 2, y: 5 },
-                Position { x: 1, y: 5 },
-                Position { x: 1, y: 4 },
-                Position { x: 1, y: 3 },
-                Position { x: 1, y: 2 },
-                Position { x: 1, y: 1 }
+                Position::new(0, 0),
+                Position::new(1, 0),
+                Position::new(2, 0),
+                Position::new(3, 0),
+                Position::new(4, 0),
+                Position::new(5, 0),
+                Position::new(5, 1),
+                Position::new(5, 2),
+                Position::new(5, 3),
+                Position::new(5, 4),
+                Position::new(5, 5),
+                Position::new(4, 5),
+                Position::new(3, 5),
+                Position::new(2, 5),
+                Position::new(1, 5),
+                Position::new(1, 4),
+                Position::new(1, 3),
+                Position::new(1, 2),
+                Position::new(1, 1)
             ])
         );
 
@@ -348,19 +463,22 @@ mod tests {
         let maze = Maze::new(&MazeConfig {
             col_count: 6,
             row_count: 8,
+            floor_count: 1,
             max_soft_wall_count: 200,
             walls: vec![],
-            entrypoints: vec![Position { x: 0, y: 0 }, Position { x: 4, y: 4 }],
+            entrypoints: vec![Position::new(0, 0), Position::new(4, 4)],
             checkpoints: vec![
                 Checkpoint {
-                    position: Position { x: 5, y: 5 },
+                    position: Position::new(5, 5),
                     level: 1,
                 },
                 Checkpoint {
-                    position: Position { x: 1, y: 1 },
+                    position: Position::new(1, 1),
                     level: 2,
                 },
             ],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
         })?;
 
         let runner = MazeRunner::new(&maze);
@@ -369,47 +487,267 @@ mod tests {
         assert_eq!(result.as_ref().map(|res| res.score()), Some(10));
 
         assert_eq!(
-            result.as_ref().map(|res| res.solved_path()),
+            result.as_ref().map(|res| res.solved_path()).transpose()?,
+            Some(vec![
+                Position::new(4, 4),
+                Position::new(5, 4),
+                Position::new(5, 5),
+                Position::new(4, 5),
+                Position::new(3, 5),
+                Position::new(2, 5),
+                Position::new(1, 5),
+                Position::new(1, 4),
+                Position::new(1, 3),
+                Position::new(1, 2),
+                Position::new(1, 1)
+            ])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_entrypoints() -> Result<(), Box<dyn Error>> {
+        let maze = Maze::new(&MazeConfig {
+            col_count: 6,
+            row_count: 8,
+            floor_count: 1,
+            max_soft_wall_count: 200,
+            walls: vec![],
+            entrypoints: vec![
+                Position::new(0, 0),
+                Position::new(4, 4),
+                Position::new(0, 7),
+            ],
+            checkpoints: vec![
+                Checkpoint {
+                    position: Position::new(5, 5),
+                    level: 1,
+                },
+                Checkpoint {
+                    position: Position::new(1, 1),
+                    level: 2,
+                },
+            ],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
+        })?;
+
+        let runner = MazeRunner::new(&maze);
+        let results = runner.run_entrypoints(&vec![Position::new(0, 6), Position::new(1, 7)])?;
+
+        assert_eq!(
+            results
+                .iter()
+                .map(|run| (run.entrypoint, run.result.as_ref().map(|res| res.score())))
+                .collect::<Vec<_>>(),
+            vec![
+                (Position::new(0, 0), Some(18)),
+                (Position::new(4, 4), Some(10)),
+                (Position::new(0, 7), None),
+            ]
+        );
+        assert_eq!(
+            results[1].result.as_ref().map(|res| *res.entrypoint()),
+            Some(Position::new(4, 4))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_leveled_segments() -> Result<(), Box<dyn Error>> {
+        let maze = Maze::new(&MazeConfig {
+            col_count: 7,
+            row_count: 8,
+            floor_count: 1,
+            max_soft_wall_count: 200,
+            walls: vec![],
+            entrypoints: vec![Position::new(0, 0)],
+            checkpoints: vec![
+                Checkpoint {
+                    position: Position::new(5, 5),
+                    level: 1,
+                },
+                Checkpoint {
+                    position: Position::new(3, 3),
+                    level: 1,
+                },
+                Checkpoint {
+                    position: Position::new(1, 1),
+                    level: 2,
+                },
+            ],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
+        })?;
+
+        let runner = MazeRunner::new(&maze);
+        let result = runner.run(&vec![])?;
+
+        assert_eq!(
+            result.as_ref().map(|res| res.segments()).transpose()?,
             Some(vec![
-                Position { x: 4, y: 4 },
-                Position { x: 5, y: 4 },
-                Position { x: 5, y: 5 },
-                Position { x: 4, y: 5 },
-                Position { x: 3, y: 5 },
-                Position { x: 2, y: 5 },
-                Position { x: 1, y: 5 },
-                Position { x: 1, y: 4 },
-                Position { x: 1, y: 3 },
-                Position { x: 1, y: 2 },
-                Position { x: 1, y: 1 }
+                PathSegment {
+                    level: 1,
+                    start: Position::new(0, 0),
+                    checkpoint: Position::new(3, 3),
+                    length: 6,
+                },
+                PathSegment {
+                    level: 2,
+                    start: Position::new(3, 3),
+                    checkpoint: Position::new(1, 1),
+                    length: 4,
+                },
             ])
         );
 
         Ok(())
     }
 
+    #[test]
+    fn test_run_batch_keeps_input_order() -> Result<(), Box<dyn Error>> {
+        let maze = Maze::new(&MazeConfig {
+            col_count: 5,
+            row_count: 3,
+            floor_count: 1,
+            max_soft_wall_count: 2,
+            walls: vec![],
+            entrypoints: vec![Position::new(0, 0)],
+            checkpoints: vec![Checkpoint {
+                position: Position::new(4, 0),
+                level: 1,
+            }],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
+        })?;
+
+        let wall_sets = vec![
+            vec![],
+            vec![Position::new(1, 0)],
+            vec![Position::new(1, 0), Position::new(1, 1)],
+            vec![Position::new(7, 0)],
+            vec![
+                Position::new(1, 0),
+                Position::new(1, 1),
+                Position::new(1, 2),
+            ],
+            vec![Position::new(3, 0), Position::new(4, 1)],
+        ];
+
+        let runner = MazeRunner::new(&maze);
+        let scores: Vec<Option<Option<u32>>> = runner
+            .run_batch(&wall_sets)
+            .into_iter()
+            .map(|result| result.ok().map(|run| run.map(|run| run.score())))
+            .collect();
+        let fast_scores: Vec<Option<Option<u32>>> = runner
+            .run_score_batch(&wall_sets)
+            .into_iter()
+            .map(|result| result.ok())
+            .collect();
+
+        assert_eq!(
+            scores,
+            vec![
+                Some(Some(4)),
+                Some(Some(6)),
+                Some(Some(8)),
+                None,
+                None,
+                Some(None)
+            ]
+        );
+        assert_eq!(fast_scores, scores);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_leveled_distance_fields() -> Result<(), Box<dyn Error>> {
+        let maze = Maze::new(&MazeConfig {
+            col_count: 4,
+            row_count: 1,
+            floor_count: 1,
+            max_soft_wall_count: 200,
+            walls: vec![],
+            entrypoints: vec![Position::new(1, 0)],
+            checkpoints: vec![
+                Checkpoint {
+                    position: Position::new(0, 0),
+                    level: 1,
+                },
+                Checkpoint {
+                    position: Position::new(3, 0),
+                    level: 2,
+                },
+            ],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
+        })?;
+
+        let runner = MazeRunner::new(&maze);
+        let result = runner.run(&vec![])?;
+
+        assert_eq!(result.as_ref().map(|res| res.score()), Some(4));
+        assert_eq!(
+            result.as_ref().and_then(|res| res.distance_field(1)),
+            Some(vec![vec![
+                vec![Some(1)],
+                vec![Some(0)],
+                vec![Some(1)],
+                vec![Some(2)]
+            ]])
+        );
+        assert_eq!(
+            result.as_ref().and_then(|res| res.distance_field(2)),
+            Some(vec![vec![
+                vec![Some(1)],
+                vec![Some(2)],
+                vec![Some(3)],
+                vec![Some(4)]
+            ]])
+        );
+        assert_eq!(result.as_ref().and_then(|res| res.distance_field(3)), None);
+        assert_eq!(
+            result.as_ref().map(|res| res.start_distance_field()),
+            Some(vec![vec![
+                vec![Some(1)],
+                vec![Some(0)],
+                vec![Some(1)],
+                vec![Some(2)]
+            ]])
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_run_leveled_with_duplicate_checkpoints_0() -> Result<(), Box<dyn Error>> {
         let maze = Maze::new(&MazeConfig {
             col_count: 7,
             row_count: 8,
+            floor_count: 1,
             max_soft_wall_count: 200,
             walls: vec![],
-            entrypoints: vec![Position { x: 0, y: 0 }],
+            entrypoints: vec![Position::new(0, 0)],
             checkpoints: vec![
                 Checkpoint {
-                    position: Position { x: 5, y: 5 },
+                    position: Position::new(5, 5),
                     level: 1,
                 },
                 Checkpoint {
-                    position: Position { x: 3, y: 3 },
+                    position: Position::new(3, 3),
                     level: 1,
                 },
                 Checkpoint {
-                    position: Position { x: 1, y: 1 },
+                    position: Position::new(1, 1),
                     level: 2,
                 },
             ],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
         })?;
 
         let runner = MazeRunner::new(&maze);
@@ -418,19 +756,19 @@ mod tests {
         assert_eq!(result.as_ref().map(|res| res.score()), Some(10));
 
         assert_eq!(
-            result.as_ref().map(|res| res.solved_path()),
+            result.as_ref().map(|res| res.solved_path()).transpose()?,
             Some(vec![
-                Position { x: 0, y: 0 },
-                Position { x: 1, y: 0 },
-                Position { x: 2, y: 0 },
-                Position { x: 3, y: 0 },
-                Position { x: 3, y: 1 },
-                Position { x: 3, y: 2 },
-                Position { x: 3, y: 3 },
-                Position { x: 2, y: 3 },
-                Position { x: 1, y: 3 },
-                Position { x: 1, y: 2 },
-                Position { x: 1, y: 1 }
+                Position::new(0, 0),
+                Position::new(1, 0),
+                Position::new(2, 0),
+                Position::new(3, 0),
+                Position::new(3, 1),
+                Position::new(3, 2),
+                Position::new(3, 3),
+                Position::new(2, 3),
+                Position::new(1, 3),
+                Position::new(1, 2),
+                Position::new(1, 1)
             ])
         );
 
@@ -442,23 +780,26 @@ mod tests {
         let maze = Maze::new(&MazeConfig {
             col_count: 7,
             row_count: 8,
+            floor_count: 1,
             max_soft_wall_count: 200,
             walls: vec![],
-            entrypoints: vec![Position { x: 0, y: 0 }],
+            entrypoints: vec![Position::new(0, 0)],
             checkpoints: vec![
                 Checkpoint {
-                    position: Position { x: 0, y: 5 },
+                    position: Position::new(0, 5),
                     level: 1,
                 },
                 Checkpoint {
-                    position: Position { x: 4, y: 4 },
+                    position: Position::new(4, 4),
                     level: 1,
                 },
                 Checkpoint {
-                    position: Position { x: 5, y: 0 },
+                    position: Position::new(5, 0),
                     level: 2,
                 },
             ],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
         })?;
 
         let runner = MazeRunner::new(&maze);
@@ -467,22 +808,82 @@ mod tests {
         assert_eq!(result.as_ref().map(|res| res.score()), Some(13));
 
         assert_eq!(
-            result.as_ref().map(|res| res.solved_path()),
+            result.as_ref().map(|res| res.solved_path()).transpose()?,
             Some(vec![
-                Position { x: 0, y: 0 },
-                Position { x: 1, y: 0 },
-                Position { x: 2, y: 0 },
-                Position { x: 3, y: 0 },
-                Position { x: 4, y: 0 },
-                Position { x: 4, y: 1 },
-                Position { x: 4, y: 2 },
-                Position { x: 4, y: 3 },
-                Position { x: 4, y: 4 },
-                Position { x: 5, y: 4 },
-                Position { x: 5, y: 3 },
-                Position { x: 5, y: 2 },
-                Position { x: 5, y: 1 },
-                Position { x: 5, y: 0 }
+                Position::new(0, 0),
+                Position::new(1, 0),
+                Position::new(2, 0),
+                Position::new(3, 0),
+                Position::new(4, 0),
+                Position::new(4, 1),
+                Position::new(4, 2),
+                Position::new(4, 3),
+                Position::new(4, 4),
+                Position::new(5, 4),
+                Position::new(5, 3),
+                Position::new(5, 2),
+                Position::new(5, 1),
+                Position::new(5, 0)
+            ])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_leveled_path_passing_unused_checkpoint_of_lower_level() -> Result<(), Box<dyn Error>>
+    {
+        let maze = Maze::new(&MazeConfig {
+            col_count: 3,
+            row_count: 6,
+            floor_count: 1,
+            max_soft_wall_count: 200,
+            walls: vec![
+                Position::new(1, 4),
+                Position::new(0, 4),
+                Position::new(1, 3),
+                Position::new(1, 2),
+            ],
+            entrypoints: vec![Position::new(0, 5)],
+            checkpoints: vec![
+                Checkpoint {
+                    position: Position::new(2, 5),
+                    level: 1,
+                },
+                Checkpoint {
+                    position: Position::new(2, 2),
+                    level: 1,
+                },
+                Checkpoint {
+                    position: Position::new(0, 1),
+                    level: 2,
+                },
+                Checkpoint {
+                    position: Position::new(0, 2),
+                    level: 2,
+                },
+            ],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
+        })?;
+
+        let runner = MazeRunner::new(&maze);
+        let result = runner.run(&vec![])?;
+
+        assert_eq!(result.as_ref().map(|res| res.score()), Some(8));
+
+        assert_eq!(
+            result.as_ref().map(|res| res.solved_path()).transpose()?,
+            Some(vec![
+                Position::new(0, 5),
+                Position::new(1, 5),
+                Position::new(2, 5),
+                Position::new(2, 4),
+                Position::new(2, 3),
+                Position::new(2, 2),
+                Position::new(2, 1),
+                Position::new(1, 1),
+                Position::new(0, 1),
             ])
         );
 
@@ -494,70 +895,73 @@ mod tests {
         let maze = Maze::new(&MazeConfig {
             col_count: 9,
             row_count: 9,
+            floor_count: 1,
             max_soft_wall_count: 200,
             walls: vec![
-                Position { x: 0, y: 7 },
-                Position { x: 1, y: 7 },
-                Position { x: 1, y: 4 },
+                Position::new(0, 7),
+                Position::new(1, 7),
+                Position::new(1, 4),
             ],
-            entrypoints: vec![Position { x: 0, y: 0 }, Position { x: 0, y: 8 }],
+            entrypoints: vec![Position::new(0, 0), Position::new(0, 8)],
             checkpoints: vec![
                 Checkpoint {
-                    position: Position { x: 0, y: 6 },
+                    position: Position::new(0, 6),
                     level: 1,
                 },
                 Checkpoint {
-                    position: Position { x: 4, y: 4 },
+                    position: Position::new(4, 4),
                     level: 2,
                 },
                 Checkpoint {
-                    position: Position { x: 5, y: 0 },
+                    position: Position::new(5, 0),
                     level: 3,
                 },
                 Checkpoint {
-                    position: Position { x: 4, y: 0 },
+                    position: Position::new(4, 0),
                     level: 3,
                 },
                 Checkpoint {
-                    position: Position { x: 6, y: 0 },
+                    position: Position::new(6, 0),
                     level: 4,
                 },
                 Checkpoint {
-                    position: Position { x: 0, y: 1 },
+                    position: Position::new(0, 1),
                     level: 4,
                 },
             ],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
         })?;
 
         let runner = MazeRunner::new(&maze);
-        let result = runner.run(&vec![Position { x: 1, y: 6 }, Position { x: 1, y: 5 }])?;
+        let result = runner.run(&vec![Position::new(1, 6), Position::new(1, 5)])?;
 
         assert_eq!(result.as_ref().map(|res| res.score()), Some(20));
 
         assert_eq!(
-            result.as_ref().map(|res| res.solved_path()),
+            result.as_ref().map(|res| res.solved_path()).transpose()?,
             Some(vec![
-                Position { x: 0, y: 0 },
-                Position { x: 0, y: 1 },
-                Position { x: 0, y: 2 },
-                Position { x: 0, y: 3 },
-                Position { x: 0, y: 4 },
-                Position { x: 0, y: 5 },
-                Position { x: 0, y: 6 },
-                Position { x: 0, y: 5 },
-                Position { x: 0, y: 4 },
-                Position { x: 0, y: 3 },
-                Position { x: 1, y: 3 },
-                Position { x: 2, y: 3 },
-                Position { x: 3, y: 3 },
-                Position { x: 4, y: 3 },
-                Position { x: 4, y: 4 },
-                Position { x: 5, y: 4 },
-                Position { x: 5, y: 3 },
-                Position { x: 5, y: 2 },
-                Position { x: 5, y: 1 },
-                Position { x: 5, y: 0 },
-                Position { x: 6, y: 0 }
+                Position::new(0, 0),
+                Position::new(0, 1),
+                Position::new(0, 2),
+                Position::new(0, 3),
+                Position::new(0, 4),
+                Position::new(0, 5),
+                Position::new(0, 6),
+                Position::new(0, 5),
+                Position::new(0, 4),
+                Position::new(0, 3),
+                Position::new(1, 3),
+                Position::new(2, 3),
+                Position::new(3, 3),
+                Position::new(4, 3),
+                Position::new(4, 4),
+                Position::new(5, 4),
+                Position::new(5, 3),
+                Position::new(5, 2),
+                Position::new(5, 1),
+                Position::new(5, 0),
+                Position::new(6, 0)
             ])
         );
 
@@ -569,49 +973,52 @@ mod tests {
         let maze = Maze::new(&MazeConfig {
             col_count: 9,
             row_count: 9,
+            floor_count: 1,
             max_soft_wall_count: 200,
             walls: vec![
-                Position { x: 0, y: 7 },
-                Position { x: 1, y: 7 },
-                Position { x: 1, y: 4 },
+                Position::new(0, 7),
+                Position::new(1, 7),
+                Position::new(1, 4),
             ],
-            entrypoints: vec![Position { x: 0, y: 0 }, Position { x: 0, y: 8 }],
+            entrypoints: vec![Position::new(0, 0), Position::new(0, 8)],
             checkpoints: vec![
                 Checkpoint {
-                    position: Position { x: 0, y: 6 },
+                    position: Position::new(0, 6),
                     level: 1,
                 },
                 Checkpoint {
-                    position: Position { x: 4, y: 4 },
+                    position: Position::new(4, 4),
                     level: 2,
                 },
                 Checkpoint {
-                    position: Position { x: 5, y: 0 },
+                    position: Position::new(5, 0),
                     level: 3,
                 },
                 Checkpoint {
-                    position: Position { x: 4, y: 0 },
+                    position: Position::new(4, 0),
                     level: 3,
                 },
                 Checkpoint {
-                    position: Position { x: 6, y: 0 },
+                    position: Position::new(6, 0),
                     level: 4,
                 },
                 Checkpoint {
-                    position: Position { x: 0, y: 1 },
+                    position: Position::new(0, 1),
                     level: 4,
                 },
             ],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
         })?;
 
         let runner = MazeRunner::new(&maze);
         let result = runner.run(&vec![
-            Position { x: 1, y: 6 },
-            Position { x: 1, y: 5 },
-            Position { x: 5, y: 4 },
-            Position { x: 3, y: 4 },
-            Position { x: 4, y: 5 },
-            Position { x: 4, y: 3 },
+            Position::new(1, 6),
+            Position::new(1, 5),
+            Position::new(5, 4),
+            Position::new(3, 4),
+            Position::new(4, 5),
+            Position::new(4, 3),
         ])?;
 
         assert!(result.is_none());
@@ -625,79 +1032,82 @@ mod tests {
         let maze = Maze::new(&MazeConfig {
             col_count: 9,
             row_count: 9,
+            floor_count: 1,
             max_soft_wall_count: 200,
             walls: vec![
-                Position { x: 0, y: 7 },
-                Position { x: 1, y: 7 },
-                Position { x: 1, y: 4 },
+                Position::new(0, 7),
+                Position::new(1, 7),
+                Position::new(1, 4),
             ],
-            entrypoints: vec![Position { x: 0, y: 0 }, Position { x: 0, y: 8 }],
+            entrypoints: vec![Position::new(0, 0), Position::new(0, 8)],
             checkpoints: vec![
                 Checkpoint {
-                    position: Position { x: 0, y: 6 },
+                    position: Position::new(0, 6),
                     level: 1,
                 },
                 Checkpoint {
-                    position: Position { x: 3, y: 0 },
+                    position: Position::new(3, 0),
                     level: 2,
                 },
                 Checkpoint {
-                    position: Position { x: 4, y: 4 },
+                    position: Position::new(4, 4),
                     level: 2,
                 },
                 Checkpoint {
-                    position: Position { x: 5, y: 0 },
+                    position: Position::new(5, 0),
                     level: 3,
                 },
                 Checkpoint {
-                    position: Position { x: 4, y: 0 },
+                    position: Position::new(4, 0),
                     level: 3,
                 },
                 Checkpoint {
-                    position: Position { x: 6, y: 0 },
+                    position: Position::new(6, 0),
                     level: 4,
                 },
                 Checkpoint {
-                    position: Position { x: 0, y: 1 },
+                    position: Position::new(0, 1),
                     level: 4,
                 },
             ],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
         })?;
 
         let runner = MazeRunner::new(&maze);
         let result = runner.run(&vec![
-            Position { x: 1, y: 6 },
-            Position { x: 1, y: 5 },
-            Position { x: 5, y: 4 },
-            Position { x: 3, y: 4 },
-            Position { x: 4, y: 5 },
-            Position { x: 4, y: 3 },
+            Position::new(1, 6),
+            Position::new(1, 5),
+            Position::new(5, 4),
+            Position::new(3, 4),
+            Position::new(4, 5),
+            Position::new(4, 3),
         ])?;
 
         assert_eq!(result.as_ref().map(|res| res.score()), Some(18));
 
         assert_eq!(
-            result.as_ref().map(|res| res.solved_path()),
+            result.as_ref().map(|res| res.solved_path()).transpose()?,
             Some(vec![
-                Position { x: 0, y: 0 },
-                Position { x: 0, y: 1 },
-                Position { x: 0, y: 2 },
-                Position { x: 0, y: 3 },
-                Position { x: 0, y: 4 },
-                Position { x: 0, y: 5 },
-                Position { x: 0, y: 6 },
-                Position { x: 0, y: 5 },
-                Position { x: 0, y: 4 },
-                Position { x: 0, y: 3 },
-                Position { x: 1, y: 3 },
-                Position { x: 2, y: 3 },
-                Position { x: 3, y: 3 },
-                Position { x: 3, y: 2 },
-                Position { x: 3, y: 1 },
-                Position { x: 3, y: 0 },
-                Position { x: 4, y: 0 },
-                Position { x: 5, y: 0 },
-                Position { x: 6, y: 0 }
+                Position::new(0, 0),
+                Position::new(0, 1),
+                Position::new(0, 2),
+                Position::new(0, 3),
+                Position::new(0, 4),
+                Position::new(0, 5),
+                Position::new(0, 6),
+                Position::new(0, 5),
+                Position::new(0, 4),
+                Position::new(0, 3),
+                Position::new(1, 3),
+                Position::new(2, 3),
+                Position::new(3, 3),
+                Position::new(3, 2),
+                Position::new(3, 1),
+                Position::new(3, 0),
+                Position::new(4, 0),
+                Position::new(5, 0),
+                Position::new(6, 0)
             ])
         );
 
@@ -709,119 +1119,330 @@ mod tests {
         let maze = Maze::new(&MazeConfig {
             col_count: 210,
             row_count: 26,
+            floor_count: 1,
             max_soft_wall_count: 200,
             walls: vec![],
-            entrypoints: vec![Position { x: 0, y: 0 }],
+            entrypoints: vec![Position::new(0, 0)],
             checkpoints: vec![
                 Checkpoint {
-                    position: Position { x: 4, y: 5 },
+                    position: Position::new(4, 5),
                     level: 1,
                 },
                 Checkpoint {
-                    position: Position { x: 150, y: 20 },
+                    position: Position::new(150, 20),
                     level: 2,
                 },
                 Checkpoint {
-                    position: Position { x: 1, y: 1 },
+                    position: Position::new(1, 1),
                     level: 3,
                 },
                 Checkpoint {
-                    position: Position { x: 160, y: 20 },
+                    position: Position::new(160, 20),
                     level: 4,
                 },
                 Checkpoint {
-                    position: Position { x: 1, y: 2 },
+                    position: Position::new(1, 2),
                     level: 5,
                 },
                 Checkpoint {
-                    position: Position { x: 10, y: 25 },
+                    position: Position::new(10, 25),
                     level: 6,
                 },
                 Checkpoint {
-                    position: Position { x: 10, y: 21 },
+                    position: Position::new(10, 21),
                     level: 6,
                 },
                 Checkpoint {
-                    position: Position { x: 3, y: 3 },
+                    position: Position::new(3, 3),
                     level: 7,
                 },
                 Checkpoint {
-                    position: Position { x: 120, y: 25 },
+                    position: Position::new(120, 25),
                     level: 8,
                 },
                 Checkpoint {
-                    position: Position { x: 4, y: 4 },
+                    position: Position::new(4, 4),
                     level: 9,
                 },
                 Checkpoint {
-                    position: Position { x: 130, y: 25 },
+                    position: Position::new(130, 25),
                     level: 10,
                 },
                 Checkpoint {
-                    position: Position { x: 0, y: 1 },
+                    position: Position::new(0, 1),
                     level: 10,
                 },
                 Checkpoint {
-                    position: Position { x: 200, y: 5 },
+                    position: Position::new(200, 5),
                     level: 11,
                 },
                 Checkpoint {
-                    position: Position { x: 1, y: 21 },
+                    position: Position::new(1, 21),
                     level: 12,
                 },
                 Checkpoint {
-                    position: Position { x: 6, y: 6 },
+                    position: Position::new(6, 6),
                     level: 13,
                 },
                 Checkpoint {
-                    position: Position { x: 120, y: 24 },
+                    position: Position::new(120, 24),
                     level: 14,
                 },
                 Checkpoint {
-                    position: Position { x: 7, y: 7 },
+                    position: Position::new(7, 7),
                     level: 15,
                 },
                 Checkpoint {
-                    position: Position { x: 8, y: 19 },
+                    position: Position::new(8, 19),
                     level: 16,
                 },
                 Checkpoint {
-                    position: Position { x: 8, y: 8 },
+                    position: Position::new(8, 8),
                     level: 17,
                 },
                 Checkpoint {
-                    position: Position { x: 150, y: 19 },
+                    position: Position::new(150, 19),
                     level: 18,
                 },
                 Checkpoint {
-                    position: Position { x: 200, y: 1 },
+                    position: Position::new(200, 1),
                     level: 19,
                 },
                 Checkpoint {
-                    position: Position { x: 202, y: 1 },
+                    position: Position::new(202, 1),
                     level: 20,
                 },
                 Checkpoint {
-                    position: Position { x: 1, y: 20 },
+                    position: Position::new(1, 20),
                     level: 20,
                 },
                 Checkpoint {
-                    position: Position { x: 206, y: 1 },
+                    position: Position::new(206, 1),
                     level: 21,
                 },
             ],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
         })?;
 
         let runner = MazeRunner::new(&maze);
         let result = runner.run(&vec![
-            Position { x: 205, y: 1 },
-            Position { x: 207, y: 1 },
-            Position { x: 206, y: 0 },
-            Position { x: 205, y: 2 },
+            Position::new(205, 1),
+            Position::new(207, 1),
+            Position::new(206, 0),
+            Position::new(205, 2),
         ])?;
 
         assert_eq!(result.as_ref().map(|res| res.score()), Some(1985));
 
+        Ok(())
+    }
+    /// Two floors of 4x3 tiles, the ground floor is split by a wall and the halves
+    /// are connected only over the upper floor
+    fn get_multi_floor_maze(
+        entrypoints: Vec<Position>,
+        checkpoints: Vec<Checkpoint>,
+    ) -> Result<Maze, Box<dyn Error>> {
+        Ok(Maze::new(&MazeConfig {
+            col_count: 4,
+            row_count: 3,
+            floor_count: 2,
+            max_soft_wall_count: 2,
+            walls: vec![
+                Position::new(2, 0),
+                Position::new(2, 1),
+                Position::new(2, 2),
+            ],
+            entrypoints,
+            checkpoints,
+            stairs: vec![
+                Stairs {
+                    position: Position::new(1, 2),
+                    to_floor: 1,
+                },
+                // stairs lead both ways, so these are walked up and down too
+                Stairs {
+                    position: Position::on_floor(3, 2, 1),
+                    to_floor: 0,
+                },
+            ],
+            scoring_rule: ScoringRuleType::Min,
+        })?)
+    }
+
+    #[test]
+    fn test_run_multi_floor() -> Result<(), Box<dyn Error>> {
+        let maze = get_multi_floor_maze(
+            vec![Position::new(0, 0)],
+            vec![Checkpoint {
+                position: Position::new(3, 0),
+                level: 1,
+            }],
+        )?;
+
+        let runner = MazeRunner::new(&maze);
+        let result = runner.run(&vec![])?.ok_or("maze should be solvable")?;
+
+        assert_eq!(result.score(), 9);
+        assert_eq!(
+            result.solved_path()?,
+            vec![
+                Position::new(0, 0),
+                Position::new(1, 0),
+                Position::new(1, 1),
+                Position::new(1, 2),
+                Position::on_floor(1, 2, 1),
+                Position::on_floor(2, 2, 1),
+                Position::on_floor(3, 2, 1),
+                Position::new(3, 2),
+                Position::new(3, 1),
+                Position::new(3, 0),
+            ]
+        );
+        assert_eq!(result.shortest_path_count(), 3);
+        assert_eq!(runner.run_score(&vec![])?, Some(9));
+        assert_eq!(result.floor_count(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_multi_floor_with_soft_walls() -> Result<(), Box<dyn Error>> {
+        let maze = get_multi_floor_maze(
+            vec![Position::new(0, 0)],
+            vec![Checkpoint {
+                position: Position::new(3, 0),
+                level: 1,
+            }],
+        )?;
+        let runner = MazeRunner::new(&maze);
+
+        let get_score = |walls: Vec<Position>| -> Result<Option<u32>, MazeRunnerError> {
+            let score = runner.run(&walls)?.map(|result| result.score());
+            assert_eq!(runner.run_score(&walls)?, score);
+            Ok(score)
+        };
+
+        assert_eq!(get_score(vec![Position::on_floor(2, 2, 1)])?, Some(11));
+        assert_eq!(
+            get_score(vec![
+                Position::on_floor(2, 2, 1),
+                Position::on_floor(1, 1, 1)
+            ])?,
+            Some(15)
+        );
+        // walling the landing of the stairs cuts the exit off
+        assert_eq!(get_score(vec![Position::new(3, 2)])?, None);
+
+        // the limit is shared by all floors
+        assert!(matches!(
+            runner.run(&vec![
+                Position::on_floor(2, 2, 1),
+                Position::on_floor(1, 1, 1),
+                Position::new(0, 1)
+            ]),
+            Err(MazeRunnerError::TooManySoftWalls { limit: 2 })
+        ));
+        assert!(matches!(
+            runner.run(&vec![Position::on_floor(0, 0, 2)]),
+            Err(MazeRunnerError::WallOutOfBounds { .. })
+        ));
+        assert!(matches!(
+            runner.run(&vec![Position::new(1, 2)]),
+            Err(MazeRunnerError::OverlappingWall { .. })
+        ));
+        assert!(matches!(
+            runner.run(&vec![Position::new(1, 1), Position::on_floor(1, 1, 0)]),
+            Err(MazeRunnerError::OverlappingWall { .. })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_multi_floor_leveled() -> Result<(), Box<dyn Error>> {
+        let maze = get_multi_floor_maze(
+            vec![Position::new(0, 0)],
+            vec![
+                Checkpoint {
+                    position: Position::on_floor(0, 0, 1),
+                    level: 1,
+                },
+                Checkpoint {
+                    position: Position::new(3, 0),
+                    level: 2,
+                },
+            ],
+        )?;
+
+        let runner = MazeRunner::new(&maze);
+        let result = runner.run(&vec![])?.ok_or("maze should be solvable")?;
+
+        assert_eq!(result.score(), 15);
+        assert_eq!(
+            result.segments()?,
+            vec![
+                PathSegment {
+                    level: 1,
+                    start: Position::new(0, 0),
+                    checkpoint: Position::on_floor(0, 0, 1),
+                    length: 7
+                },
+                PathSegment {
+                    level: 2,
+                    start: Position::on_floor(0, 0, 1),
+                    checkpoint: Position::new(3, 0),
+                    length: 8
+                }
+            ]
+        );
+
+        let floors = result.distance_field(1).ok_or("level should exist")?;
+        assert_eq!(floors.len(), 2);
+        assert_eq!(floors[1][1][2], Some(4));
+        assert_eq!(floors[1][0][0], Some(7));
+        assert_eq!(floors[0][1][2], Some(3));
+        assert_eq!(
+            result.distance_field(2).map(|floors| floors[1][3][2]),
+            Some(Some(12))
+        );
+        assert_eq!(result.start_distance_field().len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_multi_floor_entrypoints() -> Result<(), Box<dyn Error>> {
+        let maze = get_multi_floor_maze(
+            vec![Position::new(0, 0), Position::on_floor(3, 0, 1)],
+            vec![Checkpoint {
+                position: Position::new(3, 0),
+                level: 1,
+            }],
+        )?;
+
+        let runner = MazeRunner::new(&maze);
+        let scores: Vec<Option<u32>> = runner
+            .run_entrypoints(&vec![])?
+            .into_iter()
+            .map(|run| run.result.map(|result| result.score()))
+            .collect();
+        assert_eq!(scores, vec![Some(9), Some(5)]);
+
+        let result = runner.run(&vec![])?.ok_or("maze should be solvable")?;
+        assert_eq!(result.entrypoint(), &Position::on_floor(3, 0, 1));
+        assert_eq!(
+            result.solved_path()?,
+            vec![
+                Position::on_floor(3, 0, 1),
+                Position::on_floor(3, 1, 1),
+                Position::on_floor(3, 2, 1),
+                Position::new(3, 2),
+                Position::new(3, 1),
+                Position::new(3, 0),
+            ]
+        );
+
         Ok(())
     }
 }
@@ -9,4 +9,6 @@ pub enum MazeRunnerError {
     WallOutOfBounds { position: Position },
     #[error("Overlapping wall at position {position}")]
     OverlappingWall { position: Position },
+    #[error("Failed to reconstruct solved path at position {position}")]
+    PathReconstructionFailed { position: Position },
 }
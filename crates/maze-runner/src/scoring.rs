@@ -0,0 +1,309 @@
+use std::cmp::Reverse;
+
+use maze_core::Position;
+
+use crate::EntrypointRun;
+
+// rule types are part of the maze config, they are re-exported so the rules can be created from them
+pub use maze_core::ScoringRuleType;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EntrypointScore {
+    pub entrypoint: Position,
+    /// `None` if the exit can't be reached from the entrypoint
+    pub score: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoreBreakdown {
+    /// Final score of the maze, `None` if the rule considers the maze unsolved
+    pub score: Option<u32>,
+    /// Scores of separate entrypoints in the order of maze entrypoints
+    pub entrypoint_scores: Vec<EntrypointScore>,
+}
+
+/// Rules are shared between threads when the runner evaluates batches in parallel
+pub trait ScoringRule: Send + Sync {
+    /// Combines scores of separate entrypoints into the final score of the maze
+    fn combine(&self, entrypoint_scores: &[EntrypointScore]) -> Option<u32>;
+
+    /// Entrypoint whose run stands for the whole maze in `MazeRunner::run`, the first of the shortest solved runs by default
+    fn representative(&self, entrypoint_scores: &[EntrypointScore]) -> Option<usize> {
+        entrypoint_scores
+            .iter()
+            .enumerate()
+            .filter_map(|(index, score)| score.score.map(|score| (score, index)))
+            .min()
+            .map(|(_, index)| index)
+    }
+
+    /// Best score the rule can combine from entrypoints scoring at most their bounds, `None` bounds are never solved.
+    /// The highest bound holds for every rule that never scores over its best entrypoint.
+    fn upper_bound(&self, entrypoint_bounds: &[EntrypointScore]) -> u32 {
+        get_solved_scores(entrypoint_bounds).max().unwrap_or(0)
+    }
+
+    /// Rules scoring only the shortest of all runs let the runner search from all entrypoints at once
+    fn is_lowest_score(&self) -> bool {
+        false
+    }
+
+    fn score(&self, runs: &[EntrypointRun]) -> ScoreBreakdown {
+        let entrypoint_scores: Vec<EntrypointScore> = runs
+            .iter()
+            .map(|run| EntrypointScore {
+                entrypoint: run.entrypoint,
+                score: run.result.as_ref().map(|result| result.score()),
+            })
+            .collect();
+
+        ScoreBreakdown {
+            score: self.combine(&entrypoint_scores),
+            entrypoint_scores,
+        }
+    }
+}
+
+pub fn create_scoring_rule(rule_type: ScoringRuleType) -> Box<dyn ScoringRule> {
+    match rule_type {
+        ScoringRuleType::Min => Box::new(MinScoringRule),
+        ScoringRuleType::Max => Box::new(MaxScoringRule),
+        ScoringRuleType::Sum => Box::new(SumScoringRule),
+        ScoringRuleType::Average => Box::new(AverageScoringRule),
+        ScoringRuleType::AllEntrypoints => Box::new(AllEntrypointsScoringRule),
+    }
+}
+
+struct MinScoringRule;
+
+impl ScoringRule for MinScoringRule {
+    fn combine(&self, entrypoint_scores: &[EntrypointScore]) -> Option<u32> {
+        get_solved_scores(entrypoint_scores).min()
+    }
+
+    fn is_lowest_score(&self) -> bool {
+        true
+    }
+}
+
+struct MaxScoringRule;
+
+impl ScoringRule for MaxScoringRule {
+    fn combine(&self, entrypoint_scores: &[EntrypointScore]) -> Option<u32> {
+        get_solved_scores(entrypoint_scores).max()
+    }
+
+    fn representative(&self, entrypoint_scores: &[EntrypointScore]) -> Option<usize> {
+        entrypoint_scores
+            .iter()
+            .enumerate()
+            .filter_map(|(index, score)| score.score.map(|score| (score, Reverse(index))))
+            .max()
+            .map(|(_, Reverse(index))| index)
+    }
+}
+
+struct SumScoringRule;
+
+impl ScoringRule for SumScoringRule {
+    fn combine(&self, entrypoint_scores: &[EntrypointScore]) -> Option<u32> {
+        get_solved_scores(entrypoint_scores).fold(None, |sum, score| {
+            Some(sum.unwrap_or(0u32).saturating_add(score))
+        })
+    }
+
+    fn upper_bound(&self, entrypoint_bounds: &[EntrypointScore]) -> u32 {
+        self.combine(entrypoint_bounds).unwrap_or(0)
+    }
+}
+
+struct AverageScoringRule;
+
+impl ScoringRule for AverageScoringRule {
+    fn combine(&self, entrypoint_scores: &[EntrypointScore]) -> Option<u32> {
+        let scores: Vec<u64> = get_solved_scores(entrypoint_scores)
+            .map(u64::from)
+            .collect();
+        if scores.is_empty() {
+            return None;
+        }
+
+        Some((scores.iter().sum::<u64>() / scores.len() as u64) as u32)
+    }
+}
+
+struct AllEntrypointsScoringRule;
+
+impl ScoringRule for AllEntrypointsScoringRule {
+    fn combine(&self, entrypoint_scores: &[EntrypointScore]) -> Option<u32> {
+        if entrypoint_scores.iter().any(|score| score.score.is_none()) {
+            return None;
+        }
+
+        get_solved_scores(entrypoint_scores).min()
+    }
+
+    fn upper_bound(&self, entrypoint_bounds: &[EntrypointScore]) -> u32 {
+        self.combine(entrypoint_bounds).unwrap_or(0)
+    }
+}
+
+fn get_solved_scores(entrypoint_scores: &[EntrypointScore]) -> impl Iterator<Item = u32> + '_ {
+    entrypoint_scores.iter().filter_map(|score| score.score)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use maze_core::{Checkpoint, Maze, MazeConfig};
+
+    use crate::MazeRunner;
+
+    use super::*;
+
+    fn get_scores(scores: &[Option<u32>]) -> Vec<EntrypointScore> {
+        scores
+            .iter()
+            .enumerate()
+            .map(|(x, &score)| EntrypointScore {
+                entrypoint: Position::new(x, 0),
+                score,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_built_in_rules() {
+        let scores = get_scores(&[Some(4), None, Some(9), Some(6)]);
+        let combine = |rule_type| create_scoring_rule(rule_type).combine(&scores);
+
+        assert_eq!(combine(ScoringRuleType::Min), Some(4));
+        assert_eq!(combine(ScoringRuleType::Max), Some(9));
+        assert_eq!(combine(ScoringRuleType::Sum), Some(19));
+        assert_eq!(combine(ScoringRuleType::Average), Some(6));
+        assert_eq!(combine(ScoringRuleType::AllEntrypoints), None);
+
+        let scores = get_scores(&[Some(4), Some(9)]);
+        assert_eq!(
+            create_scoring_rule(ScoringRuleType::AllEntrypoints).combine(&scores),
+            Some(4)
+        );
+    }
+
+    #[test]
+    fn test_built_in_rules_unsolved() {
+        let scores = get_scores(&[None, None]);
+
+        assert_eq!(
+            create_scoring_rule(ScoringRuleType::Min).combine(&scores),
+            None
+        );
+        assert_eq!(
+            create_scoring_rule(ScoringRuleType::Max).combine(&scores),
+            None
+        );
+        assert_eq!(
+            create_scoring_rule(ScoringRuleType::Sum).combine(&scores),
+            None
+        );
+        assert_eq!(
+            create_scoring_rule(ScoringRuleType::Average).combine(&scores),
+            None
+        );
+        assert_eq!(
+            create_scoring_rule(ScoringRuleType::AllEntrypoints).combine(&scores),
+            None
+        );
+    }
+
+    #[test]
+    fn test_runner_score_breakdown() -> Result<(), Box<dyn Error>> {
+        let maze = Maze::new(&MazeConfig {
+            col_count: 5,
+            row_count: 3,
+            floor_count: 1,
+            max_soft_wall_count: 3,
+            walls: vec![],
+            entrypoints: vec![Position::new(0, 0), Position::new(0, 2)],
+            checkpoints: vec![Checkpoint {
+                position: Position::new(4, 0),
+                level: 1,
+            }],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
+        })?;
+
+        let walls = vec![Position::new(0, 1), Position::new(1, 1)];
+
+        let breakdown = MazeRunner::new(&maze).score(&walls)?;
+        assert_eq!(breakdown.score, Some(4));
+        assert_eq!(
+            breakdown.entrypoint_scores,
+            vec![
+                EntrypointScore {
+                    entrypoint: Position::new(0, 0),
+                    score: Some(4),
+                },
+                EntrypointScore {
+                    entrypoint: Position::new(0, 2),
+                    score: Some(6),
+                },
+            ]
+        );
+
+        let runner =
+            MazeRunner::with_scoring_rule(&maze, create_scoring_rule(ScoringRuleType::Sum));
+        assert_eq!(runner.score(&walls)?.score, Some(10));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_runner_honors_scoring_rule_of_maze() -> Result<(), Box<dyn Error>> {
+        let get_maze = |scoring_rule| {
+            Maze::new(&MazeConfig {
+                col_count: 5,
+                row_count: 3,
+                floor_count: 1,
+                max_soft_wall_count: 3,
+                walls: vec![],
+                entrypoints: vec![Position::new(0, 0), Position::new(0, 2)],
+                checkpoints: vec![Checkpoint {
+                    position: Position::new(4, 0),
+                    level: 1,
+                }],
+                stairs: vec![],
+                scoring_rule,
+            })
+        };
+        let walls = vec![Position::new(0, 1), Position::new(1, 1)];
+
+        for (rule_type, score, entrypoint) in [
+            (ScoringRuleType::Min, 4, Position::new(0, 0)),
+            (ScoringRuleType::Max, 6, Position::new(0, 2)),
+            (ScoringRuleType::Sum, 10, Position::new(0, 0)),
+            (ScoringRuleType::AllEntrypoints, 4, Position::new(0, 0)),
+        ] {
+            let maze = get_maze(rule_type)?;
+            let runner = MazeRunner::new(&maze);
+            let run = runner.run(&walls)?.ok_or("maze should be solved")?;
+
+            assert_eq!(run.score(), score);
+            assert_eq!(*run.entrypoint(), entrypoint);
+            assert_eq!(run.distance() as usize, run.solved_path()?.len() - 1);
+            assert_eq!(runner.run_score(&walls)?, Some(score));
+            assert_eq!(runner.score(&walls)?.score, Some(score));
+        }
+
+        // walling off the second entrypoint leaves the maze unsolved for the rule
+        let maze = get_maze(ScoringRuleType::AllEntrypoints)?;
+        let runner = MazeRunner::new(&maze);
+        let walls = vec![Position::new(0, 1), Position::new(1, 2)];
+        assert!(runner.run(&walls)?.is_none());
+        assert_eq!(runner.run_score(&walls)?, None);
+        assert_eq!(runner.score(&walls)?.score, None);
+
+        Ok(())
+    }
+}
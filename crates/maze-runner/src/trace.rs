@@ -0,0 +1,262 @@
+use std::{collections::VecDeque, slice::Iter};
+
+use maze_core::{Position, TileFloors};
+
+use super::run::{Search, SearchStatus};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SearchEvent {
+    /// Search from the entrypoint started, all following events belong to it until it is solved or unsolvable
+    Started {
+        entrypoint: Position,
+    },
+    /// Node was taken from the front of the queue and its neighbors are going to be enqueued
+    NodeExpanded {
+        position: Position,
+        distance: u32,
+        level: i32,
+    },
+    /// Node was pushed to the back of the queue while searching for the checkpoint of the level
+    NodeEnqueued {
+        position: Position,
+        distance: u32,
+        level: i32,
+    },
+    /// Checkpoint of the level was entered, search continues from it with the next level
+    LevelAdvanced {
+        position: Position,
+        distance: u32,
+        level: i32,
+    },
+    Solved {
+        entrypoint: Position,
+        score: u32,
+    },
+    Unsolvable {
+        entrypoint: Position,
+    },
+}
+
+pub(crate) trait SearchObserver {
+    fn observe(&mut self, event: SearchEvent);
+}
+
+impl SearchObserver for () {
+    #[inline(always)]
+    fn observe(&mut self, _: SearchEvent) {}
+}
+
+impl SearchObserver for VecDeque<SearchEvent> {
+    fn observe(&mut self, event: SearchEvent) {
+        self.push_back(event);
+    }
+}
+
+/// Iterator over search events of all entrypoints, the search advances only as far as the consumed events need,
+/// so the search from the next entrypoint starts only after events of the previous one are consumed
+pub struct MazeTrace<'a> {
+    floors: TileFloors,
+    ascending_checkpoint_levels: &'a [i32],
+    entrypoints: Iter<'a, Position>,
+    search: Option<Search<'a>>,
+    // events of the last search step, a step expands a single node
+    events: VecDeque<SearchEvent>,
+}
+
+impl<'a> MazeTrace<'a> {
+    pub(crate) fn new(
+        floors: TileFloors,
+        ascending_checkpoint_levels: &'a [i32],
+        entrypoints: &'a [Position],
+    ) -> Self {
+        Self {
+            floors,
+            ascending_checkpoint_levels,
+            entrypoints: entrypoints.iter(),
+            search: None,
+            events: VecDeque::new(),
+        }
+    }
+}
+
+impl Iterator for MazeTrace<'_> {
+    type Item = SearchEvent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.events.is_empty() {
+            match self.search.as_mut() {
+                Some(search) => {
+                    if search.step(&mut self.events) != SearchStatus::Running {
+                        self.search = None;
+                    }
+                }
+                None => {
+                    let entrypoint = self.entrypoints.next()?;
+                    self.search = Search::start(
+                        &self.floors,
+                        self.ascending_checkpoint_levels,
+                        entrypoint,
+                        &mut self.events,
+                    );
+                }
+            }
+        }
+
+        self.events.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use maze_core::{Checkpoint, Maze, MazeConfig, ScoringRuleType};
+
+    use crate::MazeRunner;
+
+    use super::*;
+
+    #[test]
+    fn test_trace_corridor() -> Result<(), Box<dyn Error>> {
+        let maze = Maze::new(&MazeConfig {
+            col_count: 4,
+            row_count: 1,
+            floor_count: 1,
+            max_soft_wall_count: 2,
+            walls: vec![],
+            entrypoints: vec![Position::new(0, 0)],
+            checkpoints: vec![Checkpoint {
+                position: Position::new(3, 0),
+                level: 1,
+            }],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
+        })?;
+
+        let runner = MazeRunner::new(&maze);
+        let events: Vec<SearchEvent> = runner.trace(&vec![])?.collect();
+
+        assert_eq!(
+            events,
+            vec![
+                SearchEvent::Started {
+                    entrypoint: Position::new(0, 0)
+                },
+                SearchEvent::NodeExpanded {
+                    position: Position::new(0, 0),
+                    distance: 0,
+                    level: 1
+                },
+                SearchEvent::NodeEnqueued {
+                    position: Position::new(1, 0),
+                    distance: 1,
+                    level: 1
+                },
+                SearchEvent::NodeExpanded {
+                    position: Position::new(1, 0),
+                    distance: 1,
+                    level: 1
+                },
+                SearchEvent::NodeEnqueued {
+                    position: Position::new(2, 0),
+                    distance: 2,
+                    level: 1
+                },
+                SearchEvent::NodeExpanded {
+                    position: Position::new(2, 0),
+                    distance: 2,
+                    level: 1
+                },
+                SearchEvent::LevelAdvanced {
+                    position: Position::new(3, 0),
+                    distance: 3,
+                    level: 1
+                },
+                SearchEvent::NodeEnqueued {
+                    position: Position::new(3, 0),
+                    distance: 3,
+                    level: 1
+                },
+                SearchEvent::Solved {
+                    entrypoint: Position::new(0, 0),
+                    score: 3
+                },
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_trace_matches_run_for_every_entrypoint() -> Result<(), Box<dyn Error>> {
+        let maze = Maze::new(&MazeConfig {
+            col_count: 9,
+            row_count: 9,
+            floor_count: 1,
+            max_soft_wall_count: 200,
+            walls: vec![
+                Position::new(0, 7),
+                Position::new(1, 7),
+                Position::new(1, 4),
+            ],
+            entrypoints: vec![Position::new(0, 0), Position::new(0, 8)],
+            checkpoints: vec![
+                Checkpoint {
+                    position: Position::new(0, 6),
+                    level: 1,
+                },
+                Checkpoint {
+                    position: Position::new(4, 4),
+                    level: 2,
+                },
+                Checkpoint {
+                    position: Position::new(6, 0),
+                    level: 3,
+                },
+            ],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
+        })?;
+
+        let walls = vec![
+            Position::new(1, 6),
+            Position::new(1, 5),
+            Position::new(1, 8),
+        ];
+        let runner = MazeRunner::new(&maze);
+
+        let outcomes: Vec<SearchEvent> = runner
+            .trace(&walls)?
+            .filter(|event| {
+                matches!(
+                    event,
+                    SearchEvent::Solved { .. } | SearchEvent::Unsolvable { .. }
+                )
+            })
+            .collect();
+
+        let expected: Vec<SearchEvent> = runner
+            .run_entrypoints(&walls)?
+            .into_iter()
+            .map(|run| match run.result {
+                Some(result) => SearchEvent::Solved {
+                    entrypoint: run.entrypoint,
+                    score: result.score(),
+                },
+                None => SearchEvent::Unsolvable {
+                    entrypoint: run.entrypoint,
+                },
+            })
+            .collect();
+
+        assert_eq!(outcomes, expected);
+        assert_eq!(
+            outcomes[1],
+            SearchEvent::Unsolvable {
+                entrypoint: Position::new(0, 8)
+            }
+        );
+
+        Ok(())
+    }
+}
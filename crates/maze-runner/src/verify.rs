@@ -0,0 +1,512 @@
+use std::collections::HashSet;
+
+use maze_core::{Maze, Position, TileFloors, TileKind};
+use thiserror::Error;
+
+use crate::{
+    nodes::Nodes,
+    runner::{create_board_with_soft_walls, get_ascending_checkpoint_levels},
+    runner_error::MazeRunnerError,
+    scoring::EntrypointScore,
+    MazeRunner,
+};
+
+/// Score and path submitted by the client for the soft wall placement
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClaimedRun {
+    pub score: u32,
+    pub path: Vec<Position>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    Accepted,
+    Rejected(Discrepancy),
+}
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum Discrepancy {
+    #[error("Too many soft walls submitted ({count}), limit is {limit}")]
+    TooManySoftWalls { limit: u32, count: usize },
+    #[error("Wall out of bound at position {position}")]
+    WallOutOfBounds { position: Position },
+    #[error("Duplicate wall at position {position}")]
+    DuplicateWall { position: Position },
+    #[error("Overlapping wall at position {position}")]
+    OverlappingWall { position: Position },
+    #[error("Maze is not solvable with the submitted walls")]
+    Unsolvable,
+    #[error("Claimed score {claimed} differs from the actual score {actual}")]
+    ScoreMismatch { claimed: u32, actual: u32 },
+    #[error("Claimed path is empty")]
+    EmptyPath,
+    #[error("Claimed path starts at {position} which is not an entrypoint")]
+    PathNotFromEntrypoint { position: Position },
+    #[error("Claimed path leaves the maze at position {position}")]
+    PathOutOfBounds { position: Position },
+    #[error("Claimed path enters wall at position {position}")]
+    PathEntersWall { position: Position },
+    #[error("Claimed path jumps from {from} to {to}")]
+    IllegalStep { from: Position, to: Position },
+    #[error("Claimed path continues after the exit at position {position}")]
+    PathContinuesAfterExit { position: Position },
+    #[error("Claimed path ends before reaching checkpoint of level {level}")]
+    PathIncomplete { level: i32 },
+    #[error("Claimed path has {length} steps but the shortest path of the scored entrypoint has {expected}")]
+    PathLengthMismatch { expected: u32, length: usize },
+    #[error("Claimed path is not the shortest path from entrypoint {entrypoint}")]
+    PathNotShortest { entrypoint: Position },
+}
+
+/// Verifies a submitted run against the maze.
+///
+/// Soft walls are checked first, then the score is recomputed by the runner with the scoring rule
+/// of the maze and compared to the claimed one and finally the claimed path is walked tile by tile,
+/// it has to be a shortest path of the entrypoint the rule scores the maze by. The verdict carries
+/// the first discrepancy found.
+pub fn verify(
+    maze: &Maze,
+    walls: &[Position],
+    claimed: &ClaimedRun,
+) -> Result<Verdict, MazeRunnerError> {
+    if let Some(discrepancy) = find_wall_discrepancy(maze, walls) {
+        return Ok(Verdict::Rejected(discrepancy));
+    }
+
+    let walls = walls.to_vec();
+    let runner = MazeRunner::new(maze);
+    let breakdown = runner.score(&walls)?;
+    let representative = runner
+        .scoring_rule()
+        .representative(&breakdown.entrypoint_scores)
+        .and_then(|index| breakdown.entrypoint_scores[index].score);
+    let (actual, expected_length) = match breakdown.score.zip(representative) {
+        Some(scores) => scores,
+        None => return Ok(Verdict::Rejected(Discrepancy::Unsolvable)),
+    };
+    if claimed.score != actual {
+        return Ok(Verdict::Rejected(Discrepancy::ScoreMismatch {
+            claimed: claimed.score,
+            actual,
+        }));
+    }
+
+    let floors = create_board_with_soft_walls(maze, &walls)?;
+    if let Some(discrepancy) = find_path_discrepancy(
+        &floors,
+        claimed,
+        &breakdown.entrypoint_scores,
+        expected_length,
+    ) {
+        return Ok(Verdict::Rejected(discrepancy));
+    }
+
+    Ok(Verdict::Accepted)
+}
+
+fn find_wall_discrepancy(maze: &Maze, walls: &[Position]) -> Option<Discrepancy> {
+    let limit = maze.max_soft_wall_count();
+    if walls.len() as u32 > limit {
+        return Some(Discrepancy::TooManySoftWalls {
+            limit,
+            count: walls.len(),
+        });
+    }
+
+    let mut placed = HashSet::new();
+    for &position in walls {
+        match get_tile(maze.floors(), &position) {
+            None => return Some(Discrepancy::WallOutOfBounds { position }),
+            Some(_) if !placed.insert(position) => {
+                return Some(Discrepancy::DuplicateWall { position })
+            }
+            Some(TileKind::Empty) => (),
+            Some(_) => return Some(Discrepancy::OverlappingWall { position }),
+        }
+    }
+
+    None
+}
+
+/// Walks the path the same way the runner does, a checkpoint of the current level advances to the next level
+fn find_path_discrepancy(
+    floors: &TileFloors,
+    claimed: &ClaimedRun,
+    entrypoint_scores: &[EntrypointScore],
+    expected_length: u32,
+) -> Option<Discrepancy> {
+    let levels = get_ascending_checkpoint_levels(floors);
+    let nodes = Nodes::new(floors);
+    let mut level_index = 0;
+
+    let start = match claimed.path.first() {
+        Some(start) => *start,
+        None => return Some(Discrepancy::EmptyPath),
+    };
+    if get_tile(floors, &start) != Some(TileKind::Entrypoint) {
+        return Some(Discrepancy::PathNotFromEntrypoint { position: start });
+    }
+
+    for step in claimed.path.windows(2) {
+        let (from, to) = (step[0], step[1]);
+        if level_index >= levels.len() {
+            return Some(Discrepancy::PathContinuesAfterExit { position: to });
+        }
+
+        let tile = match get_tile(floors, &to) {
+            Some(tile) => tile,
+            None => return Some(Discrepancy::PathOutOfBounds { position: to }),
+        };
+        // steps are either to a side on the same floor or over stairs to another floor
+        if !nodes.get_neighbors_positions(&from).contains(&to) {
+            return Some(Discrepancy::IllegalStep { from, to });
+        }
+        if tile == TileKind::Wall {
+            return Some(Discrepancy::PathEntersWall { position: to });
+        }
+
+        if matches!(tile, TileKind::Checkpoint { level } if level == levels[level_index]) {
+            level_index += 1;
+        }
+    }
+
+    if let Some(&level) = levels.get(level_index) {
+        return Some(Discrepancy::PathIncomplete { level });
+    }
+
+    let length = claimed.path.len() - 1;
+    if length != expected_length as usize {
+        return Some(Discrepancy::PathLengthMismatch {
+            expected: expected_length,
+            length,
+        });
+    }
+
+    // a walk as long as the scored run from an entrypoint closer to the exit is not its shortest path
+    let start_score = entrypoint_scores
+        .iter()
+        .find(|score| score.entrypoint == start)
+        .and_then(|score| score.score);
+    if start_score != Some(expected_length) {
+        return Some(Discrepancy::PathNotShortest { entrypoint: start });
+    }
+
+    None
+}
+
+fn get_tile(floors: &TileFloors, position: &Position) -> Option<TileKind> {
+    floors
+        .get(position.floor_index())
+        .and_then(|tiles| tiles.get(position.x))
+        .and_then(|column| column.get(position.y))
+        .copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use maze_core::{Checkpoint, MazeConfig, ScoringRuleType, Stairs};
+
+    use super::*;
+
+    fn get_maze() -> Result<Maze, Box<dyn Error>> {
+        Ok(Maze::new(&MazeConfig {
+            col_count: 4,
+            row_count: 3,
+            floor_count: 1,
+            max_soft_wall_count: 2,
+            walls: vec![Position::new(1, 2)],
+            entrypoints: vec![Position::new(0, 0)],
+            checkpoints: vec![
+                Checkpoint {
+                    position: Position::new(3, 0),
+                    level: 1,
+                },
+                Checkpoint {
+                    position: Position::new(3, 2),
+                    level: 2,
+                },
+            ],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Min,
+        })?)
+    }
+
+    fn get_claimed_run(maze: &Maze, walls: &[Position]) -> Result<ClaimedRun, Box<dyn Error>> {
+        let run = MazeRunner::new(maze)
+            .run(&walls.to_vec())?
+            .ok_or("maze should be solvable")?;
+
+        Ok(ClaimedRun {
+            score: run.score(),
+            path: run.solved_path()?,
+        })
+    }
+
+    #[test]
+    fn test_verify_accepts_runner_result() -> Result<(), Box<dyn Error>> {
+        let maze = get_maze()?;
+        let walls = [Position::new(1, 0), Position::new(2, 0)];
+        let claimed = get_claimed_run(&maze, &walls)?;
+
+        assert_eq!(claimed.score, 7);
+        assert_eq!(verify(&maze, &walls, &claimed)?, Verdict::Accepted);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_rejects_illegal_walls() -> Result<(), Box<dyn Error>> {
+        let maze = get_maze()?;
+        let claimed = get_claimed_run(&maze, &[])?;
+
+        let cases = [
+            (
+                vec![
+                    Position::new(1, 0),
+                    Position::new(2, 0),
+                    Position::new(2, 1),
+                ],
+                Discrepancy::TooManySoftWalls { limit: 2, count: 3 },
+            ),
+            (
+                vec![Position::new(4, 0)],
+                Discrepancy::WallOutOfBounds {
+                    position: Position::new(4, 0),
+                },
+            ),
+            (
+                vec![Position::new(2, 0), Position::new(2, 0)],
+                Discrepancy::DuplicateWall {
+                    position: Position::new(2, 0),
+                },
+            ),
+            (
+                vec![Position::new(1, 2)],
+                Discrepancy::OverlappingWall {
+                    position: Position::new(1, 2),
+                },
+            ),
+            (
+                vec![Position::new(3, 1), Position::new(2, 2)],
+                Discrepancy::Unsolvable,
+            ),
+        ];
+
+        for (walls, discrepancy) in cases.iter() {
+            assert_eq!(
+                verify(&maze, walls, &claimed)?,
+                Verdict::Rejected(discrepancy.clone())
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_rejects_wrong_score() -> Result<(), Box<dyn Error>> {
+        let maze = get_maze()?;
+        let mut claimed = get_claimed_run(&maze, &[])?;
+        claimed.score += 2;
+
+        assert_eq!(
+            verify(&maze, &[], &claimed)?,
+            Verdict::Rejected(Discrepancy::ScoreMismatch {
+                claimed: 7,
+                actual: 5
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_rejects_illegal_paths() -> Result<(), Box<dyn Error>> {
+        let maze = get_maze()?;
+        let walls = [Position::new(2, 1)];
+        let claimed = get_claimed_run(&maze, &walls)?;
+        let path = |positions: &[(usize, usize)]| ClaimedRun {
+            score: claimed.score,
+            path: positions
+                .iter()
+                .map(|&(x, y)| Position::new(x, y))
+                .collect(),
+        };
+
+        let cases = [
+            (path(&[]), Discrepancy::EmptyPath),
+            (
+                path(&[(1, 0), (2, 0)]),
+                Discrepancy::PathNotFromEntrypoint {
+                    position: Position::new(1, 0),
+                },
+            ),
+            (
+                path(&[(0, 0), (1, 0), (1, 1), (2, 1)]),
+                Discrepancy::PathEntersWall {
+                    position: Position::new(2, 1),
+                },
+            ),
+            (
+                path(&[(0, 0), (1, 0), (3, 0)]),
+                Discrepancy::IllegalStep {
+                    from: Position::new(1, 0),
+                    to: Position::new(3, 0),
+                },
+            ),
+            (
+                path(&[(0, 0), (0, 1), (0, 2), (0, 3)]),
+                Discrepancy::PathOutOfBounds {
+                    position: Position::new(0, 3),
+                },
+            ),
+            (
+                path(&[(0, 0), (1, 0), (2, 0)]),
+                Discrepancy::PathIncomplete { level: 1 },
+            ),
+            (
+                path(&[(0, 0), (1, 0), (2, 0), (3, 0), (3, 1), (3, 2), (3, 1)]),
+                Discrepancy::PathContinuesAfterExit {
+                    position: Position::new(3, 1),
+                },
+            ),
+            (
+                path(&[
+                    (0, 0),
+                    (0, 1),
+                    (1, 1),
+                    (1, 0),
+                    (2, 0),
+                    (3, 0),
+                    (3, 1),
+                    (3, 2),
+                ]),
+                Discrepancy::PathLengthMismatch {
+                    expected: 5,
+                    length: 7,
+                },
+            ),
+        ];
+
+        for (claimed, discrepancy) in cases.iter() {
+            assert_eq!(
+                verify(&maze, &walls, claimed)?,
+                Verdict::Rejected(discrepancy.clone())
+            );
+        }
+
+        // checkpoint of the higher level visited first doesn't count towards its level
+        let claimed = ClaimedRun {
+            score: 5,
+            path: vec![
+                Position::new(0, 0),
+                Position::new(1, 0),
+                Position::new(1, 1),
+                Position::new(2, 1),
+                Position::new(2, 2),
+                Position::new(3, 2),
+                Position::new(3, 1),
+                Position::new(3, 0),
+            ],
+        };
+        assert_eq!(
+            verify(&maze, &[], &claimed)?,
+            Verdict::Rejected(Discrepancy::PathIncomplete { level: 2 })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_multi_floor_path() -> Result<(), Box<dyn Error>> {
+        let maze = Maze::new(&MazeConfig {
+            col_count: 5,
+            row_count: 1,
+            floor_count: 2,
+            max_soft_wall_count: 1,
+            walls: vec![Position::new(2, 0)],
+            entrypoints: vec![Position::new(0, 0)],
+            checkpoints: vec![Checkpoint {
+                position: Position::new(4, 0),
+                level: 1,
+            }],
+            stairs: vec![
+                Stairs {
+                    position: Position::new(1, 0),
+                    to_floor: 1,
+                },
+                Stairs {
+                    position: Position::new(3, 0),
+                    to_floor: 1,
+                },
+            ],
+            scoring_rule: ScoringRuleType::Min,
+        })?;
+        let claimed = get_claimed_run(&maze, &[])?;
+
+        assert_eq!(claimed.score, 6);
+        assert_eq!(verify(&maze, &[], &claimed)?, Verdict::Accepted);
+
+        // floors may be changed only over stairs
+        let jumped = ClaimedRun {
+            score: 6,
+            path: vec![
+                Position::new(0, 0),
+                Position::on_floor(0, 0, 1),
+                Position::on_floor(1, 0, 1),
+                Position::on_floor(2, 0, 1),
+                Position::on_floor(3, 0, 1),
+                Position::new(3, 0),
+                Position::new(4, 0),
+            ],
+        };
+        assert_eq!(
+            verify(&maze, &[], &jumped)?,
+            Verdict::Rejected(Discrepancy::IllegalStep {
+                from: Position::new(0, 0),
+                to: Position::on_floor(0, 0, 1),
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_with_scoring_rule_of_maze() -> Result<(), Box<dyn Error>> {
+        let maze = Maze::new(&MazeConfig {
+            col_count: 5,
+            row_count: 3,
+            floor_count: 1,
+            max_soft_wall_count: 2,
+            walls: vec![],
+            entrypoints: vec![Position::new(0, 0), Position::new(0, 2)],
+            checkpoints: vec![Checkpoint {
+                position: Position::new(4, 0),
+                level: 1,
+            }],
+            stairs: vec![],
+            scoring_rule: ScoringRuleType::Max,
+        })?;
+
+        let claimed = get_claimed_run(&maze, &[])?;
+        assert_eq!(claimed.score, 6);
+        assert_eq!(claimed.path[0], Position::new(0, 2));
+        assert_eq!(verify(&maze, &[], &claimed)?, Verdict::Accepted);
+
+        // detour of the same length from the closer entrypoint
+        let detour = [(0, 0), (0, 1), (1, 1), (2, 1), (3, 1), (3, 0), (4, 0)];
+        let claimed = ClaimedRun {
+            score: 6,
+            path: detour.iter().map(|&(x, y)| Position::new(x, y)).collect(),
+        };
+        assert_eq!(
+            verify(&maze, &[], &claimed)?,
+            Verdict::Rejected(Discrepancy::PathNotShortest {
+                entrypoint: Position::new(0, 0)
+            })
+        );
+
+        Ok(())
+    }
+}
@@ -1,11 +1,26 @@
+mod mazer_error;
 mod models;
 mod utils;
 
+use std::convert::TryInto;
+
 use maze_core::Maze;
-use maze_generator::create_generator;
-use maze_runner::MazeRunner;
+use maze_generator::{
+    create_generator_by_id, create_targeted_generator_by_id, list_generators, mutate,
+    CampaignGenerator, MazeGenerator, TemplateGenerator,
+};
+use maze_runner::{
+    analysis::{disconnecting_cells, reachability},
+    hints::best_next_wall,
+    MazeRunner,
+};
 
-use models::{MazerConfig, MazerGeneratorType, MazerPosition, MazerRunResult};
+pub use mazer_error::MazerError;
+use models::{
+    MazerCampaign, MazerConfig, MazerDifficultyTarget, MazerEntrypointRun, MazerGeneratedMaze,
+    MazerGeneratorInfo, MazerGeneratorOptions, MazerPath, MazerPosition, MazerRunResult,
+    MazerScoreBreakdown, MazerSearchEvent, MazerWallHint,
+};
 use utils::set_panic_hook;
 use wasm_bindgen::prelude::*;
 
@@ -17,28 +32,522 @@ pub struct Mazer {
 #[wasm_bindgen]
 impl Mazer {
     #[wasm_bindgen]
-    pub fn new(config: MazerConfig) -> Mazer {
+    pub fn new(config: MazerConfig) -> Result<Mazer, MazerError> {
         set_panic_hook();
 
-        let maze = Maze::new(&config.into()).unwrap();
+        let maze = Maze::new(&config.into())?;
+
+        Ok(Self { maze })
+    }
+
+    #[wasm_bindgen]
+    pub fn run(
+        &self,
+        soft_walls: Vec<MazerPosition>,
+    ) -> Result<Option<MazerRunResult>, MazerError> {
+        let walls = soft_walls.into_iter().map(|pos| pos.into()).collect();
+
+        let runner = MazeRunner::new(&self.maze);
+        let result = runner.run(&walls)?;
+        Ok(result.map(|run| run.try_into()).transpose()?)
+    }
+
+    /// Up to `limit` shortest paths of the best entrypoint, the first one is the path of `run`
+    #[wasm_bindgen(js_name = shortestPaths)]
+    pub fn shortest_paths(
+        &self,
+        soft_walls: Vec<MazerPosition>,
+        limit: usize,
+    ) -> Result<Vec<MazerPath>, MazerError> {
+        let walls = soft_walls.into_iter().map(|pos| pos.into()).collect();
+
+        let runner = MazeRunner::new(&self.maze);
+        let paths = runner
+            .run(&walls)?
+            .map(|run| run.shortest_paths(limit))
+            .unwrap_or_default();
+        Ok(paths.into_iter().map(|path| path.into()).collect())
+    }
+
+    #[wasm_bindgen(js_name = runEntrypoints)]
+    pub fn run_entrypoints(
+        &self,
+        soft_walls: Vec<MazerPosition>,
+    ) -> Result<Vec<MazerEntrypointRun>, MazerError> {
+        let walls = soft_walls.into_iter().map(|pos| pos.into()).collect();
+
+        let runner = MazeRunner::new(&self.maze);
+        let results = runner.run_entrypoints(&walls)?;
+        Ok(results
+            .into_iter()
+            .map(|run| run.try_into())
+            .collect::<Result<_, _>>()?)
+    }
+
+    /// Score of the maze combined by the scoring rule of the config, together with the scores of separate entrypoints
+    #[wasm_bindgen]
+    pub fn score(&self, soft_walls: Vec<MazerPosition>) -> Result<MazerScoreBreakdown, MazerError> {
+        let walls = soft_walls.into_iter().map(|pos| pos.into()).collect();
 
-        Self { maze }
+        let runner = MazeRunner::new(&self.maze);
+        let breakdown = runner.score(&walls)?;
+        Ok(breakdown.into())
     }
 
     #[wasm_bindgen]
-    pub fn run(&self, soft_walls: Vec<MazerPosition>) -> Option<MazerRunResult> {
+    pub fn trace(
+        &self,
+        soft_walls: Vec<MazerPosition>,
+    ) -> Result<Vec<MazerSearchEvent>, MazerError> {
         let walls = soft_walls.into_iter().map(|pos| pos.into()).collect();
 
         let runner = MazeRunner::new(&self.maze);
-        let result = runner.run(&walls).unwrap();
-        result.map(|run| run.into())
+        let trace = runner.trace(&walls)?;
+        Ok(trace.map(|event| event.into()).collect())
     }
 
+    #[wasm_bindgen(js_name = bestNextWall)]
+    pub fn best_next_wall(
+        &self,
+        soft_walls: Vec<MazerPosition>,
+    ) -> Result<Vec<MazerWallHint>, MazerError> {
+        let walls: Vec<_> = soft_walls.into_iter().map(|pos| pos.into()).collect();
+
+        let hints = best_next_wall(&self.maze, &walls)?;
+        Ok(hints.into_iter().map(|hint| hint.into()).collect())
+    }
+
+    #[wasm_bindgen(js_name = disconnectingCells)]
+    pub fn disconnecting_cells(
+        &self,
+        soft_walls: Vec<MazerPosition>,
+    ) -> Result<Vec<MazerPosition>, MazerError> {
+        let walls: Vec<_> = soft_walls.into_iter().map(|pos| pos.into()).collect();
+
+        let cells = disconnecting_cells(&self.maze, &walls)?;
+        Ok(cells.into_iter().map(|pos| pos.into()).collect())
+    }
+
+    /// Enterable tiles no run can make use of with the current soft walls
+    #[wasm_bindgen(js_name = deadCells)]
+    pub fn dead_cells(
+        &self,
+        soft_walls: Vec<MazerPosition>,
+    ) -> Result<Vec<MazerPosition>, MazerError> {
+        let walls: Vec<_> = soft_walls.into_iter().map(|pos| pos.into()).collect();
+
+        let cells = reachability(&self.maze, &walls)?.dead_cells();
+        Ok(cells.into_iter().map(|pos| pos.into()).collect())
+    }
+
+    /// Generates config with the default options of the generator registered under the id
     #[wasm_bindgen(js_name = generateConfig)]
-    pub fn generate_config(generator_type: MazerGeneratorType) -> MazerConfig {
+    pub fn generate_config(id: &str) -> Result<MazerConfig, MazerError> {
+        set_panic_hook();
+
+        let generator = create_generator_by_id(id, None)?;
+        Ok(generator.generate()?.config.into())
+    }
+
+    /// Generates maze with the generator registered under the id from the seed, or from a random one
+    /// if none is given, the same seed, options and target give the same maze as the native generator
+    #[wasm_bindgen(js_name = generateMaze)]
+    pub fn generate_maze(
+        id: &str,
+        seed: Option<u64>,
+        options: Option<MazerGeneratorOptions>,
+        target: Option<MazerDifficultyTarget>,
+    ) -> Result<MazerGeneratedMaze, MazerError> {
         set_panic_hook();
 
-        let generator = create_generator(generator_type.into());
-        generator.generate().unwrap().into()
+        let options = options.map(|options| options.into());
+        let generator = match target {
+            Some(target) => create_targeted_generator_by_id(id, options, target.into())?,
+            None => create_generator_by_id(id, options)?,
+        };
+        let generated = match seed {
+            Some(seed) => generator.generate_with_seed(seed)?,
+            None => generator.generate()?,
+        };
+        Ok(generated.into())
+    }
+
+    /// Fills wildcards of the ASCII template, see `TemplateGenerator` for the template format
+    #[wasm_bindgen(js_name = generateMazeFromTemplate)]
+    pub fn generate_maze_from_template(
+        template: &str,
+        max_soft_wall_count: u32,
+        seed: Option<u64>,
+    ) -> Result<MazerGeneratedMaze, MazerError> {
+        set_panic_hook();
+
+        let generator = TemplateGenerator::new(template, max_soft_wall_count)?;
+        let generated = match seed {
+            Some(seed) => generator.generate_with_seed(seed)?,
+            None => generator.generate()?,
+        };
+        Ok(generated.into())
+    }
+
+    /// Nearby solvable variant of the maze made by `strength` small changes, the same seed gives the same variant
+    #[wasm_bindgen(js_name = mutateMaze)]
+    pub fn mutate_maze(
+        config: MazerConfig,
+        strength: usize,
+        seed: u64,
+    ) -> Result<MazerGeneratedMaze, MazerError> {
+        set_panic_hook();
+
+        Ok(mutate(&config.into(), strength, seed)?.into())
+    }
+
+    /// Campaign of levels growing in size, checkpoint count and difficulty,
+    /// generated from the seed, or from a random one if none is given
+    #[wasm_bindgen(js_name = generateCampaign)]
+    pub fn generate_campaign(
+        level_count: usize,
+        seed: Option<u64>,
+    ) -> Result<MazerCampaign, MazerError> {
+        set_panic_hook();
+
+        let generator = CampaignGenerator::new(level_count)?;
+        let campaign = match seed {
+            Some(seed) => generator.generate_with_seed(seed)?,
+            None => generator.generate()?,
+        };
+        Ok(campaign.into())
+    }
+
+    /// Generators available to `generateMaze` with their descriptions and default options
+    #[wasm_bindgen(js_name = listGenerators)]
+    pub fn list_generators() -> Vec<MazerGeneratorInfo> {
+        list_generators()
+            .into_iter()
+            .map(|info| info.into())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use models::{MazerCheckpoint, MazerDifficultyMetric, MazerScoringRuleType, MazerStairs};
+
+    use super::*;
+
+    fn get_position(x: usize, y: usize) -> MazerPosition {
+        MazerPosition::new(x, y)
+    }
+
+    fn get_config(
+        col_count: usize,
+        row_count: usize,
+        entrypoints: Vec<MazerPosition>,
+        checkpoints: Vec<MazerCheckpoint>,
+    ) -> MazerConfig {
+        MazerConfig::new(col_count, row_count, 2, entrypoints, checkpoints, vec![])
+    }
+
+    fn get_mazer() -> Result<Mazer, MazerError> {
+        Mazer::new(get_config(
+            4,
+            4,
+            vec![get_position(0, 0)],
+            vec![MazerCheckpoint::new(get_position(3, 3), 1)],
+        ))
+    }
+
+    #[test]
+    fn test_new_with_malformed_config() {
+        let cases = [
+            (get_config(0, 0, vec![], vec![]), "InvalidMazeSize"),
+            (
+                get_config(
+                    usize::MAX,
+                    3,
+                    vec![get_position(0, 0)],
+                    vec![MazerCheckpoint::new(get_position(1, 1), 1)],
+                ),
+                "MazeTooLarge",
+            ),
+            (
+                get_config(
+                    4,
+                    4,
+                    vec![],
+                    vec![MazerCheckpoint::new(get_position(1, 1), 1)],
+                ),
+                "NoEntrypoint",
+            ),
+            (
+                get_config(4, 4, vec![get_position(0, 0)], vec![]),
+                "NoCheckpoint",
+            ),
+            (
+                get_config(
+                    4,
+                    4,
+                    vec![get_position(usize::MAX, 0)],
+                    vec![MazerCheckpoint::new(get_position(1, 1), 1)],
+                ),
+                "TileOutOfBounds",
+            ),
+            (
+                get_config(
+                    4,
+                    4,
+                    vec![get_position(1, 1)],
+                    vec![MazerCheckpoint::new(get_position(1, 1), i32::MIN)],
+                ),
+                "OverlappingTiles",
+            ),
+        ];
+
+        for (config, code) in cases {
+            let error = Mazer::new(config).err();
+            assert_eq!(error.as_ref().map(|error| error.code()), Some(code.into()));
+            assert!(error.is_some_and(|error| !error.message().is_empty()));
+        }
+    }
+
+    #[test]
+    fn test_calls_with_malformed_soft_walls() -> Result<(), MazerError> {
+        let mazer = get_mazer()?;
+        let cases = [
+            (
+                vec![get_position(1, 0), get_position(2, 0), get_position(3, 0)],
+                "TooManySoftWalls",
+            ),
+            (
+                vec![get_position(usize::MAX, usize::MAX)],
+                "WallOutOfBounds",
+            ),
+            (vec![get_position(0, usize::MAX)], "WallOutOfBounds"),
+            (vec![get_position(0, 0)], "OverlappingWall"),
+            (
+                vec![get_position(1, 1), get_position(1, 1)],
+                "OverlappingWall",
+            ),
+        ];
+
+        for (walls, code) in cases {
+            let codes = [
+                mazer.run(walls.clone()).err(),
+                mazer.run_entrypoints(walls.clone()).err(),
+                mazer.score(walls.clone()).err(),
+                mazer.trace(walls.clone()).err(),
+                mazer.best_next_wall(walls.clone()).err(),
+                mazer.disconnecting_cells(walls.clone()).err(),
+                mazer.dead_cells(walls.clone()).err(),
+            ];
+
+            for error in codes {
+                assert_eq!(error.map(|error| error.code()), Some(code.into()));
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_calls_with_valid_soft_walls() -> Result<(), MazerError> {
+        let mazer = get_mazer()?;
+        let walls = vec![get_position(1, 0)];
+
+        assert_eq!(mazer.run(walls.clone())?.map(|run| run.score()), Some(6));
+        assert_eq!(
+            mazer.run(walls.clone())?.and_then(|run| run.path_count()),
+            Some(10)
+        );
+        // distance fields are computed on demand, walled tile is unreached
+        let run = mazer.run(walls.clone())?;
+        assert_eq!(
+            run.as_ref()
+                .and_then(|run| run.start_distances())
+                .map(|distances| distances[..5].to_vec()),
+            Some(vec![0, 1, 2, 3, -1])
+        );
+        assert!(run.and_then(|run| run.level_distances(1)).is_some());
+        assert!(MazerRunResult::new(6, vec![get_position(0, 0)])
+            .start_distances()
+            .is_none());
+        assert_eq!(mazer.shortest_paths(walls.clone(), 2)?.len(), 2);
+        assert_eq!(mazer.run_entrypoints(walls.clone())?.len(), 1);
+        assert_eq!(mazer.score(walls.clone())?.score(), Some(6));
+        assert!(!mazer.trace(walls.clone())?.is_empty());
+        assert!(mazer.best_next_wall(walls.clone()).is_ok());
+        assert!(mazer.disconnecting_cells(walls.clone()).is_ok());
+        assert!(mazer.dead_cells(walls)?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_distances_of_multiple_floors() -> Result<(), MazerError> {
+        let config = get_config(
+            2,
+            2,
+            vec![get_position(0, 0)],
+            vec![MazerCheckpoint::new(MazerPosition::on_floor(0, 1, 1), 1)],
+        )
+        .with_floors(2, vec![MazerStairs::new(get_position(1, 1), 1)]);
+        let run = Mazer::new(config)?.run(vec![])?;
+        let distances = run
+            .and_then(|run| run.start_distances())
+            .unwrap_or_default();
+
+        // every floor has colCount * rowCount distances, the ground floor goes first
+        assert_eq!(distances.len(), 8);
+        let distance = |x: usize, y: usize, floor: usize| distances[(floor * 2 + x) * 2 + y];
+        assert_eq!(distance(1, 1, 0), 2);
+        assert_eq!(distance(1, 1, 1), 3);
+        assert_eq!(distance(0, 1, 1), 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_scoring_rule() -> Result<(), MazerError> {
+        let config = get_config(
+            4,
+            4,
+            vec![get_position(0, 0), get_position(0, 3)],
+            vec![MazerCheckpoint::new(get_position(3, 3), 1)],
+        );
+
+        let mazer = Mazer::new(config.clone().with_scoring_rule(MazerScoringRuleType::Max))?;
+        let run = mazer.run(vec![])?;
+        assert_eq!(run.as_ref().map(|run| run.score()), Some(6));
+        assert_eq!(
+            run.and_then(|run| run.entrypoint())
+                .map(|position| position.y()),
+            Some(0)
+        );
+
+        let mazer = Mazer::new(config.with_scoring_rule(MazerScoringRuleType::Sum))?;
+        assert_eq!(mazer.score(vec![])?.score(), Some(9));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_maze_with_seed() -> Result<(), MazerError> {
+        let first = Mazer::generate_maze("vanilla", Some(42), None, None)?;
+        let second = Mazer::generate_maze("vanilla", Some(42), None, None)?;
+
+        assert_eq!(first.seed(), 42);
+        let walls = |maze: &MazerGeneratedMaze| {
+            maze.config()
+                .walls()
+                .iter()
+                .map(|position| (position.x(), position.y()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(walls(&first), walls(&second));
+        assert_eq!(first.config().max_soft_wall_count(), 23);
+        assert!(Mazer::new(first.config()).is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_maze_with_options() -> Result<(), MazerError> {
+        let options = MazerGeneratorOptions::default_for("vanilla")?
+            .with_size(10, 6)
+            .with_wall_range(2, 5);
+        let generated = Mazer::generate_maze("vanilla", Some(1), Some(options.clone()), None)?;
+
+        assert_eq!(generated.config().col_count(), 10);
+        assert_eq!(generated.config().row_count(), 6);
+
+        let error =
+            Mazer::generate_maze("vanilla", None, Some(options.with_size(3, 6)), None).err();
+        assert_eq!(
+            error.map(|error| error.code()),
+            Some("GeneratorBoardTooSmall".into())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_maze_with_difficulty_target() {
+        let target = MazerDifficultyTarget::new(MazerDifficultyMetric::ScoreRatio, 100.0, 200.0, 1);
+        let error = Mazer::generate_maze("waterfall", Some(3), None, Some(target)).err();
+
+        assert_eq!(
+            error.map(|error| error.code()),
+            Some("DifficultyNotReached".into())
+        );
+    }
+
+    #[test]
+    fn test_generate_maze_by_id() -> Result<(), MazerError> {
+        let generators = Mazer::list_generators();
+        assert!(generators.iter().any(|info| info.id() == "spiral"));
+
+        for info in generators {
+            let generated = Mazer::generate_maze(&info.id(), Some(2), None, None)?;
+            assert_eq!(
+                generated.config().col_count(),
+                info.default_options().col_count()
+            );
+        }
+
+        let error = Mazer::generate_maze("labyrinth", None, None, None).err();
+        assert_eq!(
+            error.map(|error| error.code()),
+            Some("UnknownGenerator".into())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_maze_from_template() -> Result<(), MazerError> {
+        let generated = Mazer::generate_maze_from_template("E?a.\n..a1", 3, Some(7))?;
+        assert_eq!(generated.seed(), 7);
+        assert_eq!(generated.config().col_count(), 4);
+        assert_eq!(generated.config().row_count(), 2);
+
+        let error = Mazer::generate_maze_from_template("E#a1", 3, Some(7)).err();
+        assert_eq!(
+            error.map(|error| error.code()),
+            Some("UnsatisfiableTemplate".into())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mutate_maze() -> Result<(), MazerError> {
+        let config = Mazer::generate_maze("vanilla", Some(3), None, None)?.config();
+
+        let mutated = Mazer::mutate_maze(config.clone(), 2, 11)?;
+        assert_eq!(mutated.seed(), 11);
+        assert_eq!(mutated.config().col_count(), config.col_count());
+        assert!(Mazer::new(mutated.config()).is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_campaign() -> Result<(), MazerError> {
+        let campaign = Mazer::generate_campaign(5, Some(4))?;
+        let levels = campaign.levels();
+
+        assert_eq!(campaign.seed(), 4);
+        assert_eq!(levels.len(), 5);
+        assert_eq!(levels[4].generator_id(), "waterfall");
+        assert!(levels
+            .windows(2)
+            .all(|pair| pair[1].difficulty() >= pair[0].difficulty()));
+
+        let error = Mazer::generate_campaign(0, None).err();
+        assert_eq!(
+            error.map(|error| error.code()),
+            Some("InvalidCampaignLength".into())
+        );
+
+        Ok(())
     }
 }
@@ -0,0 +1,86 @@
+use maze_core::MazeError;
+use maze_generator::GeneratorError;
+use maze_runner::MazeRunnerError;
+use wasm_bindgen::prelude::*;
+
+/// Error thrown to JS, `code` is stable and meant for matching while `message` is human readable
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq)]
+pub struct MazerError {
+    code: String,
+    message: String,
+}
+
+impl MazerError {
+    fn new(code: &str, message: String) -> Self {
+        Self {
+            code: code.to_string(),
+            message,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl MazerError {
+    #[wasm_bindgen(getter)]
+    pub fn code(&self) -> String {
+        self.code.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn message(&self) -> String {
+        self.message.clone()
+    }
+}
+
+impl From<MazeError> for MazerError {
+    fn from(error: MazeError) -> Self {
+        let code = match error {
+            MazeError::InvalidMazeSize { .. } => "InvalidMazeSize",
+            MazeError::MazeTooLarge { .. } => "MazeTooLarge",
+            MazeError::NoEntrypoint => "NoEntrypoint",
+            MazeError::NoCheckpoint => "NoCheckpoint",
+            MazeError::TileOutOfBounds(..) => "TileOutOfBounds",
+            MazeError::OverlappingTiles { .. } => "OverlappingTiles",
+            MazeError::InvalidStairs { .. } => "InvalidStairs",
+            MazeError::BlockedStairs { .. } => "BlockedStairs",
+        };
+
+        Self::new(code, error.to_string())
+    }
+}
+
+impl From<MazeRunnerError> for MazerError {
+    fn from(error: MazeRunnerError) -> Self {
+        let code = match error {
+            MazeRunnerError::TooManySoftWalls { .. } => "TooManySoftWalls",
+            MazeRunnerError::WallOutOfBounds { .. } => "WallOutOfBounds",
+            MazeRunnerError::OverlappingWall { .. } => "OverlappingWall",
+            MazeRunnerError::PathReconstructionFailed { .. } => "PathReconstructionFailed",
+        };
+
+        Self::new(code, error.to_string())
+    }
+}
+
+impl From<GeneratorError> for MazerError {
+    fn from(error: GeneratorError) -> Self {
+        let code = match error {
+            GeneratorError::AggregatedError(..) => "GenerationFailed",
+            GeneratorError::InvalidRange { .. } => "InvalidGeneratorRange",
+            GeneratorError::TooManyTiles { .. } => "TooManyGeneratedTiles",
+            GeneratorError::BoardTooSmall { .. } => "GeneratorBoardTooSmall",
+            GeneratorError::UnknownGenerator { .. } => "UnknownGenerator",
+            GeneratorError::DuplicateGenerator { .. } => "DuplicateGenerator",
+            GeneratorError::InvalidTemplate { .. } => "InvalidTemplate",
+            GeneratorError::UnsatisfiableTemplate { .. } => "UnsatisfiableTemplate",
+            GeneratorError::UnsolvableMaze => "UnsolvableMaze",
+            GeneratorError::InvalidCampaignLength { .. } => "InvalidCampaignLength",
+            GeneratorError::InvalidDifficultyTarget { .. } => "InvalidDifficultyTarget",
+            GeneratorError::DifficultyNotReached { .. } => "DifficultyNotReached",
+            GeneratorError::CampaignDifficultyNotReached { .. } => "CampaignDifficultyNotReached",
+        };
+
+        Self::new(code, error.to_string())
+    }
+}
@@ -1,8 +1,20 @@
-use crate::utils::set_panic_hook;
+use std::{
+    convert::{TryFrom, TryInto},
+    ops::Range,
+    rc::Rc,
+};
 
-use maze_core::{Checkpoint, MazeConfig, Position};
-use maze_generator::MazeGeneratorType;
-use maze_runner::MazeRunResult;
+use crate::{utils::set_panic_hook, MazerError};
+
+use maze_core::{Checkpoint, MazeConfig, Position, Stairs};
+use maze_generator::{
+    get_generator_info, Campaign, CampaignLesson, CampaignLevel, DifficultyMetric,
+    DifficultyTarget, GeneratedMaze, GeneratorError, GeneratorInfo, GeneratorOptions,
+};
+use maze_runner::{
+    hints::WallHint, DistanceFloors, EntrypointRun, EntrypointScore, MazeRunResult,
+    MazeRunnerError, PathSegment, ScoreBreakdown, ScoringRuleType, SearchEvent,
+};
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen]
@@ -10,6 +22,7 @@ use wasm_bindgen::prelude::*;
 pub struct MazerPosition {
     x: usize,
     y: usize,
+    floor: Option<usize>,
 }
 
 #[wasm_bindgen]
@@ -17,7 +30,12 @@ impl MazerPosition {
     #[wasm_bindgen]
     pub fn new(x: usize, y: usize) -> Self {
         set_panic_hook();
-        Self { x, y }
+        Self { x, y, floor: None }
+    }
+
+    #[wasm_bindgen(js_name = onFloor)]
+    pub fn on_floor(x: usize, y: usize, floor: usize) -> Self {
+        Position::on_floor(x, y, floor).into()
     }
 
     #[wasm_bindgen(getter)]
@@ -29,19 +47,29 @@ impl MazerPosition {
     pub fn y(&self) -> usize {
         self.y
     }
+
+    #[wasm_bindgen(getter)]
+    pub fn floor(&self) -> Option<usize> {
+        self.floor
+    }
 }
 
 impl From<Position> for MazerPosition {
     fn from(position: Position) -> Self {
-        MazerPosition::new(position.x, position.y)
+        Self {
+            x: position.x,
+            y: position.y,
+            floor: position.floor,
+        }
     }
 }
 
-impl Into<Position> for MazerPosition {
-    fn into(self) -> Position {
+impl From<MazerPosition> for Position {
+    fn from(position: MazerPosition) -> Self {
         Position {
-            x: self.x,
-            y: self.y,
+            x: position.x,
+            y: position.y,
+            floor: position.floor,
         }
     }
 }
@@ -78,23 +106,68 @@ impl From<Checkpoint> for MazerCheckpoint {
     }
 }
 
-impl Into<Checkpoint> for MazerCheckpoint {
-    fn into(self) -> Checkpoint {
+impl From<MazerCheckpoint> for Checkpoint {
+    fn from(checkpoint: MazerCheckpoint) -> Self {
         Checkpoint {
-            position: self.position.into(),
-            level: self.level,
+            position: checkpoint.position.into(),
+            level: checkpoint.level,
+        }
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct MazerStairs {
+    position: MazerPosition,
+    to_floor: usize,
+}
+
+#[wasm_bindgen]
+impl MazerStairs {
+    #[wasm_bindgen]
+    pub fn new(position: MazerPosition, to_floor: usize) -> Self {
+        set_panic_hook();
+        Self { position, to_floor }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn position(&self) -> MazerPosition {
+        self.position.clone()
+    }
+
+    #[wasm_bindgen(getter, js_name = toFloor)]
+    pub fn to_floor(&self) -> usize {
+        self.to_floor
+    }
+}
+
+impl From<Stairs> for MazerStairs {
+    fn from(stairs: Stairs) -> Self {
+        MazerStairs::new(stairs.position.into(), stairs.to_floor)
+    }
+}
+
+impl From<MazerStairs> for Stairs {
+    fn from(stairs: MazerStairs) -> Self {
+        Stairs {
+            position: stairs.position.into(),
+            to_floor: stairs.to_floor,
         }
     }
 }
 
 #[wasm_bindgen]
+#[derive(Clone)]
 pub struct MazerConfig {
     col_count: usize,
     row_count: usize,
+    floor_count: usize,
     max_soft_wall_count: u32,
     entrypoints: Vec<MazerPosition>,
     checkpoints: Vec<MazerCheckpoint>,
     walls: Vec<MazerPosition>,
+    stairs: Vec<MazerStairs>,
+    scoring_rule: MazerScoringRuleType,
 }
 
 #[wasm_bindgen]
@@ -112,13 +185,31 @@ impl MazerConfig {
         Self {
             col_count,
             row_count,
+            floor_count: 1,
             max_soft_wall_count,
             walls,
             entrypoints,
             checkpoints,
+            stairs: vec![],
+            scoring_rule: MazerScoringRuleType::Min,
         }
     }
 
+    /// Turns the config into a multi floor one, floors are connected only by the stairs
+    #[wasm_bindgen(js_name = withFloors)]
+    pub fn with_floors(mut self, floor_count: usize, stairs: Vec<MazerStairs>) -> Self {
+        self.floor_count = floor_count;
+        self.stairs = stairs;
+        self
+    }
+
+    /// Replaces the default `Min` rule the entrypoint scores are combined with
+    #[wasm_bindgen(js_name = withScoringRule)]
+    pub fn with_scoring_rule(mut self, scoring_rule: MazerScoringRuleType) -> Self {
+        self.scoring_rule = scoring_rule;
+        self
+    }
+
     #[wasm_bindgen(getter, js_name = colCount)]
     pub fn col_count(&self) -> usize {
         self.col_count
@@ -129,6 +220,11 @@ impl MazerConfig {
         self.row_count
     }
 
+    #[wasm_bindgen(getter, js_name = floorCount)]
+    pub fn floor_count(&self) -> usize {
+        self.floor_count
+    }
+
     #[wasm_bindgen(getter, js_name = maxSoftWallCount)]
     pub fn max_soft_wall_count(&self) -> u32 {
         self.max_soft_wall_count
@@ -148,6 +244,16 @@ impl MazerConfig {
     pub fn walls(&self) -> Vec<MazerPosition> {
         self.walls.clone()
     }
+
+    #[wasm_bindgen(getter, js_name = stairs)]
+    pub fn stairs(&self) -> Vec<MazerStairs> {
+        self.stairs.clone()
+    }
+
+    #[wasm_bindgen(getter, js_name = scoringRule)]
+    pub fn scoring_rule(&self) -> MazerScoringRuleType {
+        self.scoring_rule
+    }
 }
 
 impl From<MazeConfig> for MazerConfig {
@@ -160,32 +266,94 @@ impl From<MazeConfig> for MazerConfig {
             config.checkpoints.into_iter().map(|x| x.into()).collect(),
             config.walls.into_iter().map(|x| x.into()).collect(),
         )
+        .with_floors(
+            config.floor_count,
+            config.stairs.into_iter().map(|x| x.into()).collect(),
+        )
+        .with_scoring_rule(config.scoring_rule.into())
     }
 }
 
-impl Into<MazeConfig> for MazerConfig {
-    fn into(self) -> MazeConfig {
+impl From<MazerConfig> for MazeConfig {
+    fn from(config: MazerConfig) -> Self {
         MazeConfig {
-            col_count: self.col_count,
-            row_count: self.row_count,
-            max_soft_wall_count: self.max_soft_wall_count,
-            entrypoints: self.entrypoints().into_iter().map(|x| x.into()).collect(),
-            checkpoints: self.checkpoints().into_iter().map(|x| x.into()).collect(),
-            walls: self.walls().into_iter().map(|x| x.into()).collect(),
+            col_count: config.col_count,
+            row_count: config.row_count,
+            floor_count: config.floor_count,
+            max_soft_wall_count: config.max_soft_wall_count,
+            entrypoints: config.entrypoints.into_iter().map(|x| x.into()).collect(),
+            checkpoints: config.checkpoints.into_iter().map(|x| x.into()).collect(),
+            walls: config.walls.into_iter().map(|x| x.into()).collect(),
+            stairs: config.stairs.into_iter().map(|x| x.into()).collect(),
+            scoring_rule: config.scoring_rule.into(),
         }
     }
 }
 
 #[wasm_bindgen]
+#[derive(Clone)]
+pub struct MazerPathSegment {
+    level: i32,
+    start: MazerPosition,
+    checkpoint: MazerPosition,
+    length: u32,
+}
+
+#[wasm_bindgen]
+impl MazerPathSegment {
+    #[wasm_bindgen(getter)]
+    pub fn level(&self) -> i32 {
+        self.level
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn start(&self) -> MazerPosition {
+        self.start.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn checkpoint(&self) -> MazerPosition {
+        self.checkpoint.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> u32 {
+        self.length
+    }
+}
+
+impl From<PathSegment> for MazerPathSegment {
+    fn from(segment: PathSegment) -> Self {
+        Self {
+            level: segment.level,
+            start: segment.start.into(),
+            checkpoint: segment.checkpoint.into(),
+            length: segment.length,
+        }
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Clone)]
 pub struct MazerRunResult {
     score: u32,
+    entrypoint: Option<MazerPosition>,
     path: Vec<MazerPosition>,
+    segments: Vec<MazerPathSegment>,
+    // path count and distance fields are computed from the run only when asked for, results built in JS have none
+    run: Option<Rc<MazeRunResult>>,
 }
 
 #[wasm_bindgen]
 impl MazerRunResult {
     pub fn new(score: u32, path: Vec<MazerPosition>) -> Self {
-        Self { score, path }
+        Self {
+            score,
+            entrypoint: path.first().cloned(),
+            path,
+            segments: vec![],
+            run: None,
+        }
     }
 
     #[wasm_bindgen(getter)]
@@ -193,32 +361,684 @@ impl MazerRunResult {
         self.score
     }
 
+    /// `undefined` only for results constructed with an empty path
+    #[wasm_bindgen(getter)]
+    pub fn entrypoint(&self) -> Option<MazerPosition> {
+        self.entrypoint.clone()
+    }
+
     #[wasm_bindgen(getter)]
     pub fn path(&self) -> Vec<MazerPosition> {
         self.path.clone()
     }
+
+    /// Number of distinct shortest paths, `1` means the solution is forced
+    #[wasm_bindgen(js_name = pathCount)]
+    pub fn path_count(&self) -> Option<u64> {
+        let run = self.run.as_ref()?;
+        Some(run.shortest_path_count())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn segments(&self) -> Vec<MazerPathSegment> {
+        self.segments.clone()
+    }
+
+    /// Flat `[(floor * colCount + x) * rowCount + y]` distances from the entrypoint,
+    /// `-1` for unreached tiles
+    #[wasm_bindgen(js_name = startDistances)]
+    pub fn start_distances(&self) -> Option<Vec<i32>> {
+        let run = self.run.as_ref()?;
+        Some(flatten_distance_field(run.start_distance_field()))
+    }
+
+    /// Flat `[(floor * colCount + x) * rowCount + y]` distances for the given checkpoint level,
+    /// `-1` for unreached tiles
+    #[wasm_bindgen(js_name = levelDistances)]
+    pub fn level_distances(&self, checkpoint_level: i32) -> Option<Vec<i32>> {
+        let run = self.run.as_ref()?;
+        run.distance_field(checkpoint_level)
+            .map(flatten_distance_field)
+    }
 }
 
-impl From<MazeRunResult> for MazerRunResult {
-    fn from(result: MazeRunResult) -> Self {
-        MazerRunResult::new(
-            result.score(),
-            result.solved_path().into_iter().map(|x| x.into()).collect(),
-        )
+fn flatten_distance_field(field: DistanceFloors) -> Vec<i32> {
+    field
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|distance| distance.map_or(-1, |distance| distance as i32))
+        .collect()
+}
+
+impl TryFrom<MazeRunResult> for MazerRunResult {
+    type Error = MazeRunnerError;
+
+    fn try_from(result: MazeRunResult) -> Result<Self, Self::Error> {
+        let path = result.solved_path()?;
+
+        Ok(Self {
+            score: result.score(),
+            entrypoint: Some((*result.entrypoint()).into()),
+            segments: result
+                .split_path(&path)
+                .into_iter()
+                .map(|x| x.into())
+                .collect(),
+            path: path.into_iter().map(|x| x.into()).collect(),
+            run: Some(Rc::new(result)),
+        })
     }
 }
 
 #[wasm_bindgen]
-pub enum MazerGeneratorType {
-    Vanilla,
-    Waterfall,
+pub struct MazerPath {
+    positions: Vec<MazerPosition>,
 }
 
-impl Into<MazeGeneratorType> for MazerGeneratorType {
-    fn into(self) -> MazeGeneratorType {
-        match self {
-            MazerGeneratorType::Vanilla => MazeGeneratorType::Vanilla,
-            MazerGeneratorType::Waterfall => MazeGeneratorType::Waterfall,
+#[wasm_bindgen]
+impl MazerPath {
+    #[wasm_bindgen(getter)]
+    pub fn positions(&self) -> Vec<MazerPosition> {
+        self.positions.clone()
+    }
+}
+
+impl From<Vec<Position>> for MazerPath {
+    fn from(path: Vec<Position>) -> Self {
+        Self {
+            positions: path.into_iter().map(|x| x.into()).collect(),
+        }
+    }
+}
+
+#[wasm_bindgen]
+pub struct MazerEntrypointRun {
+    entrypoint: MazerPosition,
+    result: Option<MazerRunResult>,
+}
+
+#[wasm_bindgen]
+impl MazerEntrypointRun {
+    #[wasm_bindgen(getter)]
+    pub fn entrypoint(&self) -> MazerPosition {
+        self.entrypoint.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn result(&self) -> Option<MazerRunResult> {
+        self.result.clone()
+    }
+}
+
+impl TryFrom<EntrypointRun> for MazerEntrypointRun {
+    type Error = MazeRunnerError;
+
+    fn try_from(run: EntrypointRun) -> Result<Self, Self::Error> {
+        Ok(Self {
+            entrypoint: run.entrypoint.into(),
+            result: run.result.map(|result| result.try_into()).transpose()?,
+        })
+    }
+}
+
+#[wasm_bindgen]
+pub struct MazerWallHint {
+    position: MazerPosition,
+    score: u32,
+    score_gain: u32,
+}
+
+#[wasm_bindgen]
+impl MazerWallHint {
+    #[wasm_bindgen(getter)]
+    pub fn position(&self) -> MazerPosition {
+        self.position.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+
+    #[wasm_bindgen(getter, js_name = scoreGain)]
+    pub fn score_gain(&self) -> u32 {
+        self.score_gain
+    }
+}
+
+impl From<WallHint> for MazerWallHint {
+    fn from(hint: WallHint) -> Self {
+        Self {
+            position: hint.position.into(),
+            score: hint.score,
+            score_gain: hint.score_gain,
+        }
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy)]
+pub enum MazerSearchEventKind {
+    Started,
+    NodeExpanded,
+    NodeEnqueued,
+    LevelAdvanced,
+    Solved,
+    Unsolvable,
+}
+
+/// Flattened search event, `position` is the entrypoint for `Started`, `Solved` and `Unsolvable`
+/// and `distance` holds the score for `Solved`
+#[wasm_bindgen]
+pub struct MazerSearchEvent {
+    kind: MazerSearchEventKind,
+    position: MazerPosition,
+    distance: Option<u32>,
+    level: Option<i32>,
+}
+
+#[wasm_bindgen]
+impl MazerSearchEvent {
+    #[wasm_bindgen(getter)]
+    pub fn kind(&self) -> MazerSearchEventKind {
+        self.kind
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn position(&self) -> MazerPosition {
+        self.position.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn distance(&self) -> Option<u32> {
+        self.distance
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn level(&self) -> Option<i32> {
+        self.level
+    }
+}
+
+impl From<SearchEvent> for MazerSearchEvent {
+    fn from(event: SearchEvent) -> Self {
+        let (kind, position, distance, level) = match event {
+            SearchEvent::Started { entrypoint } => {
+                (MazerSearchEventKind::Started, entrypoint, None, None)
+            }
+            SearchEvent::NodeExpanded {
+                position,
+                distance,
+                level,
+            } => (
+                MazerSearchEventKind::NodeExpanded,
+                position,
+                Some(distance),
+                Some(level),
+            ),
+            SearchEvent::NodeEnqueued {
+                position,
+                distance,
+                level,
+            } => (
+                MazerSearchEventKind::NodeEnqueued,
+                position,
+                Some(distance),
+                Some(level),
+            ),
+            SearchEvent::LevelAdvanced {
+                position,
+                distance,
+                level,
+            } => (
+                MazerSearchEventKind::LevelAdvanced,
+                position,
+                Some(distance),
+                Some(level),
+            ),
+            SearchEvent::Solved { entrypoint, score } => {
+                (MazerSearchEventKind::Solved, entrypoint, Some(score), None)
+            }
+            SearchEvent::Unsolvable { entrypoint } => {
+                (MazerSearchEventKind::Unsolvable, entrypoint, None, None)
+            }
+        };
+
+        Self {
+            kind,
+            position: position.into(),
+            distance,
+            level,
+        }
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct MazerEntrypointScore {
+    entrypoint: MazerPosition,
+    score: Option<u32>,
+}
+
+#[wasm_bindgen]
+impl MazerEntrypointScore {
+    #[wasm_bindgen(getter)]
+    pub fn entrypoint(&self) -> MazerPosition {
+        self.entrypoint.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn score(&self) -> Option<u32> {
+        self.score
+    }
+}
+
+impl From<EntrypointScore> for MazerEntrypointScore {
+    fn from(score: EntrypointScore) -> Self {
+        Self {
+            entrypoint: score.entrypoint.into(),
+            score: score.score,
+        }
+    }
+}
+
+#[wasm_bindgen]
+pub struct MazerScoreBreakdown {
+    score: Option<u32>,
+    entrypoint_scores: Vec<MazerEntrypointScore>,
+}
+
+#[wasm_bindgen]
+impl MazerScoreBreakdown {
+    #[wasm_bindgen(getter)]
+    pub fn score(&self) -> Option<u32> {
+        self.score
+    }
+
+    #[wasm_bindgen(getter, js_name = entrypointScores)]
+    pub fn entrypoint_scores(&self) -> Vec<MazerEntrypointScore> {
+        self.entrypoint_scores.clone()
+    }
+}
+
+impl From<ScoreBreakdown> for MazerScoreBreakdown {
+    fn from(breakdown: ScoreBreakdown) -> Self {
+        Self {
+            score: breakdown.score,
+            entrypoint_scores: breakdown
+                .entrypoint_scores
+                .into_iter()
+                .map(|score| score.into())
+                .collect(),
+        }
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MazerScoringRuleType {
+    Min,
+    Max,
+    Sum,
+    Average,
+    AllEntrypoints,
+}
+
+impl From<MazerScoringRuleType> for ScoringRuleType {
+    fn from(rule_type: MazerScoringRuleType) -> Self {
+        match rule_type {
+            MazerScoringRuleType::Min => ScoringRuleType::Min,
+            MazerScoringRuleType::Max => ScoringRuleType::Max,
+            MazerScoringRuleType::Sum => ScoringRuleType::Sum,
+            MazerScoringRuleType::Average => ScoringRuleType::Average,
+            MazerScoringRuleType::AllEntrypoints => ScoringRuleType::AllEntrypoints,
+        }
+    }
+}
+
+impl From<ScoringRuleType> for MazerScoringRuleType {
+    fn from(rule_type: ScoringRuleType) -> Self {
+        match rule_type {
+            ScoringRuleType::Min => MazerScoringRuleType::Min,
+            ScoringRuleType::Max => MazerScoringRuleType::Max,
+            ScoringRuleType::Sum => MazerScoringRuleType::Sum,
+            ScoringRuleType::Average => MazerScoringRuleType::Average,
+            ScoringRuleType::AllEntrypoints => MazerScoringRuleType::AllEntrypoints,
+        }
+    }
+}
+
+/// Options of the generator, ranges are half-open with inclusive `start` and exclusive `end`
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct MazerGeneratorOptions {
+    col_count: usize,
+    row_count: usize,
+    entrypoint_range: Range<usize>,
+    checkpoint_range: Range<usize>,
+    exit_range: Range<usize>,
+    wall_range: Range<usize>,
+    max_soft_wall_range: Range<usize>,
+    checkpoint_padding: usize,
+    wall_padding: usize,
+}
+
+#[wasm_bindgen]
+impl MazerGeneratorOptions {
+    /// Default options of the generator registered under the id
+    #[wasm_bindgen(js_name = defaultFor)]
+    pub fn default_for(id: &str) -> Result<MazerGeneratorOptions, MazerError> {
+        set_panic_hook();
+
+        let info = get_generator_info(id)
+            .ok_or_else(|| GeneratorError::UnknownGenerator { id: id.into() })?;
+        Ok(info.default_options.into())
+    }
+
+    #[wasm_bindgen(js_name = withSize)]
+    pub fn with_size(mut self, col_count: usize, row_count: usize) -> Self {
+        self.col_count = col_count;
+        self.row_count = row_count;
+        self
+    }
+
+    #[wasm_bindgen(js_name = withEntrypointRange)]
+    pub fn with_entrypoint_range(mut self, start: usize, end: usize) -> Self {
+        self.entrypoint_range = start..end;
+        self
+    }
+
+    #[wasm_bindgen(js_name = withCheckpointRange)]
+    pub fn with_checkpoint_range(mut self, start: usize, end: usize) -> Self {
+        self.checkpoint_range = start..end;
+        self
+    }
+
+    #[wasm_bindgen(js_name = withExitRange)]
+    pub fn with_exit_range(mut self, start: usize, end: usize) -> Self {
+        self.exit_range = start..end;
+        self
+    }
+
+    #[wasm_bindgen(js_name = withWallRange)]
+    pub fn with_wall_range(mut self, start: usize, end: usize) -> Self {
+        self.wall_range = start..end;
+        self
+    }
+
+    #[wasm_bindgen(js_name = withMaxSoftWallRange)]
+    pub fn with_max_soft_wall_range(mut self, start: usize, end: usize) -> Self {
+        self.max_soft_wall_range = start..end;
+        self
+    }
+
+    #[wasm_bindgen(js_name = withPadding)]
+    pub fn with_padding(mut self, checkpoint_padding: usize, wall_padding: usize) -> Self {
+        self.checkpoint_padding = checkpoint_padding;
+        self.wall_padding = wall_padding;
+        self
+    }
+
+    #[wasm_bindgen(getter, js_name = colCount)]
+    pub fn col_count(&self) -> usize {
+        self.col_count
+    }
+
+    #[wasm_bindgen(getter, js_name = rowCount)]
+    pub fn row_count(&self) -> usize {
+        self.row_count
+    }
+}
+
+impl From<GeneratorOptions> for MazerGeneratorOptions {
+    fn from(options: GeneratorOptions) -> Self {
+        Self {
+            col_count: options.col_count,
+            row_count: options.row_count,
+            entrypoint_range: options.entrypoint_range,
+            checkpoint_range: options.checkpoint_range,
+            exit_range: options.exit_range,
+            wall_range: options.wall_range,
+            max_soft_wall_range: options.max_soft_wall_range,
+            checkpoint_padding: options.checkpoint_padding,
+            wall_padding: options.wall_padding,
+        }
+    }
+}
+
+impl From<MazerGeneratorOptions> for GeneratorOptions {
+    fn from(options: MazerGeneratorOptions) -> Self {
+        Self {
+            col_count: options.col_count,
+            row_count: options.row_count,
+            entrypoint_range: options.entrypoint_range,
+            checkpoint_range: options.checkpoint_range,
+            exit_range: options.exit_range,
+            wall_range: options.wall_range,
+            max_soft_wall_range: options.max_soft_wall_range,
+            checkpoint_padding: options.checkpoint_padding,
+            wall_padding: options.wall_padding,
+        }
+    }
+}
+
+#[wasm_bindgen]
+pub struct MazerGeneratorInfo {
+    id: String,
+    description: String,
+    default_options: MazerGeneratorOptions,
+}
+
+#[wasm_bindgen]
+impl MazerGeneratorInfo {
+    #[wasm_bindgen(getter)]
+    pub fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn description(&self) -> String {
+        self.description.clone()
+    }
+
+    #[wasm_bindgen(getter, js_name = defaultOptions)]
+    pub fn default_options(&self) -> MazerGeneratorOptions {
+        self.default_options.clone()
+    }
+}
+
+impl From<GeneratorInfo> for MazerGeneratorInfo {
+    fn from(info: GeneratorInfo) -> Self {
+        Self {
+            id: info.id,
+            description: info.description,
+            default_options: info.default_options.into(),
+        }
+    }
+}
+
+#[wasm_bindgen]
+pub enum MazerDifficultyMetric {
+    ScoreRatio,
+    UsefulWallCells,
+}
+
+impl From<MazerDifficultyMetric> for DifficultyMetric {
+    fn from(metric: MazerDifficultyMetric) -> Self {
+        match metric {
+            MazerDifficultyMetric::ScoreRatio => DifficultyMetric::ScoreRatio,
+            MazerDifficultyMetric::UsefulWallCells => DifficultyMetric::UsefulWallCells,
+        }
+    }
+}
+
+/// Band of accepted difficulty, both bounds are inclusive
+#[wasm_bindgen]
+pub struct MazerDifficultyTarget {
+    metric: DifficultyMetric,
+    min: f64,
+    max: f64,
+    max_attempts: usize,
+}
+
+#[wasm_bindgen]
+impl MazerDifficultyTarget {
+    #[wasm_bindgen]
+    pub fn new(metric: MazerDifficultyMetric, min: f64, max: f64, max_attempts: usize) -> Self {
+        set_panic_hook();
+        Self {
+            metric: metric.into(),
+            min,
+            max,
+            max_attempts,
+        }
+    }
+}
+
+impl From<MazerDifficultyTarget> for DifficultyTarget {
+    fn from(target: MazerDifficultyTarget) -> Self {
+        Self {
+            metric: target.metric,
+            min: target.min,
+            max: target.max,
+            max_attempts: target.max_attempts,
+        }
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct MazerGeneratedMaze {
+    seed: u64,
+    config: MazerConfig,
+}
+
+#[wasm_bindgen]
+impl MazerGeneratedMaze {
+    #[wasm_bindgen(getter)]
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn config(&self) -> MazerConfig {
+        self.config.clone()
+    }
+}
+
+impl From<GeneratedMaze> for MazerGeneratedMaze {
+    fn from(generated: GeneratedMaze) -> Self {
+        Self {
+            seed: generated.seed,
+            config: generated.config.into(),
+        }
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy)]
+pub enum MazerCampaignLesson {
+    FirstMaze,
+    FirstCheckpoint,
+    MultipleCheckpoints,
+    MultipleEntrypoints,
+    FirstWaterfall,
+    Practice,
+}
+
+impl From<CampaignLesson> for MazerCampaignLesson {
+    fn from(lesson: CampaignLesson) -> Self {
+        match lesson {
+            CampaignLesson::FirstMaze => MazerCampaignLesson::FirstMaze,
+            CampaignLesson::FirstCheckpoint => MazerCampaignLesson::FirstCheckpoint,
+            CampaignLesson::MultipleCheckpoints => MazerCampaignLesson::MultipleCheckpoints,
+            CampaignLesson::MultipleEntrypoints => MazerCampaignLesson::MultipleEntrypoints,
+            CampaignLesson::FirstWaterfall => MazerCampaignLesson::FirstWaterfall,
+            CampaignLesson::Practice => MazerCampaignLesson::Practice,
+        }
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct MazerCampaignLevel {
+    lesson: MazerCampaignLesson,
+    description: String,
+    generator_id: String,
+    difficulty: f64,
+    maze: MazerGeneratedMaze,
+}
+
+#[wasm_bindgen]
+impl MazerCampaignLevel {
+    #[wasm_bindgen(getter)]
+    pub fn lesson(&self) -> MazerCampaignLesson {
+        self.lesson
+    }
+
+    /// What the level is meant to teach
+    #[wasm_bindgen(getter)]
+    pub fn description(&self) -> String {
+        self.description.clone()
+    }
+
+    #[wasm_bindgen(getter, js_name = generatorId)]
+    pub fn generator_id(&self) -> String {
+        self.generator_id.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn difficulty(&self) -> f64 {
+        self.difficulty
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn maze(&self) -> MazerGeneratedMaze {
+        self.maze.clone()
+    }
+}
+
+impl From<CampaignLevel> for MazerCampaignLevel {
+    fn from(level: CampaignLevel) -> Self {
+        Self {
+            lesson: level.lesson.into(),
+            description: level.lesson.description().into(),
+            generator_id: level.generator_type.id().into(),
+            difficulty: level.difficulty,
+            maze: level.maze.into(),
+        }
+    }
+}
+
+#[wasm_bindgen]
+pub struct MazerCampaign {
+    seed: u64,
+    levels: Vec<MazerCampaignLevel>,
+}
+
+#[wasm_bindgen]
+impl MazerCampaign {
+    #[wasm_bindgen(getter)]
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn levels(&self) -> Vec<MazerCampaignLevel> {
+        self.levels.clone()
+    }
+}
+
+impl From<Campaign> for MazerCampaign {
+    fn from(campaign: Campaign) -> Self {
+        Self {
+            seed: campaign.seed,
+            levels: campaign
+                .levels
+                .into_iter()
+                .map(|level| level.into())
+                .collect(),
         }
     }
 }